@@ -0,0 +1,569 @@
+//! src/adapters/incoming/protocol/schemas 의 Kafka 메시지 JSON 스키마로부터
+//! 버전별 인코딩/디코딩이 들어간 Rust 구조체를 생성함 ($OUT_DIR/messages.rs)
+
+use serde_json::Value;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+const SCHEMA_DIR: &str = "src/adapters/incoming/protocol/schemas";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", SCHEMA_DIR);
+
+    let mut paths: Vec<_> = fs::read_dir(SCHEMA_DIR)
+        .expect("schema directory")
+        .map(|entry| entry.expect("schema entry").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    let mut out = String::new();
+    for path in paths {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let text = fs::read_to_string(&path).expect("read schema");
+        let schema: Value = serde_json::from_str(&strip_comments(&text))
+            .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        Message::parse(&schema).generate(&mut out);
+    }
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR");
+    fs::write(Path::new(&out_dir).join("messages.rs"), out).expect("write generated messages");
+}
+
+/// Kafka 스키마 파일은 `//` 주석 줄을 포함함
+fn strip_comments(text: &str) -> String {
+    text.lines()
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// "0+", "3-5", "7", "none" 형태의 버전 범위. min > max 면 빈 범위
+#[derive(Clone, Copy)]
+struct Versions {
+    min: i16,
+    max: i16,
+}
+
+impl Versions {
+    const NONE: Versions = Versions { min: 1, max: 0 };
+
+    fn parse(text: &str) -> Self {
+        let text = text.trim();
+        if text == "none" {
+            Self::NONE
+        } else if let Some(min) = text.strip_suffix('+') {
+            Versions { min: min.parse().expect("version"), max: i16::MAX }
+        } else if let Some((min, max)) = text.split_once('-') {
+            Versions { min: min.parse().expect("version"), max: max.parse().expect("version") }
+        } else {
+            let version = text.parse().expect("version");
+            Versions { min: version, max: version }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.min > self.max
+    }
+
+    /// `version` 이 이 범위에 들어가는지 검사하는 Rust 식
+    /// 메시지가 유효한 범위 (`valid`) 전체를 덮으면 true 로 단순화
+    fn condition(&self, valid: Versions) -> String {
+        if self.is_empty() {
+            return "false".to_string();
+        }
+        let lower = self.min > valid.min;
+        let upper = self.max < valid.max;
+        match (lower, upper) {
+            (false, false) => "true".to_string(),
+            (true, false) => format!("version >= {}", self.min),
+            (false, true) => format!("version <= {}", self.max),
+            (true, true) if self.min == self.max => format!("version == {}", self.min),
+            (true, true) => format!("({}..={}).contains(&version)", self.min, self.max),
+        }
+    }
+}
+
+#[derive(Clone)]
+enum FieldType {
+    Bool,
+    Int8,
+    Int16,
+    Uint16,
+    Int32,
+    Uint32,
+    Int64,
+    Float64,
+    String,
+    Bytes,
+    Records,
+    Uuid,
+    Array(Box<FieldType>),
+    Struct(String),
+}
+
+impl FieldType {
+    fn parse(text: &str) -> Self {
+        if let Some(element) = text.strip_prefix("[]") {
+            return FieldType::Array(Box::new(FieldType::parse(element)));
+        }
+        match text {
+            "bool" => FieldType::Bool,
+            "int8" => FieldType::Int8,
+            "int16" => FieldType::Int16,
+            "uint16" => FieldType::Uint16,
+            "int32" => FieldType::Int32,
+            "uint32" => FieldType::Uint32,
+            "int64" => FieldType::Int64,
+            "float64" => FieldType::Float64,
+            "string" => FieldType::String,
+            "bytes" => FieldType::Bytes,
+            "records" => FieldType::Records,
+            "uuid" => FieldType::Uuid,
+            name => FieldType::Struct(name.to_string()),
+        }
+    }
+
+    fn rust_type(&self) -> String {
+        match self {
+            FieldType::Bool => "bool".to_string(),
+            FieldType::Int8 => "i8".to_string(),
+            FieldType::Int16 => "i16".to_string(),
+            FieldType::Uint16 => "u16".to_string(),
+            FieldType::Int32 => "i32".to_string(),
+            FieldType::Uint32 => "u32".to_string(),
+            FieldType::Int64 => "i64".to_string(),
+            FieldType::Float64 => "f64".to_string(),
+            FieldType::String => "String".to_string(),
+            FieldType::Bytes | FieldType::Records => "Vec<u8>".to_string(),
+            FieldType::Uuid => "[u8; 16]".to_string(),
+            FieldType::Array(element) => format!("Vec<{}>", element.rust_type()),
+            FieldType::Struct(name) => name.clone(),
+        }
+    }
+
+    fn primitive_name(&self) -> Option<&'static str> {
+        match self {
+            FieldType::Int8 => Some("i8"),
+            FieldType::Int16 => Some("i16"),
+            FieldType::Uint16 => Some("u16"),
+            FieldType::Int32 => Some("i32"),
+            FieldType::Uint32 => Some("u32"),
+            FieldType::Int64 => Some("i64"),
+            FieldType::Float64 => Some("f64"),
+            _ => None,
+        }
+    }
+
+    /// 스키마의 default 값 (없으면 타입의 0 값) 에 해당하는 Rust 식
+    fn default_value(&self, default: Option<&str>) -> String {
+        match self {
+            FieldType::Bool => default.unwrap_or("false").to_string(),
+            FieldType::Float64 => match default {
+                Some(value) if value.contains('.') => value.to_string(),
+                Some(value) => format!("{}.0", value),
+                None => "0.0".to_string(),
+            },
+            FieldType::String => match default {
+                Some(value) if !value.is_empty() => format!("String::from({:?})", value),
+                _ => "String::new()".to_string(),
+            },
+            FieldType::Bytes | FieldType::Records | FieldType::Array(_) => "Vec::new()".to_string(),
+            FieldType::Uuid => "[0; 16]".to_string(),
+            FieldType::Struct(name) => format!("{}::default()", name),
+            _ => default.unwrap_or("0").to_string(),
+        }
+    }
+}
+
+struct Field {
+    name: String,
+    rust_name: String,
+    field_type: FieldType,
+    versions: Versions,
+    nullable: bool,
+    tag: Option<u32>,
+    tagged_versions: Versions,
+    flexible_versions: Option<Versions>,
+    default: Option<String>,
+    about: Option<String>,
+}
+
+impl Field {
+    fn parse(value: &Value, structs: &mut Vec<Struct>) -> Self {
+        let name = str_attr(value, "name").expect("field name").to_string();
+        let type_name = str_attr(value, "type").expect("field type");
+        let field_type = FieldType::parse(type_name);
+
+        // 필드 안에 정의된 구조체
+        if let Some(fields) = value.get("fields").and_then(Value::as_array) {
+            let struct_name = type_name.trim_start_matches("[]").to_string();
+            Struct::parse(struct_name, str_attr(value, "about"), fields, structs);
+        }
+
+        let default = match value.get("default") {
+            Some(Value::String(text)) => Some(text.clone()),
+            Some(other) => Some(other.to_string()),
+            None => None,
+        };
+
+        Field {
+            rust_name: snake_case(&name),
+            name,
+            field_type,
+            versions: Versions::parse(str_attr(value, "versions").expect("field versions")),
+            nullable: str_attr(value, "nullableVersions").is_some_and(|v| !Versions::parse(v).is_empty()),
+            tag: value.get("tag").and_then(Value::as_u64).map(|tag| tag as u32),
+            tagged_versions: str_attr(value, "taggedVersions").map_or(Versions::NONE, Versions::parse),
+            flexible_versions: str_attr(value, "flexibleVersions").map(Versions::parse),
+            default,
+            about: str_attr(value, "about").map(str::to_string),
+        }
+    }
+
+    fn rust_type(&self) -> String {
+        if self.nullable {
+            format!("Option<{}>", self.field_type.rust_type())
+        } else {
+            self.field_type.rust_type()
+        }
+    }
+
+    fn default_value(&self) -> String {
+        let default = self.default.as_deref();
+        if !self.nullable {
+            return self.field_type.default_value(default);
+        }
+        // nullable 필드는 default 가 "null" 이거나 records 면 None
+        match (&self.field_type, default) {
+            (_, Some("null")) | (FieldType::Records, None) => "None".to_string(),
+            (field_type, default) => format!("Some({})", field_type.default_value(default)),
+        }
+    }
+
+    /// tagged 가 아닌 위치에 인코딩되는 버전 범위
+    fn inline_versions(&self) -> Versions {
+        if self.tagged_versions.is_empty() {
+            self.versions
+        } else {
+            Versions { min: self.versions.min, max: self.versions.max.min(self.tagged_versions.min - 1) }
+        }
+    }
+
+    fn flexible(&self, valid: Versions) -> String {
+        match self.flexible_versions {
+            Some(versions) => versions.condition(valid),
+            None => "flexible".to_string(),
+        }
+    }
+
+    /// 필드 값을 읽어서 `Result<필드 타입>` 을 만드는 식
+    fn read_expr(&self, valid: Versions) -> String {
+        let flexible = self.flexible(valid);
+        match &self.field_type {
+            FieldType::Struct(name) if self.nullable => {
+                format!("codec::read_nullable_struct(buf, |buf| {}::decode(buf, version))", name)
+            }
+            FieldType::Struct(name) => format!("{}::decode(buf, version)", name),
+            FieldType::String | FieldType::Bytes | FieldType::Records | FieldType::Array(_) => {
+                let read = match &self.field_type {
+                    FieldType::String => format!("codec::read_string(buf, {})", flexible),
+                    FieldType::Array(element) => {
+                        format!("codec::read_array(buf, {}, {})", flexible, self.read_element(element, &flexible))
+                    }
+                    _ => format!("codec::read_bytes(buf, {})", flexible),
+                };
+                if self.nullable {
+                    read
+                } else {
+                    format!("{}.and_then(|value| codec::non_null(value, {:?}))", read, self.name)
+                }
+            }
+            field_type => read_primitive(field_type),
+        }
+    }
+
+    fn read_element(&self, element: &FieldType, flexible: &str) -> String {
+        match element {
+            FieldType::Struct(name) => format!("|buf| {}::decode(buf, version)", name),
+            FieldType::String => format!(
+                "|buf| codec::read_string(buf, {}).and_then(|value| codec::non_null(value, {:?}))",
+                flexible, self.name
+            ),
+            element => format!("|buf| {}", read_primitive(element)),
+        }
+    }
+
+    /// `value` (필드 타입의 참조가 아닌 값 식) 를 buf 에 쓰는 문장
+    fn write_stmt(&self, valid: Versions) -> String {
+        let flexible = self.flexible(valid);
+        let value = format!("self.{}", self.rust_name);
+        match &self.field_type {
+            FieldType::Struct(_) if self.nullable => format!(
+                "codec::write_nullable_struct(buf, {}.as_ref(), |buf, value| value.encode(buf, version));",
+                value
+            ),
+            FieldType::Struct(_) => format!("{}.encode(buf, version);", value),
+            FieldType::String | FieldType::Bytes | FieldType::Records | FieldType::Array(_) => {
+                let value = if self.nullable {
+                    format!("{}.as_deref()", value)
+                } else if let FieldType::String = self.field_type {
+                    format!("Some({}.as_str())", value)
+                } else {
+                    format!("Some({}.as_slice())", value)
+                };
+                match &self.field_type {
+                    FieldType::String => format!("codec::write_string(buf, {}, {});", value, flexible),
+                    FieldType::Array(element) => format!(
+                        "codec::write_array(buf, {}, {}, |buf, value| {});",
+                        value,
+                        flexible,
+                        write_element(element, &flexible)
+                    ),
+                    _ => format!("codec::write_bytes(buf, {}, {});", value, flexible),
+                }
+            }
+            field_type => format!("{};", write_primitive(field_type, &value)),
+        }
+    }
+}
+
+fn read_primitive(field_type: &FieldType) -> String {
+    match field_type {
+        FieldType::Bool => "codec::read_bool(buf)".to_string(),
+        FieldType::Uuid => "codec::read_uuid(buf)".to_string(),
+        field_type => format!("codec::read_{}(buf)", field_type.primitive_name().expect("primitive type")),
+    }
+}
+
+fn write_primitive(field_type: &FieldType, value: &str) -> String {
+    match field_type {
+        FieldType::Bool => format!("buf.put_u8({} as u8)", value),
+        FieldType::Uuid => format!("buf.put_slice(&{})", value),
+        field_type => format!("buf.put_{}({})", field_type.primitive_name().expect("primitive type"), value),
+    }
+}
+
+/// 배열 원소 (`value: &T`) 를 쓰는 식
+fn write_element(element: &FieldType, flexible: &str) -> String {
+    match element {
+        FieldType::Struct(_) => "value.encode(buf, version)".to_string(),
+        FieldType::String => format!("codec::write_string(buf, Some(value), {})", flexible),
+        FieldType::Uuid => "buf.put_slice(value)".to_string(),
+        element => write_primitive(element, "*value"),
+    }
+}
+
+struct Struct {
+    name: String,
+    about: Option<String>,
+    fields: Vec<Field>,
+}
+
+impl Struct {
+    /// 중첩된 구조체까지 모두 `structs` 에 추가 (바깥 구조체가 먼저)
+    fn parse(name: String, about: Option<&str>, fields: &[Value], structs: &mut Vec<Struct>) {
+        let index = structs.len();
+        structs.push(Struct { name, about: about.map(str::to_string), fields: Vec::new() });
+        let fields = fields.iter().map(|field| Field::parse(field, structs)).collect();
+        structs[index].fields = fields;
+    }
+
+    fn generate(&self, out: &mut String, valid: Versions) {
+        if let Some(about) = &self.about {
+            writeln!(out, "    /// {}", about).unwrap();
+        }
+        writeln!(out, "    #[derive(Debug, Clone, PartialEq)]").unwrap();
+        writeln!(out, "    pub struct {} {{", self.name).unwrap();
+        for field in &self.fields {
+            if let Some(about) = &field.about {
+                writeln!(out, "        /// {}", about).unwrap();
+            }
+            writeln!(out, "        pub {}: {},", field.rust_name, field.rust_type()).unwrap();
+        }
+        writeln!(out, "    }}\n").unwrap();
+
+        writeln!(out, "    impl Default for {} {{", self.name).unwrap();
+        writeln!(out, "        fn default() -> Self {{").unwrap();
+        writeln!(out, "            Self {{").unwrap();
+        for field in &self.fields {
+            writeln!(out, "                {}: {},", field.rust_name, field.default_value()).unwrap();
+        }
+        writeln!(out, "            }}\n        }}\n    }}\n").unwrap();
+
+        let mut tagged: Vec<&Field> = self.fields.iter()
+            .filter(|field| field.tag.is_some() && !field.tagged_versions.is_empty())
+            .collect();
+        tagged.sort_by_key(|field| field.tag);
+
+        self.generate_decode(out, valid, &tagged);
+        self.generate_encode(out, valid, &tagged);
+    }
+
+    fn generate_decode(&self, out: &mut String, valid: Versions, tagged: &[&Field]) {
+        writeln!(out, "    impl Decodable for {} {{", self.name).unwrap();
+        writeln!(out, "        fn decode(buf: &mut Bytes, version: i16) -> Result<Self, ApplicationError> {{").unwrap();
+        writeln!(out, "            let flexible = is_flexible(version);").unwrap();
+        writeln!(out, "            let mut this = Self::default();").unwrap();
+        for field in &self.fields {
+            let condition = field.inline_versions().condition(valid);
+            let read = format!("this.{} = {}?;", field.rust_name, field.read_expr(valid));
+            match condition.as_str() {
+                "false" => {}
+                "true" => writeln!(out, "            {}", read).unwrap(),
+                condition => writeln!(out, "            if {} {{\n                {}\n            }}", condition, read).unwrap(),
+            }
+        }
+        writeln!(out, "            if flexible {{").unwrap();
+        if tagged.is_empty() {
+            writeln!(out, "                codec::skip_tagged_fields(buf)?;").unwrap();
+        } else {
+            writeln!(out, "                for _ in 0..codec::read_unsigned_varint(buf)? {{").unwrap();
+            writeln!(out, "                    let tag = codec::read_unsigned_varint(buf)?;").unwrap();
+            writeln!(out, "                    let mut field = codec::read_tagged_field(buf)?;").unwrap();
+            writeln!(out, "                    let buf = &mut field;").unwrap();
+            writeln!(out, "                    match tag {{").unwrap();
+            for field in tagged {
+                writeln!(
+                    out,
+                    "                        {} if {} => this.{} = {}?,",
+                    field.tag.unwrap(),
+                    field.tagged_versions.condition(valid),
+                    field.rust_name,
+                    field.read_expr(valid)
+                )
+                .unwrap();
+            }
+            writeln!(out, "                        _ => {{}}").unwrap();
+            writeln!(out, "                    }}\n                }}").unwrap();
+        }
+        writeln!(out, "            }}").unwrap();
+        writeln!(out, "            Ok(this)\n        }}\n    }}\n").unwrap();
+    }
+
+    fn generate_encode(&self, out: &mut String, valid: Versions, tagged: &[&Field]) {
+        writeln!(out, "    impl Encodable for {} {{", self.name).unwrap();
+        writeln!(out, "        fn encode(&self, buf: &mut BytesMut, version: i16) {{").unwrap();
+        writeln!(out, "            let flexible = is_flexible(version);").unwrap();
+        for field in &self.fields {
+            let condition = field.inline_versions().condition(valid);
+            let write = field.write_stmt(valid);
+            match condition.as_str() {
+                "false" => {}
+                "true" => writeln!(out, "            {}", write).unwrap(),
+                condition => writeln!(out, "            if {} {{\n                {}\n            }}", condition, write).unwrap(),
+            }
+        }
+        writeln!(out, "            if flexible {{").unwrap();
+        if tagged.is_empty() {
+            writeln!(out, "                codec::write_unsigned_varint(buf, 0);").unwrap();
+        } else {
+            // 기본값인 tagged field 는 생략
+            writeln!(out, "                let mut tagged_fields = Vec::new();").unwrap();
+            for field in tagged {
+                writeln!(
+                    out,
+                    "                if {} && self.{} != {} {{",
+                    field.tagged_versions.condition(valid),
+                    field.rust_name,
+                    field.default_value()
+                )
+                .unwrap();
+                writeln!(out, "                    let mut field = BytesMut::new();").unwrap();
+                writeln!(out, "                    {{\n                        let buf = &mut field;").unwrap();
+                writeln!(out, "                        {}\n                    }}", field.write_stmt(valid)).unwrap();
+                writeln!(out, "                    tagged_fields.push(({}, field));\n                }}", field.tag.unwrap()).unwrap();
+            }
+            writeln!(out, "                codec::write_tagged_fields(buf, &tagged_fields);").unwrap();
+        }
+        writeln!(out, "            }}\n        }}\n    }}\n").unwrap();
+    }
+}
+
+struct Message {
+    name: String,
+    api_key: Option<i64>,
+    valid_versions: Versions,
+    flexible_versions: Versions,
+    structs: Vec<Struct>,
+}
+
+impl Message {
+    fn parse(schema: &Value) -> Self {
+        let name = str_attr(schema, "name").expect("message name").to_string();
+        let mut structs = Vec::new();
+
+        let fields = schema.get("fields").and_then(Value::as_array).cloned().unwrap_or_default();
+        Struct::parse(format!("{}Data", name), None, &fields, &mut structs);
+        for common in schema.get("commonStructs").and_then(Value::as_array).into_iter().flatten() {
+            let fields = common.get("fields").and_then(Value::as_array).cloned().unwrap_or_default();
+            let common_name = str_attr(common, "name").expect("common struct name").to_string();
+            Struct::parse(common_name, None, &fields, &mut structs);
+        }
+
+        Message {
+            name,
+            api_key: schema.get("apiKey").and_then(Value::as_i64),
+            valid_versions: Versions::parse(str_attr(schema, "validVersions").expect("validVersions")),
+            flexible_versions: Versions::parse(str_attr(schema, "flexibleVersions").expect("flexibleVersions")),
+            structs,
+        }
+    }
+
+    fn generate(&self, out: &mut String) {
+        let valid = self.valid_versions;
+        writeln!(out, "pub mod {} {{", snake_case(&self.name)).unwrap();
+        writeln!(out, "    use crate::adapters::incoming::protocol::codec::{{self, ApiMessage, Decodable, Encodable}};").unwrap();
+        writeln!(out, "    use crate::application::error::ApplicationError;").unwrap();
+        writeln!(out, "    use bytes::{{BufMut, Bytes, BytesMut}};\n").unwrap();
+
+        writeln!(out, "    pub const LOWEST_SUPPORTED_VERSION: i16 = {};", valid.min).unwrap();
+        writeln!(out, "    pub const HIGHEST_SUPPORTED_VERSION: i16 = {};\n", valid.max).unwrap();
+        writeln!(out, "    pub fn is_flexible(version: i16) -> bool {{").unwrap();
+        let flexible = match self.flexible_versions {
+            versions if versions.is_empty() => "false".to_string(),
+            versions => format!("version >= {}", versions.min),
+        };
+        writeln!(out, "        {}\n    }}\n", flexible).unwrap();
+
+        for item in &self.structs {
+            item.generate(out, valid);
+        }
+
+        if let Some(api_key) = self.api_key {
+            writeln!(out, "    impl ApiMessage for {}Data {{", self.name).unwrap();
+            writeln!(out, "        const API_KEY: i16 = {};", api_key).unwrap();
+            writeln!(out, "        const LOWEST_SUPPORTED_VERSION: i16 = LOWEST_SUPPORTED_VERSION;").unwrap();
+            writeln!(out, "        const HIGHEST_SUPPORTED_VERSION: i16 = HIGHEST_SUPPORTED_VERSION;\n").unwrap();
+            writeln!(out, "        fn is_flexible(version: i16) -> bool {{\n            is_flexible(version)\n        }}").unwrap();
+            writeln!(out, "    }}").unwrap();
+        }
+        writeln!(out, "}}\n").unwrap();
+    }
+}
+
+fn str_attr<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+/// "ThrottleTimeMs" -> "throttle_time_ms"
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev_lower = chars[i - 1].is_lowercase() || chars[i - 1].is_ascii_digit();
+            let next_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if prev_lower || (chars[i - 1].is_uppercase() && next_lower) {
+                out.push('_');
+            }
+        }
+        out.extend(c.to_lowercase());
+    }
+    match out.as_str() {
+        "type" | "match" | "ref" | "self" | "mod" | "move" | "static" | "async" => format!("r#{}", out),
+        _ => out,
+    }
+}
@@ -0,0 +1,9 @@
+#![no_main]
+
+use kafka_starter::adapters::incoming::kafka_protocol_parser::KafkaProtocolParser;
+use libfuzzer_sys::fuzz_target;
+
+// 크기 prefix 를 뗀 요청 바이트. 어떤 입력이든 Ok / Err 로 끝나야 하고 panic 하면 안 됨
+fuzz_target!(|data: &[u8]| {
+    let _ = KafkaProtocolParser::new().parse_request(data);
+});
@@ -0,0 +1,29 @@
+#![no_main]
+
+use kafka_starter::adapters::outgoing::log::batch;
+use kafka_starter::adapters::outgoing::protocol::legacy_message;
+use kafka_starter::adapters::outgoing::protocol::record_batch::RecordBatch;
+use libfuzzer_sys::fuzz_target;
+
+// Produce 로 들어온 records 를 그대로 로그에 붙이기 전에 거치는 경로들
+fuzz_target!(|data: &[u8]| {
+    for (start, header) in batch::batches(data) {
+        let _ = batch::find_record_by_timestamp(&data[start..start + header.size()], 0);
+        let _ = header.next_offset();
+    }
+    let _ = batch::find_record_by_timestamp(data, i64::MIN);
+
+    let mut bytes = bytes::Bytes::copy_from_slice(data);
+    while let Ok(parsed) = RecordBatch::from_bytes(&mut bytes) {
+        let _ = parsed.next_offset();
+        let _ = parsed.to_bytes();
+    }
+
+    if legacy_message::is_legacy_message_set(data) {
+        let _ = legacy_message::up_convert(data);
+    }
+    let _ = legacy_message::down_convert(data, 0, usize::MAX);
+
+    let mut records = data.to_vec();
+    let _ = batch::assign_offsets(&mut records, 0);
+});
@@ -1,32 +1,36 @@
+use crate::adapters::incoming::protocol::codec::{self, ApiMessage, Decodable, Encodable};
 use crate::adapters::incoming::protocol::constants::{
-    API_VERSIONS_KEY, DESCRIBE_TOPIC_PARTITIONS_KEY, FETCH_KEY, MAX_SUPPORTED_VERSION,
-    UNKNOWN_TOPIC_OR_PARTITION,
+    API_VERSIONS_KEY, CREATE_PARTITIONS_KEY, CREATE_TOPICS_KEY, DEFAULT_TOPIC_AUTHORIZED_OPERATIONS, DELETE_TOPICS_KEY,
+    DESCRIBE_TOPIC_PARTITIONS_KEY, FETCH_KEY, LIST_OFFSETS_KEY, METADATA_KEY, PRODUCE_KEY,
 };
 use crate::adapters::incoming::protocol::messages::{
-    ApiVersion, ApiVersionsResponse, DescribeTopicPartitionsRequest, DescribeTopicPartitionsResponse,
-    FetchPartition, FetchRequest, FetchTopic, KafkaRequest, KafkaResponse, PartitionInfo,
-    RequestHeader, RequestPayload, ResponsePayload, TopicRequest, TopicResponse,
+    ApiVersionsResponse, CreatableReplicaAssignment, CreatableTopic, CreatableTopicConfig, CreatePartitionsRequest,
+    CreatePartitionsResponse, CreatePartitionsTopic, CreateTopicsRequest,
+    CreateTopicsResponse, DeleteTopicState, DeleteTopicsRequest, DeleteTopicsResponse, DescribeTopicPartitionsRequest, DescribeTopicPartitionsResponse, FetchPartition,
+    FetchRequest, FetchResponse, FetchTopic, ForgottenTopic, KafkaRequest, KafkaResponse, ListOffsetsPartition,
+    ListOffsetsRequest, ListOffsetsResponse, ListOffsetsTopic, MetadataRequest, MetadataRequestTopic,
+    MetadataResponse, ProducePartition, ProduceRequest, ProduceResponse, ProduceTopic, RequestHeader,
+    RequestPayload, ResponsePayload, TopicRequest,
+};
+use crate::adapters::incoming::protocol::schema::{
+    api_versions_request, api_versions_response, create_partitions_request, create_partitions_response, create_topics_request, create_topics_response,
+    delete_topics_request, delete_topics_response,
+    describe_topic_partitions_request, describe_topic_partitions_response,
+    fetch_request, fetch_response, list_offsets_request, list_offsets_response, metadata_request,
+    metadata_response, produce_request, produce_response, request_header, response_header,
 };
 use crate::application::error::ApplicationError;
-use bytes::{Buf, BufMut, Bytes, BytesMut};
+use bytes::{BufMut, Bytes, BytesMut};
 
-pub trait PutVarint {
-    fn put_uvarint(&mut self, num: i64);
-}
+#[derive(Clone)]
+pub struct KafkaProtocolParser;
 
-impl PutVarint for Vec<u8> {
-    fn put_uvarint(&mut self, mut num: i64) {
-        while (num & !0x7F) != 0 {
-            self.push(((num & 0x7F) | 0x80) as u8);
-            num >>= 7;
-        }
-        self.push(num as u8);
+impl Default for KafkaProtocolParser {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-#[derive(Clone)]
-pub struct KafkaProtocolParser;
-
 impl KafkaProtocolParser {
     pub fn new() -> Self {
         Self
@@ -35,404 +39,600 @@ impl KafkaProtocolParser {
     pub fn parse_request(&self, data: &[u8]) -> Result<KafkaRequest, ApplicationError> {
         println!("[REQUEST] Raw bytes: {:02x?}", data);
         let mut buf = Bytes::copy_from_slice(data);
-        
-        // API Key (2 bytes)
-        let api_key = buf.get_u16() as i16;
-        println!("[REQUEST] API Key: {}", api_key);
-        
-        // API Version (2 bytes)
-        let api_version = buf.get_u16() as i16;
-        println!("[REQUEST] API Version: {}", api_version);
-        
-        // Correlation ID (4 bytes)
-        let correlation_id = buf.get_u32() as i32;
-        println!("[REQUEST] Correlation ID: {}", correlation_id);
-        
-        // Client ID
-        let client_id_len = buf.get_u16() as usize;
-        println!("[REQUEST] Client ID length: {}", client_id_len);
-        let client_id = if client_id_len > 0 {
-            let client_id_bytes = buf.copy_to_bytes(client_id_len);
-            let client_id = String::from_utf8(client_id_bytes.to_vec())
-                .map_err(|_| ApplicationError::Protocol("Invalid client ID encoding".to_string()))?;
-            println!("[REQUEST] Client ID: {}", client_id);
-            Some(client_id)
-        } else {
-            println!("[REQUEST] Client ID: None");
-            None
-        };
-        
-        buf.get_u8(); // tag buffer
-        println!("[REQUEST] Remaining bytes after header: {:02x?}", buf);
-        
+
+        // 헤더 버전을 정하려면 API Key 와 API Version 을 먼저 알아야 함
+        let mut peek = buf.clone();
+        let api_key = codec::read_i16(&mut peek)?;
+        let api_version = codec::read_i16(&mut peek)?;
+        let header_version = request_header_version(api_key, api_version);
+
+        let header = request_header::RequestHeaderData::decode(&mut buf, header_version)?;
+        println!("[REQUEST] API Key: {}, API Version: {}, Correlation ID: {}, Client ID: {:?}",
+            api_key, api_version, header.correlation_id, header.client_id);
+
         let header = RequestHeader {
             api_key,
             api_version,
-            correlation_id,
-            client_id,
+            correlation_id: header.correlation_id,
+            // 빈 client_id 는 없는 것으로 취급
+            client_id: header.client_id.filter(|client_id| !client_id.is_empty()),
         };
-        
+
         let payload = match api_key {
+            // 클라이언트가 지원 버전을 모르는 상태에서 보내므로 body 는 해석하지 않음
             API_VERSIONS_KEY => RequestPayload::ApiVersions,
-            FETCH_KEY => {
-                // max_wait_ms
-                let max_wait_ms = buf.get_i32();
-                println!("[REQUEST] Max Wait MS: {}", max_wait_ms);
-                
-                // min_bytes
-                let min_bytes = buf.get_i32();
-                println!("[REQUEST] Min Bytes: {}", min_bytes);
-                
-                // max_bytes
-                let max_bytes = buf.get_i32();
-                println!("[REQUEST] Max Bytes: {}", max_bytes);
-                
-                // isolation_level
-                let isolation_level = buf.get_i8();
-                println!("[REQUEST] Isolation Level: {}", isolation_level);
-                
-                // session_id
-                let session_id = buf.get_i32();
-                println!("[REQUEST] Session ID: {}", session_id);
-                
-                // session_epoch
-                let session_epoch = buf.get_i32();
-                println!("[REQUEST] Session Epoch: {}", session_epoch);
-                
-                // topics array length (COMPACT_ARRAY)
-                let topics_length = buf.get_i8() - 1;
-                println!("[REQUEST] Topics Length: {}", topics_length);
-                
-                let mut topics = Vec::new();
-                for _ in 0..topics_length {
-                    // topic_id
-                    let mut topic_id = [0u8; 16];
-                    topic_id.copy_from_slice(&buf.copy_to_bytes(16));
-                    println!("[REQUEST] Topic ID: {:02x?}", topic_id);
-                    
-                    // partitions array length (COMPACT_ARRAY)
-                    let partitions_length = buf.get_i8() - 1;
-                    println!("[REQUEST] Partitions Length: {}", partitions_length);
-                    
-                    let mut partitions = Vec::new();
-                    for _ in 0..partitions_length {
-                        let partition = buf.get_i32();
-                        let current_leader_epoch = buf.get_i32();
-                        let fetch_offset = buf.get_i64();
-                        let last_fetched_epoch = buf.get_i32();
-                        let log_start_offset = buf.get_i64();
-                        let partition_max_bytes = buf.get_i32();
-                        
-                        partitions.push(FetchPartition {
-                            partition,
-                            current_leader_epoch,
-                            fetch_offset,
-                            last_fetched_epoch,
-                            log_start_offset,
-                            partition_max_bytes,
-                        });
-                    }
-                    
-                    buf.get_u8(); // TAG_BUFFER
-                    
-                    topics.push(FetchTopic {
-                        topic_id,
-                        partitions,
-                    });
-                }
-                
-                RequestPayload::Fetch(FetchRequest {
-                    max_wait_ms,
-                    min_bytes,
-                    max_bytes,
-                    isolation_level,
-                    session_id,
-                    session_epoch,
-                    topics,
-                })
+            FETCH_KEY if has_schema_version::<fetch_request::FetchRequestData>(api_version) => {
+                RequestPayload::Fetch(parse_fetch_request(&mut buf, api_version)?)
             },
-            DESCRIBE_TOPIC_PARTITIONS_KEY => {
-                let mut array_length_buf = [0u8; 8];
-                let mut pos = 0;
-                
-                loop {
-                    if pos >= buf.len() {
-                        return Err(ApplicationError::Protocol("Buffer too short for array length".to_string()));
-                    }
-                    let byte = buf.get_u8();
-                    array_length_buf[pos] = byte;
-                    pos += 1;
-                    println!("[REQUEST] Array length byte {}: {:02x}", pos, byte);
-                    
-                    if byte & 0x80 == 0 {
-                        break;
-                    }
-                }
-                
-                let mut topics = Vec::new();
-                let array_length = decode_varint(&array_length_buf[..pos]) - 1;
-                println!("[REQUEST] Topics array length (decoded): {}", array_length);
-                
-                for _ in 0..array_length {
-                    let mut name_length_buf = [0u8; 8];
-                    let mut pos = 0;
-                    
-                    loop {
-                        if pos >= buf.len() {
-                            return Err(ApplicationError::Protocol("Buffer too short for name length".to_string()));
-                        }
-                        let byte = buf.get_u8();
-                        name_length_buf[pos] = byte;
-                        pos += 1;
-                        println!("[REQUEST] Name length byte {}: {:02x}", pos, byte);
-                        
-                        if byte & 0x80 == 0 {
-                            break;
-                        }
-                    }
-                    
-                    let name_length = decode_varint(&name_length_buf[..pos]) - 1;
-                    println!("[REQUEST] Name length (decoded): {}", name_length);
-                    
-                    let mut topic_name_buf = vec![0u8; name_length as usize];
-                    if name_length as usize > buf.len() {
-                        println!("[REQUEST] Error: name_length ({}) > remaining buffer length ({})", name_length, buf.len());
-                        return Err(ApplicationError::Protocol("Buffer too short for topic name".to_string()));
-                    }
-                    let bytes_to_copy = buf.copy_to_bytes(name_length as usize);
-                    println!("[REQUEST] Bytes to copy: {:02x?}", bytes_to_copy);
-                    topic_name_buf.copy_from_slice(&bytes_to_copy);
-                    
-                    let topic_name = String::from_utf8(topic_name_buf)
-                        .map_err(|_| ApplicationError::Protocol("Invalid topic name encoding".to_string()))?;
-                    println!("[REQUEST] Topic name: {}", topic_name);
-                    
-                    buf.get_u8(); // tag buffer
-                    
-                    topics.push(TopicRequest {
-                        topic_name,
-                        partitions: vec![],
-                    });
-                }
-                
-                let response_partition_limit = buf.get_u32();
-                println!("[REQUEST] Response partition limit: {}", response_partition_limit);
-                buf.get_u8(); // cursor
-                buf.get_u8(); // tag buffer
-                
-                RequestPayload::DescribeTopicPartitions(DescribeTopicPartitionsRequest {
-                    topics,
-                })
-            }
-            _ => return Err(ApplicationError::Protocol("Invalid API key".to_string())),
+            DESCRIBE_TOPIC_PARTITIONS_KEY if has_schema_version::<describe_topic_partitions_request::DescribeTopicPartitionsRequestData>(api_version) => {
+                RequestPayload::DescribeTopicPartitions(parse_describe_topic_partitions_request(&mut buf, api_version)?)
+            },
+            PRODUCE_KEY if has_schema_version::<produce_request::ProduceRequestData>(api_version) => {
+                RequestPayload::Produce(parse_produce_request(&mut buf, api_version)?)
+            },
+            METADATA_KEY if has_schema_version::<metadata_request::MetadataRequestData>(api_version) => {
+                RequestPayload::Metadata(parse_metadata_request(&mut buf, api_version)?)
+            },
+            LIST_OFFSETS_KEY if has_schema_version::<list_offsets_request::ListOffsetsRequestData>(api_version) => {
+                RequestPayload::ListOffsets(parse_list_offsets_request(&mut buf, api_version)?)
+            },
+            CREATE_TOPICS_KEY if has_schema_version::<create_topics_request::CreateTopicsRequestData>(api_version) => {
+                RequestPayload::CreateTopics(parse_create_topics_request(&mut buf, api_version)?)
+            },
+            DELETE_TOPICS_KEY if has_schema_version::<delete_topics_request::DeleteTopicsRequestData>(api_version) => {
+                RequestPayload::DeleteTopics(parse_delete_topics_request(&mut buf, api_version)?)
+            },
+            CREATE_PARTITIONS_KEY if has_schema_version::<create_partitions_request::CreatePartitionsRequestData>(api_version) => {
+                RequestPayload::CreatePartitions(parse_create_partitions_request(&mut buf, api_version)?)
+            },
+            FETCH_KEY | DESCRIBE_TOPIC_PARTITIONS_KEY | PRODUCE_KEY | METADATA_KEY | LIST_OFFSETS_KEY | CREATE_TOPICS_KEY
+            | DELETE_TOPICS_KEY | CREATE_PARTITIONS_KEY => {
+                println!("[REQUEST] No schema for API Key {} version {}, skipping body", api_key, api_version);
+                RequestPayload::Unsupported
+            },
+            _ => return Err(ApplicationError::Protocol(format!("Unknown API key: {}", api_key))),
         };
-        
+
         Ok(KafkaRequest::new(header, payload))
     }
 
     pub fn encode_response(&self, response: KafkaResponse) -> Vec<u8> {
         let mut buf = BytesMut::new();
-        
-        // correlation_id
-        buf.put_i32(response.correlation_id);
-        
+        let correlation_id = response.correlation_id;
+        let api_version = response.api_version;
+
         match &response.payload {
             ResponsePayload::ApiVersions(api_versions) => {
-                buf.put_i16(response.error_code);
-                
-                // array of api keys
-                buf.put_i8((api_versions.api_versions.len() + 1) as i8);
-                
-                // Write each API version
-                for version in &api_versions.api_versions {
-                    buf.put_i16(version.api_key);
-                    buf.put_i16(version.min_version);
-                    buf.put_i16(version.max_version);
-                    buf.put_i8(0); // TAG_BUFFER
-                }
-                
-                // throttle time ms
-                buf.put_i32(0);
-                buf.put_i8(0); // TAG_BUFFER
+                // ApiVersions 응답 헤더는 항상 v0 (클라이언트가 버전을 모르는 상태에서도 읽을 수 있어야 함)
+                put_response_header(&mut buf, correlation_id, false);
+                encode_api_versions_response(api_versions, response.error_code).encode(&mut buf, api_version);
             }
             ResponsePayload::DescribeTopicPartitions(describe_response) => {
                 println!("[RESPONSE] Encoding DescribeTopicPartitions response: {:?}", describe_response);
-                
-                // throttle time ms
-                buf.put_i32(0);
-                buf.put_i8(0); // TAG_BUFFER
-                
-                // topics array length (COMPACT_ARRAY)
-                buf.put_i8((describe_response.topics.len() + 1) as i8);
-                
-                // Write each topic
-                for topic in &describe_response.topics {
-                    // topic error code
-                    buf.put_i16(topic.error_code);
-                    
-                    // topic name (COMPACT_STRING)
-                    let topic_name_bytes = topic.topic_name.as_bytes();
-                    buf.put_i8((topic_name_bytes.len() + 1) as i8);
-                    buf.put_slice(topic_name_bytes);
-                    
-                    // topic id (UUID)
-                    buf.put_slice(&topic.topic_id);
-                    
-                    // is_internal
-                    buf.put_i8(topic.is_internal as i8);
-                    
-                    // partitions array (COMPACT_ARRAY)
-                    buf.put_i8((topic.partitions.len() + 1) as i8);
-                    println!("[RESPONSE] Encoding {} partitions", topic.partitions.len());
-                    
-                    // Write each partition
-                    for partition in &topic.partitions {
-                        println!("[RESPONSE] Encoding partition: {:?}", partition);
-                        buf.put_i16(partition.error_code);  // error code 먼저
-                        buf.put_i32(partition.partition_id);  // 그 다음 partition id
-                        buf.put_i32(1);  // leader id (1로 고정)
-                        buf.put_i32(0);  // leader epoch
-                        
-                        // replica nodes array
-                        buf.put_i8(2);  // array length + 1 (1개의 replica이므로 2)
-                        buf.put_i32(1);  // replica node id (1로 고정)
-                        
-                        // isr nodes array
-                        buf.put_i8(2);  // array length + 1 (1개의 isr이므로 2)
-                        buf.put_i32(1);  // isr node id (1로 고정)
-                        
-                        // eligible leader replicas array
-                        buf.put_i8(1);  // array length + 1 (0개이므로 1)
-                        
-                        // last known eligible leader replicas array
-                        buf.put_i8(1);  // array length + 1 (0개이므로 1)
-                        
-                        // offline replicas array
-                        buf.put_i8(1);  // array length + 1 (0개이므로 1)
-                        
-                        buf.put_i8(0);  // TAG_BUFFER for partition
-                    }
-                    
-                    // topic authorized operations
-                    buf.put_u32(0x00000df8);
-                    
-                    buf.put_i8(0);  // TAG_BUFFER for topic
-                }
-                
-                // next_cursor (nullable)
-                buf.put_u8(0xff);  // null
-                
-                buf.put_i8(0);  // TAG_BUFFER for entire response
+                put_message(&mut buf, correlation_id, api_version, encode_describe_topic_partitions_response(describe_response));
             }
             ResponsePayload::Fetch(fetch_response) => {
-                // TAG_BUFFER after header
-                buf.put_i8(0);
-
-                // Body
-                buf.put_i32(fetch_response.throttle_time_ms);
-                buf.put_i16(response.error_code);
-                buf.put_i32(fetch_response.session_id);
-
-                // responses array length (COMPACT_ARRAY)
-                let mut varint_buf = Vec::new();
-                varint_buf.put_uvarint((fetch_response.responses.len() as i64) + 1);
-                buf.put_slice(&varint_buf);
-
-                // Write each topic response
-                for topic in &fetch_response.responses {
-                    // topic_id
-                    buf.put_slice(&topic.topic_id);
-
-                    // partitions array length (COMPACT_ARRAY)
-                    let mut varint_buf = Vec::new();
-                    varint_buf.put_uvarint((topic.partitions.len() as i64) + 1);
-                    buf.put_slice(&varint_buf);
-
-                    // Write each partition
-                    for partition in &topic.partitions {
-                        // partition_index
-                        buf.put_i32(partition.partition_index);
-
-                        // error_code
-                        buf.put_i16(partition.error_code);
-
-                        // high_watermark
-                        buf.put_i64(partition.high_watermark);
-
-                        // last_stable_offset
-                        buf.put_i64(0);
-
-                        // log_start_offset
-                        buf.put_i64(0);
-
-                        // aborted_transactions array (COMPACT_ARRAY)
-                        let mut varint_buf = Vec::new();
-                        varint_buf.put_uvarint(1);
-                        buf.put_slice(&varint_buf);
-
-                        // preferred_read_replica
-                        buf.put_i32(-1);
-
-                        // records (COMPACT_RECORDS)
-                        if let Some(records) = &partition.records {
-                            let mut varint_buf = Vec::new();
-                            varint_buf.put_uvarint(2);
-                            buf.put_slice(&varint_buf);
-                            buf.put_slice(records);
-                        } else {
-                            let mut varint_buf = Vec::new();
-                            varint_buf.put_uvarint(1);
-                            buf.put_slice(&varint_buf);
-                        }
-
-                        // TAG_BUFFER for partition
-                        buf.put_i8(0);
-                    }
-
-                    // TAG_BUFFER for topic
-                    buf.put_i8(0);
-                }
-
-                // TAG_BUFFER for entire response
-                buf.put_i8(0);
+                put_message(&mut buf, correlation_id, api_version, encode_fetch_response(fetch_response, response.error_code));
+            }
+            ResponsePayload::Produce(produce_response) => {
+                put_message(&mut buf, correlation_id, api_version, encode_produce_response(produce_response));
+            }
+            ResponsePayload::Metadata(metadata_response) => {
+                put_message(&mut buf, correlation_id, api_version, encode_metadata_response(metadata_response));
+            }
+            ResponsePayload::ListOffsets(list_offsets_response) => {
+                put_message(&mut buf, correlation_id, api_version, encode_list_offsets_response(list_offsets_response));
+            }
+            ResponsePayload::CreateTopics(create_topics_response) => {
+                put_message(&mut buf, correlation_id, api_version, encode_create_topics_response(create_topics_response));
+            }
+            ResponsePayload::DeleteTopics(delete_topics_response) => {
+                put_message(&mut buf, correlation_id, api_version, encode_delete_topics_response(delete_topics_response));
+            }
+            ResponsePayload::CreatePartitions(create_partitions_response) => {
+                put_message(&mut buf, correlation_id, api_version, encode_create_partitions_response(create_partitions_response));
             }
         }
-        
+
         let total_size = buf.len() as i32;
         let mut final_buf = BytesMut::new();
         final_buf.put_i32(total_size);
         final_buf.put_slice(&buf);
-        
+
         let result = final_buf.to_vec();
         println!("[RESPONSE] Raw bytes: {:02x?}", result);
         result
     }
 }
 
-fn decode_varint(buf: &[u8]) -> u64 {
-    let mut result: u64 = 0;
-    let mut shift = 0;
-    
-    for &byte in buf {
-        result |= ((byte & 0x7f) as u64) << shift;
-        shift += 7;
-        
-        if byte & 0x80 == 0 {
-            break;
-        }
+/// 요청 헤더는 body 가 flexible 이면 v2 (client_id 뒤에 tagged fields), 아니면 v1
+/// 모르는 API 는 v1 로 읽어서 correlation_id 까지는 얻을 수 있게 함
+fn request_header_version(api_key: i16, api_version: i16) -> i16 {
+    let flexible = match api_key {
+        API_VERSIONS_KEY => api_versions_request::is_flexible(api_version),
+        FETCH_KEY => fetch_request::is_flexible(api_version),
+        DESCRIBE_TOPIC_PARTITIONS_KEY => describe_topic_partitions_request::is_flexible(api_version),
+        PRODUCE_KEY => produce_request::is_flexible(api_version),
+        METADATA_KEY => metadata_request::is_flexible(api_version),
+        LIST_OFFSETS_KEY => list_offsets_request::is_flexible(api_version),
+        CREATE_TOPICS_KEY => create_topics_request::is_flexible(api_version),
+        DELETE_TOPICS_KEY => delete_topics_request::is_flexible(api_version),
+        CREATE_PARTITIONS_KEY => create_partitions_request::is_flexible(api_version),
+        _ => false,
+    };
+    if flexible { 2 } else { 1 }
+}
+
+/// 이 버전의 body 를 스키마로 해석할 수 있는지 여부
+fn has_schema_version<M: ApiMessage>(api_version: i16) -> bool {
+    (M::LOWEST_SUPPORTED_VERSION..=M::HIGHEST_SUPPORTED_VERSION).contains(&api_version)
+}
+
+/// 응답 헤더는 body 가 flexible 이면 v1 (tagged fields 포함), 아니면 v0
+fn put_response_header(buf: &mut BytesMut, correlation_id: i32, flexible: bool) {
+    let header_version = if flexible { 1 } else { 0 };
+    response_header::ResponseHeaderData { correlation_id }.encode(buf, header_version);
+}
+
+/// 스키마 범위 밖의 버전 (UNSUPPORTED_VERSION 응답) 은 가장 가까운 스키마 버전으로 인코딩
+fn put_message<M: ApiMessage>(buf: &mut BytesMut, correlation_id: i32, api_version: i16, body: M) {
+    let api_version = api_version.clamp(M::LOWEST_SUPPORTED_VERSION, M::HIGHEST_SUPPORTED_VERSION);
+    put_response_header(buf, correlation_id, M::is_flexible(api_version));
+    body.encode(buf, api_version);
+}
+
+fn parse_fetch_request(buf: &mut Bytes, api_version: i16) -> Result<FetchRequest, ApplicationError> {
+    let data = fetch_request::FetchRequestData::decode(buf, api_version)?;
+    println!("[REQUEST] Fetch max_wait_ms: {}, min_bytes: {}, max_bytes: {}, session: {}/{}",
+        data.max_wait_ms, data.min_bytes, data.max_bytes, data.session_id, data.session_epoch);
+
+    Ok(FetchRequest {
+        // v15 부터 replica_id 는 replica_state 안으로 옮겨감
+        replica_id: if api_version >= 15 { data.replica_state.replica_id } else { data.replica_id },
+        max_wait_ms: data.max_wait_ms,
+        min_bytes: data.min_bytes,
+        max_bytes: data.max_bytes,
+        isolation_level: data.isolation_level,
+        session_id: data.session_id,
+        session_epoch: data.session_epoch,
+        topics: data.topics.into_iter()
+            .map(|topic| FetchTopic {
+                topic: topic.topic,
+                topic_id: topic.topic_id,
+                partitions: topic.partitions.into_iter()
+                    .map(|partition| FetchPartition {
+                        partition: partition.partition,
+                        current_leader_epoch: partition.current_leader_epoch,
+                        fetch_offset: partition.fetch_offset,
+                        last_fetched_epoch: partition.last_fetched_epoch,
+                        log_start_offset: partition.log_start_offset,
+                        partition_max_bytes: partition.partition_max_bytes,
+                    })
+                    .collect(),
+            })
+            .collect(),
+        forgotten_topics_data: data.forgotten_topics_data.into_iter()
+            .map(|topic| ForgottenTopic {
+                topic: topic.topic,
+                topic_id: topic.topic_id,
+                partitions: topic.partitions,
+            })
+            .collect(),
+        rack_id: data.rack_id,
+    })
+}
+
+fn parse_describe_topic_partitions_request(buf: &mut Bytes, api_version: i16) -> Result<DescribeTopicPartitionsRequest, ApplicationError> {
+    let data = describe_topic_partitions_request::DescribeTopicPartitionsRequestData::decode(buf, api_version)?;
+    println!("[REQUEST] DescribeTopicPartitions topics: {:?}, response partition limit: {}",
+        data.topics, data.response_partition_limit);
+
+    Ok(DescribeTopicPartitionsRequest {
+        topics: data.topics.into_iter()
+            .map(|topic| TopicRequest {
+                topic_name: topic.name,
+                partitions: vec![],
+            })
+            .collect(),
+    })
+}
+
+fn parse_produce_request(buf: &mut Bytes, api_version: i16) -> Result<ProduceRequest, ApplicationError> {
+    let data = produce_request::ProduceRequestData::decode(buf, api_version)?;
+    println!("[REQUEST] Produce acks: {}, timeout_ms: {}", data.acks, data.timeout_ms);
+
+    Ok(ProduceRequest {
+        transactional_id: data.transactional_id,
+        acks: data.acks,
+        timeout_ms: data.timeout_ms,
+        topics: data.topic_data.into_iter()
+            .map(|topic| ProduceTopic {
+                partitions: topic.partition_data.into_iter()
+                    .map(|partition| {
+                        println!("[REQUEST] Produce {}-{}: {:?} bytes",
+                            topic.name, partition.index, partition.records.as_ref().map(Vec::len));
+                        ProducePartition {
+                            index: partition.index,
+                            records: partition.records,
+                        }
+                    })
+                    .collect(),
+                name: topic.name,
+            })
+            .collect(),
+    })
+}
+
+fn parse_metadata_request(buf: &mut Bytes, api_version: i16) -> Result<MetadataRequest, ApplicationError> {
+    let data = metadata_request::MetadataRequestData::decode(buf, api_version)?;
+
+    let topics = data.topics.map(|topics| {
+        topics.into_iter()
+            .map(|topic| MetadataRequestTopic {
+                topic_id: topic.topic_id,
+                name: topic.name,
+            })
+            .collect::<Vec<_>>()
+    });
+    // v0 에서는 빈 배열이 모든 토픽을 의미함
+    let topics = match topics {
+        Some(topics) if api_version == 0 && topics.is_empty() => None,
+        topics => topics,
+    };
+    println!("[REQUEST] Metadata topics: {:?}", topics);
+
+    Ok(MetadataRequest {
+        topics,
+        allow_auto_topic_creation: data.allow_auto_topic_creation,
+        include_cluster_authorized_operations: data.include_cluster_authorized_operations,
+        include_topic_authorized_operations: data.include_topic_authorized_operations,
+    })
+}
+
+fn parse_list_offsets_request(buf: &mut Bytes, api_version: i16) -> Result<ListOffsetsRequest, ApplicationError> {
+    let data = list_offsets_request::ListOffsetsRequestData::decode(buf, api_version)?;
+
+    let topics: Vec<ListOffsetsTopic> = data.topics.into_iter()
+        .map(|topic| ListOffsetsTopic {
+            name: topic.name,
+            partitions: topic.partitions.into_iter()
+                .map(|partition| ListOffsetsPartition {
+                    partition_index: partition.partition_index,
+                    current_leader_epoch: partition.current_leader_epoch,
+                    timestamp: partition.timestamp,
+                })
+                .collect(),
+        })
+        .collect();
+    println!("[REQUEST] ListOffsets isolation_level: {}, topics: {:?}", data.isolation_level, topics);
+
+    Ok(ListOffsetsRequest {
+        replica_id: data.replica_id,
+        isolation_level: data.isolation_level,
+        topics,
+    })
+}
+
+fn parse_create_topics_request(buf: &mut Bytes, api_version: i16) -> Result<CreateTopicsRequest, ApplicationError> {
+    let data = create_topics_request::CreateTopicsRequestData::decode(buf, api_version)?;
+
+    let topics: Vec<CreatableTopic> = data.topics.into_iter()
+        .map(|topic| CreatableTopic {
+            name: topic.name,
+            num_partitions: topic.num_partitions,
+            replication_factor: topic.replication_factor,
+            assignments: topic.assignments.into_iter()
+                .map(|assignment| CreatableReplicaAssignment {
+                    partition_index: assignment.partition_index,
+                    broker_ids: assignment.broker_ids,
+                })
+                .collect(),
+            configs: topic.configs.into_iter()
+                .map(|config| CreatableTopicConfig {
+                    name: config.name,
+                    value: config.value,
+                })
+                .collect(),
+        })
+        .collect();
+    println!("[REQUEST] CreateTopics timeout_ms: {}, validate_only: {}, topics: {:?}",
+        data.timeout_ms, data.validate_only, topics);
+
+    Ok(CreateTopicsRequest {
+        topics,
+        timeout_ms: data.timeout_ms,
+        validate_only: data.validate_only,
+    })
+}
+
+fn parse_delete_topics_request(buf: &mut Bytes, api_version: i16) -> Result<DeleteTopicsRequest, ApplicationError> {
+    let data = delete_topics_request::DeleteTopicsRequestData::decode(buf, api_version)?;
+
+    // v6 부터는 topics (이름 또는 ID), 그 전에는 topic_names
+    let topics: Vec<DeleteTopicState> = if api_version >= 6 {
+        data.topics.into_iter()
+            .map(|topic| DeleteTopicState { name: topic.name, topic_id: topic.topic_id })
+            .collect()
+    } else {
+        data.topic_names.into_iter()
+            .map(|name| DeleteTopicState { name: Some(name), topic_id: [0; 16] })
+            .collect()
+    };
+    println!("[REQUEST] DeleteTopics timeout_ms: {}, topics: {:?}", data.timeout_ms, topics);
+
+    Ok(DeleteTopicsRequest {
+        topics,
+        timeout_ms: data.timeout_ms,
+    })
+}
+
+fn parse_create_partitions_request(buf: &mut Bytes, api_version: i16) -> Result<CreatePartitionsRequest, ApplicationError> {
+    let data = create_partitions_request::CreatePartitionsRequestData::decode(buf, api_version)?;
+
+    let topics: Vec<CreatePartitionsTopic> = data.topics.into_iter()
+        .map(|topic| CreatePartitionsTopic {
+            name: topic.name,
+            count: topic.count,
+            assignments: topic.assignments.map(|assignments| {
+                assignments.into_iter().map(|assignment| assignment.broker_ids).collect()
+            }),
+        })
+        .collect();
+    println!("[REQUEST] CreatePartitions timeout_ms: {}, validate_only: {}, topics: {:?}",
+        data.timeout_ms, data.validate_only, topics);
+
+    Ok(CreatePartitionsRequest {
+        topics,
+        timeout_ms: data.timeout_ms,
+        validate_only: data.validate_only,
+    })
+}
+
+fn encode_api_versions_response(response: &ApiVersionsResponse, error_code: i16) -> api_versions_response::ApiVersionsResponseData {
+    api_versions_response::ApiVersionsResponseData {
+        error_code,
+        api_keys: response.api_versions.iter()
+            .map(|version| api_versions_response::ApiVersion {
+                api_key: version.api_key,
+                min_version: version.min_version,
+                max_version: version.max_version,
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn encode_describe_topic_partitions_response(response: &DescribeTopicPartitionsResponse) -> describe_topic_partitions_response::DescribeTopicPartitionsResponseData {
+    use describe_topic_partitions_response::{DescribeTopicPartitionsResponsePartition, DescribeTopicPartitionsResponseTopic};
+
+    describe_topic_partitions_response::DescribeTopicPartitionsResponseData {
+        topics: response.topics.iter()
+            .map(|topic| DescribeTopicPartitionsResponseTopic {
+                error_code: topic.error_code,
+                name: Some(topic.topic_name.clone()),
+                topic_id: topic.topic_id,
+                is_internal: topic.is_internal,
+                partitions: topic.partitions.iter()
+                    .map(|partition| DescribeTopicPartitionsResponsePartition {
+                        error_code: partition.error_code,
+                        partition_index: partition.partition_id,
+                        leader_id: partition.leader_id,
+                        leader_epoch: partition.leader_epoch,
+                        replica_nodes: partition.replica_nodes.clone(),
+                        isr_nodes: partition.isr_nodes.clone(),
+                        eligible_leader_replicas: Some(partition.eligible_leader_replicas.clone()),
+                        last_known_elr: Some(partition.last_known_elr.clone()),
+                        offline_replicas: partition.offline_replicas.clone(),
+                    })
+                    .collect(),
+                topic_authorized_operations: DEFAULT_TOPIC_AUTHORIZED_OPERATIONS,
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn encode_fetch_response(response: &FetchResponse, error_code: i16) -> fetch_response::FetchResponseData {
+    fetch_response::FetchResponseData {
+        throttle_time_ms: response.throttle_time_ms,
+        error_code,
+        session_id: response.session_id,
+        responses: response.responses.iter()
+            .map(|topic| fetch_response::FetchableTopicResponse {
+                topic: topic.topic.clone(),
+                topic_id: topic.topic_id,
+                partitions: topic.partitions.iter()
+                    .map(|partition| fetch_response::PartitionData {
+                        partition_index: partition.partition_index,
+                        error_code: partition.error_code,
+                        high_watermark: partition.high_watermark,
+                        last_stable_offset: partition.last_stable_offset,
+                        log_start_offset: partition.log_start_offset,
+                        diverging_epoch: partition.diverging_epoch
+                            .map(|diverging| fetch_response::EpochEndOffset {
+                                epoch: diverging.epoch,
+                                end_offset: diverging.end_offset,
+                            })
+                            .unwrap_or_default(),
+                        current_leader: partition.current_leader
+                            .map(|leader| fetch_response::LeaderIdAndEpoch {
+                                leader_id: leader.leader_id,
+                                leader_epoch: leader.leader_epoch,
+                            })
+                            .unwrap_or_default(),
+                        aborted_transactions: partition.aborted_transactions.as_ref().map(|aborted| {
+                            aborted.iter()
+                                .map(|transaction| fetch_response::AbortedTransaction {
+                                    producer_id: transaction.producer_id,
+                                    first_offset: transaction.first_offset,
+                                })
+                                .collect()
+                        }),
+                        preferred_read_replica: partition.preferred_read_replica,
+                        records: partition.records.clone(),
+                        ..Default::default()
+                    })
+                    .collect(),
+            })
+            .collect(),
+        node_endpoints: response.node_endpoints.iter()
+            .map(|node| fetch_response::NodeEndpoint {
+                node_id: node.node_id,
+                host: node.host.clone(),
+                port: node.port,
+                rack: node.rack.clone(),
+            })
+            .collect(),
+    }
+}
+
+fn encode_produce_response(response: &ProduceResponse) -> produce_response::ProduceResponseData {
+    produce_response::ProduceResponseData {
+        responses: response.responses.iter()
+            .map(|topic| produce_response::TopicProduceResponse {
+                name: topic.name.clone(),
+                partition_responses: topic.partitions.iter()
+                    .map(|partition| produce_response::PartitionProduceResponse {
+                        index: partition.index,
+                        error_code: partition.error_code,
+                        base_offset: partition.base_offset,
+                        log_append_time_ms: partition.log_append_time_ms,
+                        log_start_offset: partition.log_start_offset,
+                        ..Default::default()
+                    })
+                    .collect(),
+            })
+            .collect(),
+        throttle_time_ms: response.throttle_time_ms,
+        ..Default::default()
+    }
+}
+
+fn encode_metadata_response(response: &MetadataResponse) -> metadata_response::MetadataResponseData {
+    metadata_response::MetadataResponseData {
+        throttle_time_ms: response.throttle_time_ms,
+        brokers: response.brokers.iter()
+            .map(|broker| metadata_response::MetadataResponseBroker {
+                node_id: broker.node_id,
+                host: broker.host.clone(),
+                port: broker.port,
+                rack: broker.rack.clone(),
+            })
+            .collect(),
+        cluster_id: response.cluster_id.clone(),
+        controller_id: response.controller_id,
+        topics: response.topics.iter()
+            .map(|topic| metadata_response::MetadataResponseTopic {
+                error_code: topic.error_code,
+                name: topic.name.clone(),
+                topic_id: topic.topic_id,
+                is_internal: topic.is_internal,
+                partitions: topic.partitions.iter()
+                    .map(|partition| metadata_response::MetadataResponsePartition {
+                        error_code: partition.error_code,
+                        partition_index: partition.partition_index,
+                        leader_id: partition.leader_id,
+                        leader_epoch: partition.leader_epoch,
+                        replica_nodes: partition.replica_nodes.clone(),
+                        isr_nodes: partition.isr_nodes.clone(),
+                        offline_replicas: partition.offline_replicas.clone(),
+                    })
+                    .collect(),
+                topic_authorized_operations: topic.topic_authorized_operations,
+            })
+            .collect(),
+        cluster_authorized_operations: response.cluster_authorized_operations,
+    }
+}
+
+fn encode_list_offsets_response(response: &ListOffsetsResponse) -> list_offsets_response::ListOffsetsResponseData {
+    list_offsets_response::ListOffsetsResponseData {
+        throttle_time_ms: response.throttle_time_ms,
+        topics: response.topics.iter()
+            .map(|topic| list_offsets_response::ListOffsetsTopicResponse {
+                name: topic.name.clone(),
+                partitions: topic.partitions.iter()
+                    .map(|partition| list_offsets_response::ListOffsetsPartitionResponse {
+                        partition_index: partition.partition_index,
+                        error_code: partition.error_code,
+                        timestamp: partition.timestamp,
+                        offset: partition.offset,
+                        leader_epoch: partition.leader_epoch,
+                        ..Default::default()
+                    })
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+fn encode_create_topics_response(response: &CreateTopicsResponse) -> create_topics_response::CreateTopicsResponseData {
+    create_topics_response::CreateTopicsResponseData {
+        throttle_time_ms: response.throttle_time_ms,
+        topics: response.topics.iter()
+            .map(|topic| create_topics_response::CreatableTopicResult {
+                name: topic.name.clone(),
+                topic_id: topic.topic_id,
+                error_code: topic.error_code,
+                error_message: topic.error_message.clone(),
+                num_partitions: topic.num_partitions,
+                replication_factor: topic.replication_factor,
+                configs: topic.configs.as_ref().map(|configs| {
+                    configs.iter()
+                        .map(|config| create_topics_response::CreatableTopicConfigs {
+                            name: config.name.clone(),
+                            value: config.value.clone(),
+                            read_only: config.read_only,
+                            config_source: config.config_source,
+                            is_sensitive: config.is_sensitive,
+                        })
+                        .collect()
+                }),
+                ..Default::default()
+            })
+            .collect(),
+    }
+}
+
+fn encode_delete_topics_response(response: &DeleteTopicsResponse) -> delete_topics_response::DeleteTopicsResponseData {
+    delete_topics_response::DeleteTopicsResponseData {
+        throttle_time_ms: response.throttle_time_ms,
+        responses: response.responses.iter()
+            .map(|topic| delete_topics_response::DeletableTopicResult {
+                name: topic.name.clone(),
+                topic_id: topic.topic_id,
+                error_code: topic.error_code,
+                error_message: topic.error_message.clone(),
+            })
+            .collect(),
+    }
+}
+
+fn encode_create_partitions_response(response: &CreatePartitionsResponse) -> create_partitions_response::CreatePartitionsResponseData {
+    create_partitions_response::CreatePartitionsResponseData {
+        throttle_time_ms: response.throttle_time_ms,
+        results: response.results.iter()
+            .map(|result| create_partitions_response::CreatePartitionsTopicResult {
+                name: result.name.clone(),
+                error_code: result.error_code,
+                error_message: result.error_message.clone(),
+            })
+            .collect(),
     }
-    
-    result
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::adapters::incoming::protocol::constants::{
-        MAX_SUPPORTED_VERSION, UNKNOWN_TOPIC_OR_PARTITION
+        MAX_SUPPORTED_VERSION, UNKNOWN_TOPIC_OR_PARTITION, UNSUPPORTED_VERSION
     };
     use crate::adapters::incoming::protocol::messages::{
-        ApiVersion, ApiVersionsResponse, DescribeTopicPartitionsResponse, PartitionInfo
+        AbortedTransaction, ApiVersion, ApiVersionsResponse, DescribeTopicPartitionsResponse, EpochEndOffset,
+        FetchablePartitionResponse, LeaderIdAndEpoch,
+        FetchableTopicResponse, MetadataResponseBroker,
+        MetadataResponsePartition, MetadataResponseTopic, PartitionInfo, ProducePartitionResponse,
+        ProduceResponse, ProduceTopicResponse, TopicResponse,
     };
 
     #[test]
@@ -460,6 +660,50 @@ mod tests {
         assert!(matches!(request.payload, RequestPayload::ApiVersions));
     }
 
+    #[test]
+    fn test_parse_api_versions_request_header_versions() -> Result<(), ApplicationError> {
+        let parser = KafkaProtocolParser::new();
+
+        // v0 ~ v2 는 요청 헤더 v1: client_id 뒤에 tag buffer 없음
+        let mut data = Vec::new();
+        data.extend_from_slice(&API_VERSIONS_KEY.to_be_bytes());
+        data.extend_from_slice(&2i16.to_be_bytes());
+        data.extend_from_slice(&5i32.to_be_bytes());
+        data.extend_from_slice(&3i16.to_be_bytes());
+        data.extend_from_slice(b"abc");
+        let request = parser.parse_request(&data)?;
+        assert_eq!(request.header.correlation_id, 5);
+        assert_eq!(request.header.client_id, Some("abc".to_string()));
+
+        // v3 부터는 요청 헤더 v2: client_id 는 그대로 STRING, 뒤에 tag buffer
+        let mut data = Vec::new();
+        data.extend_from_slice(&API_VERSIONS_KEY.to_be_bytes());
+        data.extend_from_slice(&3i16.to_be_bytes());
+        data.extend_from_slice(&6i32.to_be_bytes());
+        data.extend_from_slice(&3i16.to_be_bytes());
+        data.extend_from_slice(b"abc");
+        data.push(0); // tag buffer
+        data.extend_from_slice(&[4, b'k', b'c', b'a', 2, b'1', 0]); // client_software_name / version
+        let request = parser.parse_request(&data)?;
+        assert_eq!(request.header.correlation_id, 6);
+        assert_eq!(request.header.client_id, Some("abc".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_request_header_version() {
+        assert_eq!(request_header_version(API_VERSIONS_KEY, 2), 1);
+        assert_eq!(request_header_version(API_VERSIONS_KEY, 3), 2);
+        assert_eq!(request_header_version(FETCH_KEY, 11), 1);
+        assert_eq!(request_header_version(FETCH_KEY, 12), 2);
+        assert_eq!(request_header_version(PRODUCE_KEY, 8), 1);
+        assert_eq!(request_header_version(PRODUCE_KEY, 9), 2);
+        assert_eq!(request_header_version(METADATA_KEY, 8), 1);
+        assert_eq!(request_header_version(LIST_OFFSETS_KEY, 6), 2);
+        assert_eq!(request_header_version(DESCRIBE_TOPIC_PARTITIONS_KEY, 0), 2);
+        assert_eq!(request_header_version(-1, 0), 1);
+    }
+
     #[test]
     fn test_parse_describe_topic_partitions_request() {
         let mut data = Vec::new();
@@ -491,8 +735,8 @@ mod tests {
         // Response partition limit
         data.extend_from_slice(&1u32.to_be_bytes());
         
-        // cursor
-        data.push(0);
+        // cursor (nullable struct, -1 = null)
+        data.push(0xff);
         
         // tag buffer after cursor
         data.push(0);
@@ -543,8 +787,8 @@ mod tests {
         // Response partition limit
         data.extend_from_slice(&1u32.to_be_bytes());
         
-        // cursor
-        data.push(0);
+        // cursor (nullable struct, -1 = null)
+        data.push(0xff);
         
         // tag buffer after cursor
         data.push(0);
@@ -572,6 +816,7 @@ mod tests {
         let response = KafkaResponse::new(
             123,
             0,
+            0,
             ResponsePayload::ApiVersions(ApiVersionsResponse::new(vec![
                 ApiVersion {
                     api_key: API_VERSIONS_KEY,
@@ -597,6 +842,7 @@ mod tests {
     fn test_encode_describe_topic_partitions_response() {
         let response = KafkaResponse::new(
             123,
+            0,
             UNKNOWN_TOPIC_OR_PARTITION,
             ResponsePayload::DescribeTopicPartitions(
                 DescribeTopicPartitionsResponse {
@@ -632,6 +878,7 @@ mod tests {
         let response = KafkaResponse::new(
             123,
             0,
+            0,
             ResponsePayload::DescribeTopicPartitions(
                 DescribeTopicPartitionsResponse {
                     topics: vec![TopicResponse {
@@ -643,10 +890,24 @@ mod tests {
                             PartitionInfo {
                                 partition_id: 0,
                                 error_code: 0,
+                                leader_id: 1,
+                                leader_epoch: 0,
+                                replica_nodes: vec![1],
+                                isr_nodes: vec![1],
+                                eligible_leader_replicas: vec![],
+                                last_known_elr: vec![],
+                                offline_replicas: vec![],
                             },
                             PartitionInfo {
                                 partition_id: 1,
                                 error_code: 0,
+                                leader_id: 1,
+                                leader_epoch: 0,
+                                replica_nodes: vec![1],
+                                isr_nodes: vec![1],
+                                eligible_leader_replicas: vec![],
+                                last_known_elr: vec![],
+                                offline_replicas: vec![],
                             }
                         ],
                     }],
@@ -686,4 +947,828 @@ mod tests {
         // partitions array length = 3 (2개의 파티션 + 1)
         assert_eq!(encoded[44], 3);
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_parse_produce_request_v3() {
+        let mut data = Vec::new();
+
+        // Header (v1, tag buffer 없음)
+        data.extend_from_slice(&PRODUCE_KEY.to_be_bytes());  // API Key
+        data.extend_from_slice(&3i16.to_be_bytes());  // API Version
+        data.extend_from_slice(&7i32.to_be_bytes());  // Correlation ID
+        data.extend_from_slice(&(-1i16).to_be_bytes());  // Client ID (null)
+
+        data.extend_from_slice(&(-1i16).to_be_bytes());  // transactional_id (null)
+        data.extend_from_slice(&1i16.to_be_bytes());  // acks
+        data.extend_from_slice(&1500i32.to_be_bytes());  // timeout_ms
+
+        data.extend_from_slice(&1i32.to_be_bytes());  // topics array length
+        data.extend_from_slice(&3i16.to_be_bytes());  // topic name length
+        data.extend_from_slice(b"foo");
+        data.extend_from_slice(&1i32.to_be_bytes());  // partitions array length
+        data.extend_from_slice(&2i32.to_be_bytes());  // partition index
+        data.extend_from_slice(&3i32.to_be_bytes());  // records length
+        data.extend_from_slice(&[1, 2, 3]);
+
+        let parser = KafkaProtocolParser::new();
+        let request = parser.parse_request(&data).unwrap();
+
+        assert_eq!(request.header.api_key, PRODUCE_KEY);
+        assert_eq!(request.header.api_version, 3);
+        assert_eq!(request.header.correlation_id, 7);
+
+        match request.payload {
+            RequestPayload::Produce(req) => {
+                assert_eq!(req.transactional_id, None);
+                assert_eq!(req.acks, 1);
+                assert_eq!(req.timeout_ms, 1500);
+                assert_eq!(req.topics.len(), 1);
+                assert_eq!(req.topics[0].name, "foo");
+                assert_eq!(req.topics[0].partitions, vec![ProducePartition {
+                    index: 2,
+                    records: Some(vec![1, 2, 3]),
+                }]);
+            }
+            _ => panic!("Expected Produce payload"),
+        }
+    }
+
+    #[test]
+    fn test_parse_produce_request_v9() {
+        let mut data = Vec::new();
+
+        // Header (v2)
+        data.extend_from_slice(&PRODUCE_KEY.to_be_bytes());  // API Key
+        data.extend_from_slice(&9i16.to_be_bytes());  // API Version
+        data.extend_from_slice(&7i32.to_be_bytes());  // Correlation ID
+        data.extend_from_slice(&0i16.to_be_bytes());  // Client ID length
+        data.push(0); // tag buffer
+
+        data.push(0); // transactional_id (COMPACT_NULLABLE_STRING, null)
+        data.extend_from_slice(&(-1i16).to_be_bytes());  // acks
+        data.extend_from_slice(&1500i32.to_be_bytes());  // timeout_ms
+
+        data.push(2); // topics array length + 1
+        data.push(4); // topic name length + 1
+        data.extend_from_slice(b"foo");
+        data.push(2); // partitions array length + 1
+        data.extend_from_slice(&0i32.to_be_bytes());  // partition index
+        data.push(4); // records length + 1
+        data.extend_from_slice(&[1, 2, 3]);
+        data.push(0); // tag buffer for partition
+        data.push(0); // tag buffer for topic
+        data.push(0); // tag buffer for request
+
+        let parser = KafkaProtocolParser::new();
+        let request = parser.parse_request(&data).unwrap();
+
+        match request.payload {
+            RequestPayload::Produce(req) => {
+                assert_eq!(req.acks, -1);
+                assert_eq!(req.topics[0].name, "foo");
+                assert_eq!(req.topics[0].partitions[0].records, Some(vec![1, 2, 3]));
+            }
+            _ => panic!("Expected Produce payload"),
+        }
+    }
+
+    #[test]
+    fn test_parse_fetch_request_v16() {
+        let mut data = Vec::new();
+
+        // Header (v2)
+        data.extend_from_slice(&FETCH_KEY.to_be_bytes());  // API Key
+        data.extend_from_slice(&16i16.to_be_bytes());  // API Version
+        data.extend_from_slice(&3i32.to_be_bytes());  // Correlation ID
+        data.extend_from_slice(&(-1i16).to_be_bytes());  // Client ID (null)
+        data.push(0); // tag buffer
+
+        data.extend_from_slice(&500i32.to_be_bytes());  // max_wait_ms
+        data.extend_from_slice(&1i32.to_be_bytes());  // min_bytes
+        data.extend_from_slice(&1024i32.to_be_bytes());  // max_bytes
+        data.push(0); // isolation_level
+        data.extend_from_slice(&77i32.to_be_bytes());  // session_id
+        data.extend_from_slice(&2i32.to_be_bytes());  // session_epoch
+
+        data.push(2); // topics array length + 1
+        data.extend_from_slice(&[1; 16]);  // topic_id
+        data.push(3); // partitions array length + 1
+        for partition in 0..2i32 {
+            data.extend_from_slice(&partition.to_be_bytes());  // partition
+            data.extend_from_slice(&(-1i32).to_be_bytes());  // current_leader_epoch
+            data.extend_from_slice(&10i64.to_be_bytes());  // fetch_offset
+            data.extend_from_slice(&(-1i32).to_be_bytes());  // last_fetched_epoch
+            data.extend_from_slice(&(-1i64).to_be_bytes());  // log_start_offset
+            data.extend_from_slice(&512i32.to_be_bytes());  // partition_max_bytes
+            data.push(0); // tag buffer for partition
+        }
+        data.push(0); // tag buffer for topic
+
+        data.push(2); // forgotten_topics_data array length + 1
+        data.extend_from_slice(&[2; 16]);  // topic_id
+        data.push(2); // partitions array length + 1
+        data.extend_from_slice(&5i32.to_be_bytes());
+        data.push(0); // tag buffer for forgotten topic
+
+        data.push(1); // rack_id (empty)
+        data.push(0); // tag buffer for request
+
+        let parser = KafkaProtocolParser::new();
+        let request = parser.parse_request(&data).unwrap();
+
+        match request.payload {
+            RequestPayload::Fetch(req) => {
+                assert_eq!(req.session_id, 77);
+                assert_eq!(req.session_epoch, 2);
+                assert_eq!(req.topics[0].partitions.len(), 2);
+                assert_eq!(req.topics[0].partitions[1].partition, 1);
+                assert_eq!(req.topics[0].partitions[1].partition_max_bytes, 512);
+                assert_eq!(req.forgotten_topics_data, vec![ForgottenTopic { topic: String::new(), topic_id: [2; 16], partitions: vec![5] }]);
+            }
+            _ => panic!("Expected Fetch payload"),
+        }
+    }
+
+    #[test]
+    fn test_parse_produce_request_truncated() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&PRODUCE_KEY.to_be_bytes());
+        data.extend_from_slice(&3i16.to_be_bytes());
+        data.extend_from_slice(&7i32.to_be_bytes());
+        data.extend_from_slice(&(-1i16).to_be_bytes());
+        data.extend_from_slice(&(-1i16).to_be_bytes());  // transactional_id
+        data.extend_from_slice(&1i16.to_be_bytes());  // acks (timeout_ms 누락)
+
+        let parser = KafkaProtocolParser::new();
+        assert!(parser.parse_request(&data).is_err());
+    }
+
+    fn produce_response(api_version: i16) -> KafkaResponse {
+        KafkaResponse::new(
+            7,
+            api_version,
+            0,
+            ResponsePayload::Produce(ProduceResponse {
+                responses: vec![ProduceTopicResponse {
+                    name: "foo".to_string(),
+                    partitions: vec![ProducePartitionResponse {
+                        index: 0,
+                        error_code: 0,
+                        base_offset: 5,
+                        log_append_time_ms: -1,
+                        log_start_offset: 0,
+                    }],
+                }],
+                throttle_time_ms: 0,
+            }),
+        )
+    }
+
+    #[test]
+    fn test_encode_produce_response_v3() {
+        let parser = KafkaProtocolParser::new();
+        let encoded = parser.encode_response(produce_response(3));
+
+        let mut expected = Vec::new();
+        expected.extend_from_slice(&7i32.to_be_bytes());  // correlation id
+        expected.extend_from_slice(&1i32.to_be_bytes());  // responses length
+        expected.extend_from_slice(&3i16.to_be_bytes());  // name length
+        expected.extend_from_slice(b"foo");
+        expected.extend_from_slice(&1i32.to_be_bytes());  // partitions length
+        expected.extend_from_slice(&0i32.to_be_bytes());  // index
+        expected.extend_from_slice(&0i16.to_be_bytes());  // error code
+        expected.extend_from_slice(&5i64.to_be_bytes());  // base offset
+        expected.extend_from_slice(&(-1i64).to_be_bytes());  // log append time
+        expected.extend_from_slice(&0i32.to_be_bytes());  // throttle time
+
+        assert_eq!(&encoded[..4], &(expected.len() as i32).to_be_bytes());
+        assert_eq!(&encoded[4..], &expected[..]);
+    }
+
+    #[test]
+    fn test_encode_produce_response_v9() {
+        let parser = KafkaProtocolParser::new();
+        let encoded = parser.encode_response(produce_response(9));
+
+        let mut expected = Vec::new();
+        expected.extend_from_slice(&7i32.to_be_bytes());  // correlation id
+        expected.push(0);  // header tag buffer
+        expected.push(2);  // responses length + 1
+        expected.push(4);  // name length + 1
+        expected.extend_from_slice(b"foo");
+        expected.push(2);  // partitions length + 1
+        expected.extend_from_slice(&0i32.to_be_bytes());  // index
+        expected.extend_from_slice(&0i16.to_be_bytes());  // error code
+        expected.extend_from_slice(&5i64.to_be_bytes());  // base offset
+        expected.extend_from_slice(&(-1i64).to_be_bytes());  // log append time
+        expected.extend_from_slice(&0i64.to_be_bytes());  // log start offset
+        expected.push(1);  // record errors (empty)
+        expected.push(0);  // error message (null)
+        expected.push(0);  // tag buffer for partition
+        expected.push(0);  // tag buffer for topic
+        expected.extend_from_slice(&0i32.to_be_bytes());  // throttle time
+        expected.push(0);  // tag buffer for response
+
+        assert_eq!(&encoded[4..], &expected[..]);
+    }
+
+    #[test]
+    fn test_parse_metadata_request_v1() {
+        let mut data = Vec::new();
+
+        // Header (v1)
+        data.extend_from_slice(&METADATA_KEY.to_be_bytes());  // API Key
+        data.extend_from_slice(&1i16.to_be_bytes());  // API Version
+        data.extend_from_slice(&3i32.to_be_bytes());  // Correlation ID
+        data.extend_from_slice(&(-1i16).to_be_bytes());  // Client ID (null)
+
+        data.extend_from_slice(&1i32.to_be_bytes());  // topics array length
+        data.extend_from_slice(&3i16.to_be_bytes());  // topic name length
+        data.extend_from_slice(b"foo");
+
+        let parser = KafkaProtocolParser::new();
+        let request = parser.parse_request(&data).unwrap();
+
+        match request.payload {
+            RequestPayload::Metadata(req) => {
+                let topics = req.topics.unwrap();
+                assert_eq!(topics.len(), 1);
+                assert_eq!(topics[0].name, Some("foo".to_string()));
+                assert!(req.allow_auto_topic_creation);
+            }
+            _ => panic!("Expected Metadata payload"),
+        }
+    }
+
+    #[test]
+    fn test_parse_metadata_request_v12_all_topics() {
+        let mut data = Vec::new();
+
+        // Header (v2)
+        data.extend_from_slice(&METADATA_KEY.to_be_bytes());  // API Key
+        data.extend_from_slice(&12i16.to_be_bytes());  // API Version
+        data.extend_from_slice(&3i32.to_be_bytes());  // Correlation ID
+        data.extend_from_slice(&0i16.to_be_bytes());  // Client ID length
+        data.push(0); // tag buffer
+
+        data.push(0); // topics (COMPACT_ARRAY, null)
+        data.push(0); // allow_auto_topic_creation
+        data.push(1); // include_topic_authorized_operations
+        data.push(0); // tag buffer
+
+        let parser = KafkaProtocolParser::new();
+        let request = parser.parse_request(&data).unwrap();
+
+        match request.payload {
+            RequestPayload::Metadata(req) => {
+                assert_eq!(req.topics, None);
+                assert!(!req.allow_auto_topic_creation);
+                assert!(!req.include_cluster_authorized_operations);
+                assert!(req.include_topic_authorized_operations);
+            }
+            _ => panic!("Expected Metadata payload"),
+        }
+    }
+
+    #[test]
+    fn test_encode_metadata_response_v0() {
+        let response = KafkaResponse::new(
+            3,
+            0,
+            0,
+            ResponsePayload::Metadata(MetadataResponse {
+                throttle_time_ms: 0,
+                brokers: vec![MetadataResponseBroker {
+                    node_id: 1,
+                    host: "h".to_string(),
+                    port: 9092,
+                    rack: None,
+                }],
+                cluster_id: Some("ignored".to_string()),
+                controller_id: 1,
+                topics: vec![MetadataResponseTopic {
+                    error_code: 0,
+                    name: Some("foo".to_string()),
+                    topic_id: [1; 16],
+                    is_internal: false,
+                    partitions: vec![MetadataResponsePartition {
+                        error_code: 0,
+                        partition_index: 0,
+                        leader_id: 1,
+                        leader_epoch: 0,
+                        replica_nodes: vec![1],
+                        isr_nodes: vec![1],
+                        offline_replicas: vec![],
+                    }],
+                    topic_authorized_operations: i32::MIN,
+                }],
+                cluster_authorized_operations: i32::MIN,
+            }),
+        );
+
+        let parser = KafkaProtocolParser::new();
+        let encoded = parser.encode_response(response);
+
+        let mut expected = Vec::new();
+        expected.extend_from_slice(&3i32.to_be_bytes());  // correlation id
+        expected.extend_from_slice(&1i32.to_be_bytes());  // brokers length
+        expected.extend_from_slice(&1i32.to_be_bytes());  // node id
+        expected.extend_from_slice(&1i16.to_be_bytes());  // host length
+        expected.extend_from_slice(b"h");
+        expected.extend_from_slice(&9092i32.to_be_bytes());  // port
+        expected.extend_from_slice(&1i32.to_be_bytes());  // topics length
+        expected.extend_from_slice(&0i16.to_be_bytes());  // error code
+        expected.extend_from_slice(&3i16.to_be_bytes());  // name length
+        expected.extend_from_slice(b"foo");
+        expected.extend_from_slice(&1i32.to_be_bytes());  // partitions length
+        expected.extend_from_slice(&0i16.to_be_bytes());  // error code
+        expected.extend_from_slice(&0i32.to_be_bytes());  // partition index
+        expected.extend_from_slice(&1i32.to_be_bytes());  // leader id
+        expected.extend_from_slice(&1i32.to_be_bytes());  // replicas length
+        expected.extend_from_slice(&1i32.to_be_bytes());  // replica
+        expected.extend_from_slice(&1i32.to_be_bytes());  // isr length
+        expected.extend_from_slice(&1i32.to_be_bytes());  // isr
+
+        assert_eq!(&encoded[4..], &expected[..]);
+    }
+
+    #[test]
+    fn test_encode_fetch_response_v16_round_trip() -> Result<(), ApplicationError> {
+        let response = KafkaResponse::new(
+            9,
+            16,
+            0,
+            ResponsePayload::Fetch(FetchResponse {
+                throttle_time_ms: 0,
+                session_id: 5,
+                responses: vec![FetchableTopicResponse {
+                    topic: String::new(),
+                    topic_id: [3; 16],
+                    partitions: vec![FetchablePartitionResponse {
+                        partition_index: 0,
+                        error_code: 0,
+                        high_watermark: 10,
+                        last_stable_offset: 10,
+                        log_start_offset: 0,
+                        records: Some(vec![1, 2, 3]),
+                        ..FetchablePartitionResponse::error(0, 0)
+                    }],
+                }],
+                node_endpoints: vec![],
+            }),
+        );
+
+        let parser = KafkaProtocolParser::new();
+        let encoded = parser.encode_response(response);
+
+        // correlation id 뒤에 헤더 v1 의 tagged fields
+        let mut buf = Bytes::copy_from_slice(&encoded[4..]);
+        let header = response_header::ResponseHeaderData::decode(&mut buf, 1)?;
+        assert_eq!(header.correlation_id, 9);
+
+        let body = fetch_response::FetchResponseData::decode(&mut buf, 16)?;
+        assert!(buf.is_empty());
+        assert_eq!(body.session_id, 5);
+        let partition = &body.responses[0].partitions[0];
+        assert_eq!(body.responses[0].topic_id, [3; 16]);
+        assert_eq!(partition.high_watermark, 10);
+        assert_eq!(partition.preferred_read_replica, -1);
+        assert_eq!(partition.records, Some(vec![1, 2, 3]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_fetch_request_versions() -> Result<(), ApplicationError> {
+        let request = fetch_request::FetchRequestData {
+            replica_id: 2,
+            replica_state: fetch_request::ReplicaState { replica_id: 3, replica_epoch: 1 },
+            isolation_level: 1,
+            topics: vec![fetch_request::FetchTopic {
+                topic: "foo".to_string(),
+                topic_id: [4; 16],
+                partitions: vec![fetch_request::FetchPartition {
+                    partition: 1,
+                    fetch_offset: 7,
+                    last_fetched_epoch: 0,
+                    ..Default::default()
+                }],
+            }],
+            rack_id: "rack-a".to_string(),
+            ..Default::default()
+        };
+
+        // (버전, replica_id, 토픽 이름, topic_id, last_fetched_epoch, rack_id)
+        let parser = KafkaProtocolParser::new();
+        for (api_version, replica_id, topic, topic_id, last_fetched_epoch, rack_id) in [
+            (4, 2, "foo", [0; 16], -1, ""),
+            (11, 2, "foo", [0; 16], -1, "rack-a"),
+            (12, 2, "foo", [0; 16], 0, "rack-a"),
+            (13, 2, "", [4; 16], 0, "rack-a"),
+            (15, 3, "", [4; 16], 0, "rack-a"),
+        ] {
+            match parser.parse_request(&encode_request(&request, api_version))?.payload {
+                RequestPayload::Fetch(req) => {
+                    assert_eq!(req.replica_id, replica_id);
+                    assert_eq!(req.isolation_level, 1);
+                    assert_eq!(req.topics[0].topic, topic);
+                    assert_eq!(req.topics[0].topic_id, topic_id);
+                    assert_eq!(req.topics[0].partitions[0].fetch_offset, 7);
+                    assert_eq!(req.topics[0].partitions[0].last_fetched_epoch, last_fetched_epoch);
+                    assert_eq!(req.rack_id, rack_id);
+                }
+                _ => panic!("Expected Fetch payload"),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_fetch_response_versions() -> Result<(), ApplicationError> {
+        let response = |api_version| KafkaResponse::new(
+            9,
+            api_version,
+            0,
+            ResponsePayload::Fetch(FetchResponse {
+                throttle_time_ms: 0,
+                session_id: 0,
+                responses: vec![FetchableTopicResponse {
+                    topic: "foo".to_string(),
+                    topic_id: [0; 16],
+                    partitions: vec![FetchablePartitionResponse {
+                        partition_index: 0,
+                        error_code: 0,
+                        high_watermark: 10,
+                        last_stable_offset: 8,
+                        log_start_offset: 0,
+                        aborted_transactions: Some(vec![AbortedTransaction { producer_id: 5, first_offset: 8 }]),
+                        diverging_epoch: Some(EpochEndOffset { epoch: 1, end_offset: 4 }),
+                        current_leader: Some(LeaderIdAndEpoch { leader_id: 3, leader_epoch: 2 }),
+                        ..FetchablePartitionResponse::error(0, 0)
+                    }],
+                }],
+                node_endpoints: vec![],
+            }),
+        );
+
+        let parser = KafkaProtocolParser::new();
+        for api_version in [4, 11, 12] {
+            let encoded = parser.encode_response(response(api_version));
+            let mut buf = Bytes::copy_from_slice(&encoded[4..]);
+            let header_version = if api_version >= 12 { 1 } else { 0 };
+            response_header::ResponseHeaderData::decode(&mut buf, header_version)?;
+            let body = fetch_response::FetchResponseData::decode(&mut buf, api_version)?;
+            assert!(buf.is_empty());
+
+            assert_eq!(body.responses[0].topic, "foo");
+            let partition = &body.responses[0].partitions[0];
+            assert_eq!(partition.last_stable_offset, 8);
+            assert_eq!(partition.aborted_transactions.as_ref().unwrap()[0].producer_id, 5);
+            // tagged field 라서 v12 부터만 실림
+            if api_version >= 12 {
+                assert_eq!(partition.diverging_epoch.end_offset, 4);
+                assert_eq!((partition.current_leader.leader_id, partition.current_leader.leader_epoch), (3, 2));
+            } else {
+                assert_eq!(partition.diverging_epoch, fetch_response::EpochEndOffset::default());
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsupported_versions() -> Result<(), ApplicationError> {
+        let parser = KafkaProtocolParser::new();
+
+        // 스키마에 없는 버전은 body 를 읽지 않음
+        let mut data = Vec::new();
+        data.extend_from_slice(&FETCH_KEY.to_be_bytes());
+        data.extend_from_slice(&17i16.to_be_bytes());
+        data.extend_from_slice(&4i32.to_be_bytes());
+        data.extend_from_slice(&(-1i16).to_be_bytes());
+        data.extend_from_slice(&[0, 0xff, 0xff]);
+        assert_eq!(parser.parse_request(&data)?.payload, RequestPayload::Unsupported);
+
+        // 스키마 범위 안이면 브로커가 지원하지 않는 버전이어도 body 를 읽음
+        let produce = produce_request::ProduceRequestData { acks: 1, ..Default::default() };
+        assert!(matches!(parser.parse_request(&encode_request(&produce, 2))?.payload, RequestPayload::Produce(_)));
+
+        data[..2].copy_from_slice(&999i16.to_be_bytes());
+        assert!(parser.parse_request(&data).is_err());
+
+        // 응답은 스키마에서 가장 가까운 버전으로 인코딩
+        let response = KafkaResponse::new(4, 17, UNSUPPORTED_VERSION, ResponsePayload::Fetch(FetchResponse {
+            throttle_time_ms: 0,
+            session_id: 0,
+            responses: vec![],
+            node_endpoints: vec![],
+        }));
+        let encoded = parser.encode_response(response);
+        let mut buf = Bytes::copy_from_slice(&encoded[4..]);
+        response_header::ResponseHeaderData::decode(&mut buf, 1)?;
+        let body = fetch_response::FetchResponseData::decode(&mut buf, 16)?;
+        assert!(buf.is_empty());
+        assert_eq!(body.error_code, UNSUPPORTED_VERSION);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_list_offsets_request_versions() -> Result<(), ApplicationError> {
+        let request = list_offsets_request::ListOffsetsRequestData {
+            replica_id: -1,
+            isolation_level: 1,
+            topics: vec![list_offsets_request::ListOffsetsTopic {
+                name: "foo".to_string(),
+                partitions: vec![list_offsets_request::ListOffsetsPartition {
+                    partition_index: 0,
+                    current_leader_epoch: 4,
+                    timestamp: -2,
+                    ..Default::default()
+                }],
+            }],
+        };
+
+        let parser = KafkaProtocolParser::new();
+        for (api_version, isolation_level, current_leader_epoch) in [(1, 0, -1), (8, 1, 4)] {
+            let mut data = BytesMut::new();
+            let header_version = if api_version >= 6 { 2 } else { 1 };
+            request_header::RequestHeaderData {
+                request_api_key: LIST_OFFSETS_KEY,
+                request_api_version: api_version,
+                correlation_id: 1,
+                client_id: None,
+            }.encode(&mut data, header_version);
+            request.encode(&mut data, api_version);
+
+            match parser.parse_request(&data)?.payload {
+                RequestPayload::ListOffsets(req) => {
+                    assert_eq!(req.isolation_level, isolation_level);
+                    assert_eq!(req.topics[0].name, "foo");
+                    assert_eq!(req.topics[0].partitions, vec![ListOffsetsPartition {
+                        partition_index: 0,
+                        current_leader_epoch,
+                        timestamp: -2,
+                    }]);
+                }
+                _ => panic!("Expected ListOffsets payload"),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_create_topics_request_versions() -> Result<(), ApplicationError> {
+        let request = create_topics_request::CreateTopicsRequestData {
+            topics: vec![create_topics_request::CreatableTopic {
+                name: "foo".to_string(),
+                num_partitions: -1,
+                replication_factor: -1,
+                assignments: vec![create_topics_request::CreatableReplicaAssignment {
+                    partition_index: 0,
+                    broker_ids: vec![1],
+                }],
+                configs: vec![create_topics_request::CreatableTopicConfig {
+                    name: "retention.ms".to_string(),
+                    value: None,
+                }],
+            }],
+            timeout_ms: 5000,
+            validate_only: true,
+        };
+
+        let parser = KafkaProtocolParser::new();
+        for api_version in [2, 5, 7] {
+            match parser.parse_request(&encode_request(&request, api_version))?.payload {
+                RequestPayload::CreateTopics(req) => {
+                    assert_eq!(req.timeout_ms, 5000);
+                    assert!(req.validate_only);
+                    assert_eq!(req.topics[0].name, "foo");
+                    assert_eq!(req.topics[0].assignments, vec![CreatableReplicaAssignment { partition_index: 0, broker_ids: vec![1] }]);
+                    assert_eq!(req.topics[0].configs, vec![CreatableTopicConfig { name: "retention.ms".to_string(), value: None }]);
+                }
+                _ => panic!("Expected CreateTopics payload"),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_create_topics_response_versions() -> Result<(), ApplicationError> {
+        use crate::adapters::incoming::protocol::messages::{CreatableTopicConfigs, CreatableTopicResult};
+
+        let response = CreateTopicsResponse {
+            throttle_time_ms: 0,
+            topics: vec![
+                CreatableTopicResult {
+                    name: "foo".to_string(),
+                    topic_id: [7; 16],
+                    error_code: 0,
+                    error_message: None,
+                    num_partitions: 3,
+                    replication_factor: 1,
+                    configs: Some(vec![CreatableTopicConfigs {
+                        name: "cleanup.policy".to_string(),
+                        value: Some("compact".to_string()),
+                        read_only: false,
+                        config_source: 1,
+                        is_sensitive: false,
+                    }]),
+                },
+                CreatableTopicResult::error("bar".to_string(), 36, Some("Topic 'bar' already exists.".to_string())),
+            ],
+        };
+
+        let parser = KafkaProtocolParser::new();
+        for api_version in [2, 5, 7] {
+            let encoded = parser.encode_response(KafkaResponse::new(1, api_version, 0, ResponsePayload::CreateTopics(response.clone())));
+            let mut buf = Bytes::from(encoded[4..].to_vec());
+            response_header::ResponseHeaderData::decode(&mut buf, if api_version >= 5 { 1 } else { 0 })?;
+            let decoded = create_topics_response::CreateTopicsResponseData::decode(&mut buf, api_version)?;
+            assert!(buf.is_empty());
+            assert_eq!(decoded.topics[1].error_code, 36);
+            assert_eq!(decoded.topics[1].error_message.as_deref(), Some("Topic 'bar' already exists."));
+            assert_eq!(decoded.topics[0].topic_id, if api_version >= 7 { [7; 16] } else { [0; 16] });
+            if api_version >= 5 {
+                assert_eq!(decoded.topics[0].num_partitions, 3);
+                assert_eq!(decoded.topics[0].configs.as_ref().unwrap()[0].value.as_deref(), Some("compact"));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_delete_topics_request_versions() -> Result<(), ApplicationError> {
+        let request = delete_topics_request::DeleteTopicsRequestData {
+            topics: vec![
+                delete_topics_request::DeleteTopicState { name: None, topic_id: [7; 16] },
+                delete_topics_request::DeleteTopicState { name: Some("bar".to_string()), topic_id: [0; 16] },
+            ],
+            topic_names: vec!["foo".to_string(), "bar".to_string()],
+            timeout_ms: 5000,
+        };
+
+        let parser = KafkaProtocolParser::new();
+        for api_version in [1, 4, 5, 6] {
+            match parser.parse_request(&encode_request(&request, api_version))?.payload {
+                RequestPayload::DeleteTopics(req) => {
+                    assert_eq!(req.timeout_ms, 5000);
+                    let first = if api_version >= 6 {
+                        DeleteTopicState { name: None, topic_id: [7; 16] }
+                    } else {
+                        DeleteTopicState { name: Some("foo".to_string()), topic_id: [0; 16] }
+                    };
+                    assert_eq!(req.topics, vec![first, DeleteTopicState { name: Some("bar".to_string()), topic_id: [0; 16] }]);
+                }
+                _ => panic!("Expected DeleteTopics payload"),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_delete_topics_response_versions() -> Result<(), ApplicationError> {
+        use crate::adapters::incoming::protocol::messages::DeletableTopicResult;
+
+        let response = DeleteTopicsResponse {
+            throttle_time_ms: 0,
+            responses: vec![
+                DeletableTopicResult { name: Some("foo".to_string()), topic_id: [7; 16], error_code: 0, error_message: None },
+                DeletableTopicResult {
+                    name: Some("bar".to_string()),
+                    topic_id: [0; 16],
+                    error_code: 3,
+                    error_message: Some("This server does not host this topic-partition.".to_string()),
+                },
+            ],
+        };
+
+        let parser = KafkaProtocolParser::new();
+        for api_version in [1, 4, 5, 6] {
+            let encoded = parser.encode_response(KafkaResponse::new(1, api_version, 0, ResponsePayload::DeleteTopics(response.clone())));
+            let mut buf = Bytes::from(encoded[4..].to_vec());
+            response_header::ResponseHeaderData::decode(&mut buf, if api_version >= 4 { 1 } else { 0 })?;
+            let decoded = delete_topics_response::DeleteTopicsResponseData::decode(&mut buf, api_version)?;
+            assert!(buf.is_empty());
+            assert_eq!(decoded.responses[0].name.as_deref(), Some("foo"));
+            assert_eq!(decoded.responses[1].error_code, 3);
+            assert_eq!(decoded.responses[0].topic_id, if api_version >= 6 { [7; 16] } else { [0; 16] });
+            assert_eq!(decoded.responses[1].error_message.is_some(), api_version >= 5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_create_partitions_request_versions() -> Result<(), ApplicationError> {
+        let request = create_partitions_request::CreatePartitionsRequestData {
+            topics: vec![
+                create_partitions_request::CreatePartitionsTopic {
+                    name: "foo".to_string(),
+                    count: 3,
+                    assignments: Some(vec![create_partitions_request::CreatePartitionsAssignment { broker_ids: vec![1] }]),
+                },
+                create_partitions_request::CreatePartitionsTopic {
+                    name: "bar".to_string(),
+                    count: 5,
+                    assignments: None,
+                },
+            ],
+            timeout_ms: 5000,
+            validate_only: true,
+        };
+
+        let parser = KafkaProtocolParser::new();
+        for api_version in [0, 2, 3] {
+            match parser.parse_request(&encode_request(&request, api_version))?.payload {
+                RequestPayload::CreatePartitions(req) => {
+                    assert_eq!(req.timeout_ms, 5000);
+                    assert!(req.validate_only);
+                    assert_eq!(req.topics, vec![
+                        CreatePartitionsTopic { name: "foo".to_string(), count: 3, assignments: Some(vec![vec![1]]) },
+                        CreatePartitionsTopic { name: "bar".to_string(), count: 5, assignments: None },
+                    ]);
+                }
+                _ => panic!("Expected CreatePartitions payload"),
+            }
+        }
+
+        let response = CreatePartitionsResponse {
+            throttle_time_ms: 0,
+            results: vec![crate::adapters::incoming::protocol::messages::CreatePartitionsTopicResult {
+                name: "foo".to_string(),
+                error_code: 37,
+                error_message: Some("Topic already has 3 partition(s).".to_string()),
+            }],
+        };
+        for api_version in [0, 2, 3] {
+            let encoded = parser.encode_response(KafkaResponse::new(1, api_version, 0, ResponsePayload::CreatePartitions(response.clone())));
+            let mut buf = Bytes::from(encoded[4..].to_vec());
+            response_header::ResponseHeaderData::decode(&mut buf, if api_version >= 2 { 1 } else { 0 })?;
+            let decoded = create_partitions_response::CreatePartitionsResponseData::decode(&mut buf, api_version)?;
+            assert!(buf.is_empty());
+            assert_eq!(decoded.results[0].error_code, 37);
+            assert_eq!(decoded.results[0].error_message, response.results[0].error_message);
+        }
+        Ok(())
+    }
+
+    fn encode_request<M: ApiMessage>(body: &M, api_version: i16) -> Vec<u8> {
+        let mut data = BytesMut::new();
+        let header_version = if M::is_flexible(api_version) { 2 } else { 1 };
+        request_header::RequestHeaderData {
+            request_api_key: M::API_KEY,
+            request_api_version: api_version,
+            correlation_id: 1,
+            client_id: Some("fuzz".to_string()),
+        }.encode(&mut data, header_version);
+        body.encode(&mut data, api_version);
+        data.to_vec()
+    }
+
+    #[test]
+    fn test_parse_request_never_panics_on_corrupted_input() {
+        let fetch = fetch_request::FetchRequestData {
+            topics: vec![fetch_request::FetchTopic {
+                topic_id: [1; 16],
+                partitions: vec![fetch_request::FetchPartition { partition: 0, ..Default::default() }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let produce = produce_request::ProduceRequestData {
+            topic_data: vec![produce_request::TopicProduceData {
+                name: "foo".to_string(),
+                partition_data: vec![produce_request::PartitionProduceData {
+                    index: 0,
+                    records: Some(vec![0; 70]),
+                }],
+            }],
+            ..Default::default()
+        };
+        let requests = [
+            encode_request(&fetch, 4),
+            encode_request(&fetch, 16),
+            encode_request(&produce, 3),
+            encode_request(&produce, 9),
+            encode_request(&metadata_request::MetadataRequestData::default(), 12),
+            encode_request(&describe_topic_partitions_request::DescribeTopicPartitionsRequestData::default(), 0),
+            encode_request(&list_offsets_request::ListOffsetsRequestData::default(), 8),
+            encode_request(&create_topics_request::CreateTopicsRequestData::default(), 7),
+            encode_request(&delete_topics_request::DeleteTopicsRequestData::default(), 6),
+            encode_request(&create_partitions_request::CreatePartitionsRequestData::default(), 3),
+        ];
+
+        // 잘린 입력과 바이트가 깨진 입력 모두 에러로 끝나야 하고 panic 하면 안 됨
+        let parser = KafkaProtocolParser::new();
+        for request in &requests {
+            assert!(parser.parse_request(request).is_ok());
+            for len in 0..request.len() {
+                let _ = parser.parse_request(&request[..len]);
+            }
+            for pos in 0..request.len() {
+                for byte in [0x00, 0x7f, 0x80, 0xff] {
+                    let mut corrupted = request.clone();
+                    corrupted[pos] = byte;
+                    let _ = parser.parse_request(&corrupted);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use crate::application::error::ApplicationError;
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+/// 스키마에서 생성된 구조체를 주어진 버전으로 디코딩
+pub trait Decodable: Sized {
+    fn decode(buf: &mut Bytes, version: i16) -> Result<Self, ApplicationError>;
+}
+
+/// 스키마에서 생성된 구조체를 주어진 버전으로 인코딩
+/// 해당 버전에 없는 필드는 건너뜀
+pub trait Encodable {
+    fn encode(&self, buf: &mut BytesMut, version: i16);
+}
+
+/// 요청 / 응답 최상위 메시지 (헤더 제외)
+pub trait ApiMessage: Decodable + Encodable {
+    const API_KEY: i16;
+    const LOWEST_SUPPORTED_VERSION: i16;
+    const HIGHEST_SUPPORTED_VERSION: i16;
+
+    /// compact 인코딩 + tagged fields 를 쓰는 버전인지 여부
+    fn is_flexible(version: i16) -> bool;
+}
+
+fn ensure_remaining(buf: &Bytes, len: usize, what: &str) -> Result<(), ApplicationError> {
+    if buf.remaining() < len {
+        return Err(ApplicationError::Protocol(format!("Buffer too short for {}", what)));
+    }
+    Ok(())
+}
+
+pub fn read_bool(buf: &mut Bytes) -> Result<bool, ApplicationError> {
+    ensure_remaining(buf, 1, "boolean")?;
+    Ok(buf.get_u8() != 0)
+}
+
+pub fn read_i8(buf: &mut Bytes) -> Result<i8, ApplicationError> {
+    ensure_remaining(buf, 1, "int8")?;
+    Ok(buf.get_i8())
+}
+
+pub fn read_i16(buf: &mut Bytes) -> Result<i16, ApplicationError> {
+    ensure_remaining(buf, 2, "int16")?;
+    Ok(buf.get_i16())
+}
+
+pub fn read_u16(buf: &mut Bytes) -> Result<u16, ApplicationError> {
+    ensure_remaining(buf, 2, "uint16")?;
+    Ok(buf.get_u16())
+}
+
+pub fn read_i32(buf: &mut Bytes) -> Result<i32, ApplicationError> {
+    ensure_remaining(buf, 4, "int32")?;
+    Ok(buf.get_i32())
+}
+
+pub fn read_u32(buf: &mut Bytes) -> Result<u32, ApplicationError> {
+    ensure_remaining(buf, 4, "uint32")?;
+    Ok(buf.get_u32())
+}
+
+pub fn read_i64(buf: &mut Bytes) -> Result<i64, ApplicationError> {
+    ensure_remaining(buf, 8, "int64")?;
+    Ok(buf.get_i64())
+}
+
+pub fn read_f64(buf: &mut Bytes) -> Result<f64, ApplicationError> {
+    ensure_remaining(buf, 8, "float64")?;
+    Ok(buf.get_f64())
+}
+
+pub fn read_uuid(buf: &mut Bytes) -> Result<[u8; 16], ApplicationError> {
+    ensure_remaining(buf, 16, "uuid")?;
+    let mut uuid = [0u8; 16];
+    buf.copy_to_slice(&mut uuid);
+    Ok(uuid)
+}
+
+pub fn read_unsigned_varint(buf: &mut Bytes) -> Result<u32, ApplicationError> {
+    let mut result: u32 = 0;
+    for shift in (0..35).step_by(7) {
+        ensure_remaining(buf, 1, "varint")?;
+        let byte = buf.get_u8();
+        result |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
+    Err(ApplicationError::Protocol("Varint too long".to_string()))
+}
+
+/// STRING / BYTES / ARRAY 의 길이. null (-1 / COMPACT 0) 이면 None
+/// 일반 인코딩은 `width` 바이트 (STRING 은 2, 나머지는 4), COMPACT 는 UNSIGNED_VARINT (길이 + 1)
+fn read_length(buf: &mut Bytes, flexible: bool, width: usize) -> Result<Option<usize>, ApplicationError> {
+    let len = match (flexible, width) {
+        (true, _) => read_unsigned_varint(buf)? as i64 - 1,
+        (false, 2) => read_i16(buf)? as i64,
+        (false, _) => read_i32(buf)? as i64,
+    };
+    Ok(if len < 0 { None } else { Some(len as usize) })
+}
+
+pub fn read_string(buf: &mut Bytes, flexible: bool) -> Result<Option<String>, ApplicationError> {
+    let Some(len) = read_length(buf, flexible, 2)? else {
+        return Ok(None);
+    };
+    ensure_remaining(buf, len, "string")?;
+    let bytes = buf.split_to(len);
+    String::from_utf8(bytes.to_vec())
+        .map(Some)
+        .map_err(|_| ApplicationError::Protocol("Invalid string encoding".to_string()))
+}
+
+pub fn read_bytes(buf: &mut Bytes, flexible: bool) -> Result<Option<Vec<u8>>, ApplicationError> {
+    let Some(len) = read_length(buf, flexible, 4)? else {
+        return Ok(None);
+    };
+    ensure_remaining(buf, len, "bytes")?;
+    Ok(Some(buf.split_to(len).to_vec()))
+}
+
+pub fn read_array<T>(
+    buf: &mut Bytes,
+    flexible: bool,
+    mut read_element: impl FnMut(&mut Bytes) -> Result<T, ApplicationError>,
+) -> Result<Option<Vec<T>>, ApplicationError> {
+    let Some(len) = read_length(buf, flexible, 4)? else {
+        return Ok(None);
+    };
+    // 원소는 최소 1 바이트이므로 남은 바이트보다 길 수 없음 (거대한 길이로 메모리를 잡아먹지 않도록)
+    if len > buf.remaining() {
+        return Err(ApplicationError::Protocol(format!(
+            "array of {} elements but only {} bytes remaining", len, buf.remaining()
+        )));
+    }
+    let mut elements = Vec::with_capacity(len);
+    for _ in 0..len {
+        elements.push(read_element(buf)?);
+    }
+    Ok(Some(elements))
+}
+
+/// nullable struct 는 앞에 INT8 (-1: null, 1: 존재) 이 붙음
+pub fn read_nullable_struct<T>(
+    buf: &mut Bytes,
+    read_struct: impl FnOnce(&mut Bytes) -> Result<T, ApplicationError>,
+) -> Result<Option<T>, ApplicationError> {
+    if read_i8(buf)? < 0 {
+        return Ok(None);
+    }
+    read_struct(buf).map(Some)
+}
+
+/// tagged field 하나의 값 부분 (크기 + 바이트)
+pub fn read_tagged_field(buf: &mut Bytes) -> Result<Bytes, ApplicationError> {
+    let size = read_unsigned_varint(buf)? as usize;
+    ensure_remaining(buf, size, "tagged field")?;
+    Ok(buf.split_to(size))
+}
+
+pub fn skip_tagged_fields(buf: &mut Bytes) -> Result<(), ApplicationError> {
+    let count = read_unsigned_varint(buf)?;
+    for _ in 0..count {
+        read_unsigned_varint(buf)?; // tag
+        read_tagged_field(buf)?;
+    }
+    Ok(())
+}
+
+pub fn non_null<T>(value: Option<T>, field: &str) -> Result<T, ApplicationError> {
+    value.ok_or_else(|| ApplicationError::Protocol(format!("non-nullable field {} was null", field)))
+}
+
+pub fn write_unsigned_varint(buf: &mut BytesMut, mut value: u32) {
+    while value & !0x7f != 0 {
+        buf.put_u8(((value & 0x7f) | 0x80) as u8);
+        value >>= 7;
+    }
+    buf.put_u8(value as u8);
+}
+
+fn write_length(buf: &mut BytesMut, len: Option<usize>, flexible: bool, width: usize) {
+    match (len, flexible, width) {
+        (Some(len), true, _) => write_unsigned_varint(buf, len as u32 + 1),
+        (None, true, _) => write_unsigned_varint(buf, 0),
+        (len, false, 2) => buf.put_i16(len.map_or(-1, |len| len as i16)),
+        (len, false, _) => buf.put_i32(len.map_or(-1, |len| len as i32)),
+    }
+}
+
+pub fn write_string(buf: &mut BytesMut, value: Option<&str>, flexible: bool) {
+    write_length(buf, value.map(str::len), flexible, 2);
+    if let Some(value) = value {
+        buf.put_slice(value.as_bytes());
+    }
+}
+
+pub fn write_bytes(buf: &mut BytesMut, value: Option<&[u8]>, flexible: bool) {
+    write_length(buf, value.map(<[u8]>::len), flexible, 4);
+    if let Some(value) = value {
+        buf.put_slice(value);
+    }
+}
+
+pub fn write_array<T>(
+    buf: &mut BytesMut,
+    value: Option<&[T]>,
+    flexible: bool,
+    mut write_element: impl FnMut(&mut BytesMut, &T),
+) {
+    write_length(buf, value.map(<[T]>::len), flexible, 4);
+    for element in value.unwrap_or_default() {
+        write_element(buf, element);
+    }
+}
+
+pub fn write_nullable_struct<T>(buf: &mut BytesMut, value: Option<&T>, write_struct: impl FnOnce(&mut BytesMut, &T)) {
+    match value {
+        Some(value) => {
+            buf.put_i8(1);
+            write_struct(buf, value);
+        }
+        None => buf.put_i8(-1),
+    }
+}
+
+/// (tag, 인코딩된 값) 목록을 tag 순서대로 기록. 목록은 tag 오름차순이어야 함
+pub fn write_tagged_fields(buf: &mut BytesMut, fields: &[(u32, BytesMut)]) {
+    write_unsigned_varint(buf, fields.len() as u32);
+    for (tag, value) in fields {
+        write_unsigned_varint(buf, *tag);
+        write_unsigned_varint(buf, value.len() as u32);
+        buf.put_slice(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_and_legacy_lengths_round_trip() -> Result<(), ApplicationError> {
+        let mut buf = BytesMut::new();
+        write_string(&mut buf, Some("foo"), true);
+        write_string(&mut buf, None, false);
+        write_array(&mut buf, Some(&[1i32, 2][..]), false, |buf, v| buf.put_i32(*v));
+        write_bytes(&mut buf, Some(&[7u8; 200][..]), true);
+        write_tagged_fields(&mut buf, &[(3, BytesMut::from(&[1u8][..]))]);
+        assert_eq!(&buf[..4], &[4, b'f', b'o', b'o']);
+
+        let mut buf = buf.freeze();
+        assert_eq!(read_string(&mut buf, true)?, Some("foo".to_string()));
+        assert_eq!(read_string(&mut buf, false)?, None);
+        assert_eq!(read_array(&mut buf, false, read_i32)?, Some(vec![1, 2]));
+        assert_eq!(read_bytes(&mut buf, true)?, Some(vec![7u8; 200]));
+        skip_tagged_fields(&mut buf)?;
+        assert!(buf.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_truncated_input_is_an_error() {
+        let mut buf = Bytes::from_static(&[0, 5, b'a']);
+        assert!(read_string(&mut buf, false).is_err());
+
+        // 남은 바이트보다 긴 배열은 읽기 전에 거부
+        let mut buf = Bytes::from_static(&[0x7f, 0xff, 0xff, 0xff]);
+        assert!(read_array(&mut buf, false, read_i32).is_err());
+    }
+}
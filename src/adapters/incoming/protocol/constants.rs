@@ -12,6 +12,92 @@ pub const MAX_SUPPORTED_VERSION: i16 = 4;
 pub const API_VERSIONS_KEY: i16 = 18;
 pub const FETCH_KEY: i16 = 1;
 
+/// Fetch API는 v0 부터 v16 까지 지원
+/// v4 미만 클라이언트는 magic v0 (v0~v1) / v1 (v2~v3) 메시지 셋만 읽을 수 있어서 변환해서 응답함
+/// v13 부터 토픽을 이름 대신 topic_id 로 지정함
+pub const FETCH_MIN_VERSION: i16 = 0;
+pub const FETCH_MAX_VERSION: i16 = 16;
+pub const FETCH_RECORD_BATCH_VERSION: i16 = 4;
+pub const FETCH_TOPIC_ID_VERSION: i16 = 13;
+
+/// v4 미만 Fetch 를 위해 한 파티션에서 읽어서 변환하는 최대 바이트
+/// 변환 결과를 통째로 메모리에 만들어야 하므로 partition_max_bytes 가 커도 이만큼만 변환
+pub const DOWN_CONVERSION_MAX_BYTES: i32 = 1024 * 1024;
+
+/// Produce API의 키 값
+/// 프로듀서가 레코드 배치를 파티션 로그에 추가할 때 사용함
+pub const PRODUCE_KEY: i16 = 0;
+
+/// Produce API는 v0 부터 v11 까지 지원
+/// v3 미만은 magic v0 / v1 메시지 셋을 보내고, 로그에는 RecordBatch v2 로 바꿔서 저장함
+/// v9 부터 flexible version (compact 인코딩 + tagged fields)
+pub const PRODUCE_MIN_VERSION: i16 = 0;
+pub const PRODUCE_MAX_VERSION: i16 = 11;
+
+/// ListOffsets API의 키 값
+/// 컨슈머가 첫 Fetch 전에 earliest / latest / timestamp 에 해당하는 offset 을 찾을 때 사용함
+pub const LIST_OFFSETS_KEY: i16 = 2;
+
+/// ListOffsets API는 v1 부터 v8 까지 지원
+/// v6 부터 flexible version, v7 부터 max_timestamp (-3) 조회
+pub const LIST_OFFSETS_MIN_VERSION: i16 = 1;
+pub const LIST_OFFSETS_MAX_VERSION: i16 = 8;
+
+/// ListOffsets 의 특수 timestamp 값
+pub const LATEST_TIMESTAMP: i64 = -1;
+pub const EARLIEST_TIMESTAMP: i64 = -2;
+pub const MAX_TIMESTAMP: i64 = -3;
+pub const EARLIEST_LOCAL_TIMESTAMP: i64 = -4;
+
+/// Metadata API의 키 값
+/// 클라이언트가 부트스트랩 시 브로커, 토픽, 파티션 리더 정보를 조회할 때 사용함
+pub const METADATA_KEY: i16 = 3;
+
+/// Metadata API는 v0 부터 v12 까지 지원
+/// v9 부터 flexible version, v10 부터 topic_id 포함
+pub const METADATA_MIN_VERSION: i16 = 0;
+pub const METADATA_MAX_VERSION: i16 = 12;
+
+/// CreateTopics API의 키 값
+/// 관리 클라이언트가 파티션 수, 복제 계수, 토픽 설정을 지정해서 토픽을 만들 때 사용함
+pub const CREATE_TOPICS_KEY: i16 = 19;
+
+/// CreateTopics API는 v2 부터 v7 까지 지원
+/// v5 부터 flexible version 이고 응답에 토픽 설정이 들어감, v7 부터 응답에 topic_id 포함
+pub const CREATE_TOPICS_MIN_VERSION: i16 = 2;
+pub const CREATE_TOPICS_MAX_VERSION: i16 = 7;
+
+/// DeleteTopics API의 키 값
+/// 관리 클라이언트가 토픽 이름 또는 토픽 ID 로 토픽을 지울 때 사용함
+pub const DELETE_TOPICS_KEY: i16 = 20;
+
+/// DeleteTopics API는 v1 부터 v6 까지 지원
+/// v4 부터 flexible version, v5 부터 응답에 error_message, v6 부터 토픽 ID 로 지정 가능
+pub const DELETE_TOPICS_MIN_VERSION: i16 = 1;
+pub const DELETE_TOPICS_MAX_VERSION: i16 = 6;
+
+/// CreatePartitions API의 키 값
+/// 관리 클라이언트가 기존 토픽의 파티션 수를 늘릴 때 사용함
+pub const CREATE_PARTITIONS_KEY: i16 = 37;
+
+/// CreatePartitions API는 v0 부터 v3 까지 지원
+/// v2 부터 flexible version
+pub const CREATE_PARTITIONS_MIN_VERSION: i16 = 0;
+pub const CREATE_PARTITIONS_MAX_VERSION: i16 = 3;
+
+/// CreateTopics 에서 replication_factor 가 -1 일 때 쓰는 복제 계수 (default.replication.factor)
+pub const DEFAULT_REPLICATION_FACTOR: i16 = 1;
+
+/// DescribeConfigs / CreateTopics 응답의 config_source: 토픽에 직접 지정한 설정
+pub const DYNAMIC_TOPIC_CONFIG_SOURCE: i8 = 1;
+
+/// 자동 생성되는 토픽의 파티션 수 (num.partitions 기본값)
+pub const DEFAULT_NUM_PARTITIONS: u32 = 1;
+
+/// 토픽 하나가 가질 수 있는 최대 파티션 수
+/// 파티션마다 메타데이터 레코드와 디렉토리가 생기므로 요청 하나로 브로커 메모리나 디스크를 다 쓰지 못하게 막음
+pub const MAX_PARTITIONS_PER_TOPIC: i32 = 10_000;
+
 /// DescribeTopicPartitions API의 키 값
 /// Kafka 프로토콜에서 정의된 표준 API 키임
 /// 클라이언트가 브로커가 지원하는 API 버전을 조회할 때 사용함
@@ -21,5 +107,9 @@ pub const DESCRIBE_TOPIC_PARTITIONS_KEY: i16 = 75;
 pub const DESCRIBE_TOPIC_PARTITIONS_MAX_VERSION: i16 = 0;
 pub const DESCRIBE_TOPIC_PARTITIONS_MIN_VERSION: i16 = 0;
 
+/// DescribeTopicPartitions 응답의 topic_authorized_operations
+/// READ, WRITE, CREATE, DELETE, ALTER, DESCRIBE, DESCRIBE_CONFIGS, ALTER_CONFIGS 비트
+pub const DEFAULT_TOPIC_AUTHORIZED_OPERATIONS: i32 = 0x0df8;
+
 /// Error codes
 pub const UNKNOWN_TOPIC_OR_PARTITION: i16 = 3; 
\ No newline at end of file
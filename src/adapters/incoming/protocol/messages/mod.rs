@@ -1,7 +1,9 @@
 mod common;
+mod error_code;
 mod request;
 mod response;
 
 pub use common::*;
+pub use error_code::*;
 pub use request::*;
 pub use response::*; 
\ No newline at end of file
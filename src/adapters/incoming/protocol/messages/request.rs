@@ -1,7 +1,27 @@
 use crate::adapters::incoming::protocol::constants::{
     API_VERSIONS_KEY,
+    CREATE_PARTITIONS_KEY,
+    CREATE_PARTITIONS_MAX_VERSION,
+    CREATE_PARTITIONS_MIN_VERSION,
+    CREATE_TOPICS_KEY,
+    CREATE_TOPICS_MAX_VERSION,
+    CREATE_TOPICS_MIN_VERSION,
+    DELETE_TOPICS_KEY,
+    DELETE_TOPICS_MAX_VERSION,
+    DELETE_TOPICS_MIN_VERSION,
     DESCRIBE_TOPIC_PARTITIONS_KEY,
     FETCH_KEY,
+    FETCH_MAX_VERSION,
+    FETCH_MIN_VERSION,
+    LIST_OFFSETS_KEY,
+    LIST_OFFSETS_MAX_VERSION,
+    LIST_OFFSETS_MIN_VERSION,
+    METADATA_KEY,
+    METADATA_MAX_VERSION,
+    METADATA_MIN_VERSION,
+    PRODUCE_KEY,
+    PRODUCE_MAX_VERSION,
+    PRODUCE_MIN_VERSION,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -16,8 +36,14 @@ impl RequestHeader {
     pub fn is_supported_version(&self) -> bool {
         match self.api_key {
             API_VERSIONS_KEY => self.api_version >= 0 && self.api_version <= 4,
-            FETCH_KEY => self.api_version == 16,
+            FETCH_KEY => (FETCH_MIN_VERSION..=FETCH_MAX_VERSION).contains(&self.api_version),
             DESCRIBE_TOPIC_PARTITIONS_KEY => self.api_version == 0,
+            PRODUCE_KEY => (PRODUCE_MIN_VERSION..=PRODUCE_MAX_VERSION).contains(&self.api_version),
+            METADATA_KEY => (METADATA_MIN_VERSION..=METADATA_MAX_VERSION).contains(&self.api_version),
+            LIST_OFFSETS_KEY => (LIST_OFFSETS_MIN_VERSION..=LIST_OFFSETS_MAX_VERSION).contains(&self.api_version),
+            CREATE_TOPICS_KEY => (CREATE_TOPICS_MIN_VERSION..=CREATE_TOPICS_MAX_VERSION).contains(&self.api_version),
+            DELETE_TOPICS_KEY => (DELETE_TOPICS_MIN_VERSION..=DELETE_TOPICS_MAX_VERSION).contains(&self.api_version),
+            CREATE_PARTITIONS_KEY => (CREATE_PARTITIONS_MIN_VERSION..=CREATE_PARTITIONS_MAX_VERSION).contains(&self.api_version),
             _ => false,
         }
     }
@@ -36,6 +62,8 @@ pub struct DescribeTopicPartitionsRequest {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct FetchRequest {
+    /// 컨슈머는 -1, 팔로워는 broker id (v15 부터는 replica_state 에 들어있음)
+    pub replica_id: i32,
     pub max_wait_ms: i32,
     pub min_bytes: i32,
     pub max_bytes: i32,
@@ -43,10 +71,14 @@ pub struct FetchRequest {
     pub session_id: i32,
     pub session_epoch: i32,
     pub topics: Vec<FetchTopic>,
+    pub forgotten_topics_data: Vec<ForgottenTopic>,
+    pub rack_id: String,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct FetchTopic {
+    /// v12 까지는 이름으로, v13 부터는 topic_id 로 토픽을 지정 (다른 쪽은 비어있음)
+    pub topic: String,
     pub topic_id: [u8; 16],
     pub partitions: Vec<FetchPartition>,
 }
@@ -63,15 +95,146 @@ pub struct FetchPartition {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ForgottenTopic {
+    pub topic: String,
     pub topic_id: [u8; 16],
     pub partitions: Vec<i32>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProduceRequest {
+    pub transactional_id: Option<String>,
+    pub acks: i16,
+    pub timeout_ms: i32,
+    pub topics: Vec<ProduceTopic>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProduceTopic {
+    pub name: String,
+    pub partitions: Vec<ProducePartition>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProducePartition {
+    pub index: i32,
+    pub records: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataRequest {
+    /// None 이면 모든 토픽을 조회
+    pub topics: Option<Vec<MetadataRequestTopic>>,
+    pub allow_auto_topic_creation: bool,
+    pub include_cluster_authorized_operations: bool,
+    pub include_topic_authorized_operations: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataRequestTopic {
+    /// v10 미만이거나 이름으로 조회하는 경우 0
+    pub topic_id: [u8; 16],
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListOffsetsRequest {
+    pub replica_id: i32,
+    /// 0: READ_UNCOMMITTED, 1: READ_COMMITTED (v2+)
+    pub isolation_level: i8,
+    pub topics: Vec<ListOffsetsTopic>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListOffsetsTopic {
+    pub name: String,
+    pub partitions: Vec<ListOffsetsPartition>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListOffsetsPartition {
+    pub partition_index: i32,
+    /// v4 미만이면 -1
+    pub current_leader_epoch: i32,
+    /// -1: latest, -2: earliest, -3: max timestamp, -4: earliest local, 그 외: 이 timestamp 이상인 첫 레코드
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateTopicsRequest {
+    pub topics: Vec<CreatableTopic>,
+    pub timeout_ms: i32,
+    /// true 면 검증만 하고 토픽을 만들지 않음
+    pub validate_only: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatableTopic {
+    pub name: String,
+    /// -1 이면 num.partitions 또는 assignments 의 파티션 수
+    pub num_partitions: i32,
+    /// -1 이면 기본 복제 계수 또는 assignments 의 replica 수
+    pub replication_factor: i16,
+    /// 비어있지 않으면 파티션마다 replica 를 직접 지정
+    pub assignments: Vec<CreatableReplicaAssignment>,
+    pub configs: Vec<CreatableTopicConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatableReplicaAssignment {
+    pub partition_index: i32,
+    pub broker_ids: Vec<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatableTopicConfig {
+    pub name: String,
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeleteTopicsRequest {
+    pub topics: Vec<DeleteTopicState>,
+    pub timeout_ms: i32,
+}
+
+/// 지울 토픽. v6 미만은 이름만 있고 topic_id 는 0
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeleteTopicState {
+    /// v6 부터 None 이면 topic_id 로 찾음
+    pub name: Option<String>,
+    pub topic_id: [u8; 16],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatePartitionsRequest {
+    pub topics: Vec<CreatePartitionsTopic>,
+    pub timeout_ms: i32,
+    /// true 면 검증만 하고 파티션을 늘리지 않음
+    pub validate_only: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatePartitionsTopic {
+    pub name: String,
+    /// 늘린 뒤의 전체 파티션 수
+    pub count: i32,
+    /// 새 파티션마다 replica 를 직접 지정. None 이면 브로커가 정함
+    pub assignments: Option<Vec<Vec<i32>>>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum RequestPayload {
     ApiVersions,
     DescribeTopicPartitions(DescribeTopicPartitionsRequest),
     Fetch(FetchRequest),
+    Produce(ProduceRequest),
+    Metadata(MetadataRequest),
+    ListOffsets(ListOffsetsRequest),
+    CreateTopics(CreateTopicsRequest),
+    DeleteTopics(DeleteTopicsRequest),
+    CreatePartitions(CreatePartitionsRequest),
+    /// 스키마에 없는 버전이라 body 를 해석하지 않은 요청 (UNSUPPORTED_VERSION 응답용)
+    Unsupported,
 }
 
 #[derive(Debug, Clone)]
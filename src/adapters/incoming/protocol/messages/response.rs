@@ -1,7 +1,27 @@
 use crate::adapters::incoming::protocol::constants::{
     API_VERSIONS_KEY,
+    CREATE_PARTITIONS_KEY,
+    CREATE_PARTITIONS_MAX_VERSION,
+    CREATE_PARTITIONS_MIN_VERSION,
+    CREATE_TOPICS_KEY,
+    CREATE_TOPICS_MAX_VERSION,
+    CREATE_TOPICS_MIN_VERSION,
+    DELETE_TOPICS_KEY,
+    DELETE_TOPICS_MAX_VERSION,
+    DELETE_TOPICS_MIN_VERSION,
     DESCRIBE_TOPIC_PARTITIONS_KEY,
     FETCH_KEY,
+    FETCH_MAX_VERSION,
+    FETCH_MIN_VERSION,
+    LIST_OFFSETS_KEY,
+    LIST_OFFSETS_MAX_VERSION,
+    LIST_OFFSETS_MIN_VERSION,
+    METADATA_KEY,
+    METADATA_MAX_VERSION,
+    METADATA_MIN_VERSION,
+    PRODUCE_KEY,
+    PRODUCE_MAX_VERSION,
+    PRODUCE_MIN_VERSION,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -20,18 +40,50 @@ impl ApiVersionsResponse {
     pub fn new(api_versions: Vec<ApiVersion>) -> Self {
         Self { api_versions }
     }
+}
 
-    pub fn default() -> Self {
+impl Default for ApiVersionsResponse {
+    fn default() -> Self {
         Self::new(vec![
+            ApiVersion {
+                api_key: PRODUCE_KEY,
+                min_version: PRODUCE_MIN_VERSION,
+                max_version: PRODUCE_MAX_VERSION,
+            },
+            ApiVersion {
+                api_key: LIST_OFFSETS_KEY,
+                min_version: LIST_OFFSETS_MIN_VERSION,
+                max_version: LIST_OFFSETS_MAX_VERSION,
+            },
+            ApiVersion {
+                api_key: METADATA_KEY,
+                min_version: METADATA_MIN_VERSION,
+                max_version: METADATA_MAX_VERSION,
+            },
             ApiVersion {
                 api_key: API_VERSIONS_KEY,
                 min_version: 0,
                 max_version: 4,
             },
+            ApiVersion {
+                api_key: CREATE_TOPICS_KEY,
+                min_version: CREATE_TOPICS_MIN_VERSION,
+                max_version: CREATE_TOPICS_MAX_VERSION,
+            },
+            ApiVersion {
+                api_key: DELETE_TOPICS_KEY,
+                min_version: DELETE_TOPICS_MIN_VERSION,
+                max_version: DELETE_TOPICS_MAX_VERSION,
+            },
+            ApiVersion {
+                api_key: CREATE_PARTITIONS_KEY,
+                min_version: CREATE_PARTITIONS_MIN_VERSION,
+                max_version: CREATE_PARTITIONS_MAX_VERSION,
+            },
             ApiVersion {
                 api_key: FETCH_KEY,
-                min_version: 0,
-                max_version: 16,
+                min_version: FETCH_MIN_VERSION,
+                max_version: FETCH_MAX_VERSION,
             },
             ApiVersion {
                 api_key: DESCRIBE_TOPIC_PARTITIONS_KEY,
@@ -60,6 +112,13 @@ pub struct DescribeTopicPartitionsResponse {
 pub struct PartitionInfo {
     pub partition_id: i32,
     pub error_code: i16,
+    pub leader_id: i32,
+    pub leader_epoch: i32,
+    pub replica_nodes: Vec<i32>,
+    pub isr_nodes: Vec<i32>,
+    pub eligible_leader_replicas: Vec<i32>,
+    pub last_known_elr: Vec<i32>,
+    pub offline_replicas: Vec<i32>,
 }
 
 impl DescribeTopicPartitionsResponse {
@@ -81,10 +140,14 @@ pub struct FetchResponse {
     pub throttle_time_ms: i32,
     pub session_id: i32,
     pub responses: Vec<FetchableTopicResponse>,
+    /// current_leader 로 알려준 리더들의 접속 정보 (v16+)
+    pub node_endpoints: Vec<MetadataResponseBroker>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct FetchableTopicResponse {
+    /// 요청에서 토픽을 지정한 방식 (이름 / topic_id) 그대로 돌려줌
+    pub topic: String,
     pub topic_id: [u8; 16],
     pub partitions: Vec<FetchablePartitionResponse>,
 }
@@ -94,77 +157,257 @@ pub struct FetchablePartitionResponse {
     pub partition_index: i32,
     pub error_code: i16,
     pub high_watermark: i64,
+    pub last_stable_offset: i64,
+    pub log_start_offset: i64,
+    /// READ_COMMITTED (v4+) 에서만 목록, 아니면 null
+    pub aborted_transactions: Option<Vec<AbortedTransaction>>,
+    pub preferred_read_replica: i32,
+    /// 팔로워의 last_fetched_epoch 가 리더의 epoch 기록과 어긋날 때 (v12+)
+    pub diverging_epoch: Option<EpochEndOffset>,
+    /// FENCED_LEADER_EPOCH 일 때 알려주는 현재 리더 (v12+)
+    pub current_leader: Option<LeaderIdAndEpoch>,
     pub records: Option<Vec<u8>>,
 }
 
-impl FetchResponse {
-    pub fn empty() -> Self {
+impl FetchablePartitionResponse {
+    pub fn error(partition_index: i32, error_code: i16) -> Self {
         Self {
-            throttle_time_ms: 0,
-            session_id: 0,
-            responses: vec![],
+            partition_index,
+            error_code,
+            high_watermark: -1,
+            last_stable_offset: -1,
+            log_start_offset: -1,
+            aborted_transactions: None,
+            preferred_read_replica: -1,
+            diverging_epoch: None,
+            current_leader: None,
+            records: None,
         }
     }
+}
 
-    pub fn empty_topic(topic_id: [u8; 16]) -> Self {
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbortedTransaction {
+    pub producer_id: i64,
+    pub first_offset: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EpochEndOffset {
+    pub epoch: i32,
+    pub end_offset: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LeaderIdAndEpoch {
+    pub leader_id: i32,
+    pub leader_epoch: i32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProduceResponse {
+    pub responses: Vec<ProduceTopicResponse>,
+    pub throttle_time_ms: i32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProduceTopicResponse {
+    pub name: String,
+    pub partitions: Vec<ProducePartitionResponse>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProducePartitionResponse {
+    pub index: i32,
+    pub error_code: i16,
+    pub base_offset: i64,
+    pub log_append_time_ms: i64,
+    pub log_start_offset: i64,
+}
+
+impl ProducePartitionResponse {
+    pub fn error(index: i32, error_code: i16) -> Self {
         Self {
-            throttle_time_ms: 0,
-            session_id: 0,
-            responses: vec![
-                FetchableTopicResponse {
-                    topic_id,
-                    partitions: vec![
-                        FetchablePartitionResponse {
-                            partition_index: 0,
-                            error_code: 0,  // No Error
-                            high_watermark: 0,
-                            records: None,
-                        }
-                    ],
-                }
-            ],
+            index,
+            error_code,
+            base_offset: -1,
+            log_append_time_ms: -1,
+            log_start_offset: -1,
         }
     }
+}
 
-    pub fn unknown_topic(topic_id: [u8; 16]) -> Self {
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataResponse {
+    pub throttle_time_ms: i32,
+    pub brokers: Vec<MetadataResponseBroker>,
+    pub cluster_id: Option<String>,
+    pub controller_id: i32,
+    pub topics: Vec<MetadataResponseTopic>,
+    pub cluster_authorized_operations: i32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataResponseBroker {
+    pub node_id: i32,
+    pub host: String,
+    pub port: i32,
+    pub rack: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataResponseTopic {
+    pub error_code: i16,
+    pub name: Option<String>,
+    pub topic_id: [u8; 16],
+    pub is_internal: bool,
+    pub partitions: Vec<MetadataResponsePartition>,
+    pub topic_authorized_operations: i32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataResponsePartition {
+    pub error_code: i16,
+    pub partition_index: i32,
+    pub leader_id: i32,
+    pub leader_epoch: i32,
+    pub replica_nodes: Vec<i32>,
+    pub isr_nodes: Vec<i32>,
+    pub offline_replicas: Vec<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListOffsetsResponse {
+    pub throttle_time_ms: i32,
+    pub topics: Vec<ListOffsetsTopicResponse>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListOffsetsTopicResponse {
+    pub name: String,
+    pub partitions: Vec<ListOffsetsPartitionResponse>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListOffsetsPartitionResponse {
+    pub partition_index: i32,
+    pub error_code: i16,
+    pub timestamp: i64,
+    pub offset: i64,
+    pub leader_epoch: i32,
+}
+
+impl ListOffsetsPartitionResponse {
+    pub fn error(partition_index: i32, error_code: i16) -> Self {
+        Self {
+            partition_index,
+            error_code,
+            timestamp: -1,
+            offset: -1,
+            leader_epoch: -1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateTopicsResponse {
+    pub throttle_time_ms: i32,
+    pub topics: Vec<CreatableTopicResult>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatableTopicResult {
+    pub name: String,
+    /// 만들어진 토픽의 ID (v7+). 실패했거나 validate_only 면 0
+    pub topic_id: [u8; 16],
+    pub error_code: i16,
+    pub error_message: Option<String>,
+    pub num_partitions: i32,
+    pub replication_factor: i16,
+    /// 토픽에 지정된 설정 (v5+). 실패하면 None
+    pub configs: Option<Vec<CreatableTopicConfigs>>,
+}
+
+impl CreatableTopicResult {
+    pub fn error(name: String, error_code: i16, error_message: Option<String>) -> Self {
         Self {
-            throttle_time_ms: 0,
-            session_id: 0,
-            responses: vec![
-                FetchableTopicResponse {
-                    topic_id,
-                    partitions: vec![
-                        FetchablePartitionResponse {
-                            partition_index: 0,
-                            error_code: 100, // UNKNOWN_TOPIC
-                            high_watermark: 0,
-                            records: None,
-                        }
-                    ],
-                }
-            ],
+            name,
+            topic_id: [0; 16],
+            error_code,
+            error_message,
+            num_partitions: -1,
+            replication_factor: -1,
+            configs: None,
         }
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatableTopicConfigs {
+    pub name: String,
+    pub value: Option<String>,
+    pub read_only: bool,
+    /// 1: DYNAMIC_TOPIC_CONFIG, 5: DEFAULT_CONFIG
+    pub config_source: i8,
+    pub is_sensitive: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeleteTopicsResponse {
+    pub throttle_time_ms: i32,
+    pub responses: Vec<DeletableTopicResult>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeletableTopicResult {
+    /// 토픽 ID 로 요청했는데 토픽이 없으면 None (v6+)
+    pub name: Option<String>,
+    /// v6+
+    pub topic_id: [u8; 16],
+    pub error_code: i16,
+    /// v5+
+    pub error_message: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatePartitionsResponse {
+    pub throttle_time_ms: i32,
+    pub results: Vec<CreatePartitionsTopicResult>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatePartitionsTopicResult {
+    pub name: String,
+    pub error_code: i16,
+    pub error_message: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ResponsePayload {
     ApiVersions(ApiVersionsResponse),
     DescribeTopicPartitions(DescribeTopicPartitionsResponse),
     Fetch(FetchResponse),
+    Produce(ProduceResponse),
+    Metadata(MetadataResponse),
+    ListOffsets(ListOffsetsResponse),
+    CreateTopics(CreateTopicsResponse),
+    DeleteTopics(DeleteTopicsResponse),
+    CreatePartitions(CreatePartitionsResponse),
 }
 
 #[derive(Debug, Clone)]
 pub struct KafkaResponse {
     pub correlation_id: i32,
+    pub api_version: i16,
     pub error_code: i16,
     pub payload: ResponsePayload,
 }
 
 impl KafkaResponse {
-    pub fn new(correlation_id: i32, error_code: i16, payload: ResponsePayload) -> Self {
+    pub fn new(correlation_id: i32, api_version: i16, error_code: i16, payload: ResponsePayload) -> Self {
         Self {
             correlation_id,
+            api_version,
             error_code,
             payload,
         }
@@ -1,2 +1,4 @@
+pub mod codec;
 pub mod constants;
-pub mod messages;
\ No newline at end of file
+pub mod messages;
+pub mod schema;
@@ -0,0 +1,6 @@
+//! schemas/*.json 에서 build.rs 가 생성한 요청 / 응답 타입
+//! 스키마 파일마다 모듈 하나 (예: FetchRequest.json -> `fetch_request::FetchRequestData`)
+
+#![allow(clippy::all, dead_code, unused_imports, unused_mut, unused_variables)]
+
+include!(concat!(env!("OUT_DIR"), "/messages.rs"));
@@ -1,15 +1,22 @@
-use crate::ports::incoming::message_handler::MessageHandler;
+use crate::ports::incoming::message_handler::{MessageHandler, ParkSignal};
 use crate::adapters::incoming::kafka_protocol_parser::KafkaProtocolParser;
 use crate::application::ApplicationError;
+use crate::adapters::incoming::protocol::messages::KafkaResponse;
 use crate::Result;
 use std::sync::Arc;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// socket.request.max.bytes 기본값 (Kafka 와 동일하게 100 MiB)
+pub const DEFAULT_SOCKET_REQUEST_MAX_BYTES: i32 = 100 * 1024 * 1024;
 
 pub struct TcpAdapter {
     listener: TcpListener,
     message_handler: Arc<dyn MessageHandler>,
     protocol_parser: KafkaProtocolParser,
+    socket_request_max_bytes: i32,
 }
 
 impl TcpAdapter {
@@ -17,26 +24,29 @@ impl TcpAdapter {
         addr: &str, 
         message_handler: Arc<dyn MessageHandler>,
         protocol_parser: KafkaProtocolParser,
+        socket_request_max_bytes: i32,
     ) -> Result<Self> {
         let listener = TcpListener::bind(addr).await.map_err(ApplicationError::Io)?;
         Ok(Self { 
             listener,
             message_handler,
             protocol_parser,
+            socket_request_max_bytes,
         })
     }
 
     pub async fn run(&self) -> Result<()> {
-        println!("Server listening on port 9092");
+        println!("Server listening on {}", self.listener.local_addr().map_err(ApplicationError::Io)?);
         
         loop {
             match self.listener.accept().await {
                 Ok((stream, _)) => {
                     let message_handler = Arc::clone(&self.message_handler);
                     let protocol_parser = self.protocol_parser.clone();
+                    let socket_request_max_bytes = self.socket_request_max_bytes;
                     
                     tokio::spawn(async move {
-                        if let Err(e) = handle_connection(stream, message_handler, protocol_parser).await {
+                        if let Err(e) = handle_connection(stream, message_handler, protocol_parser, socket_request_max_bytes).await {
                             println!("Connection error: {}", e);
                         }
                     });
@@ -47,37 +57,192 @@ impl TcpAdapter {
     }
 }
 
+/// 한 커넥션에서 응답을 기다리는 최대 요청 수
+const MAX_IN_FLIGHT_REQUESTS: usize = 100;
+
+/// 요청은 Kafka 처럼 커넥션마다 하나씩 순서대로 처리함
+/// purgatory 에 걸린 Fetch 만 writer 가 기다리게 하고 다음 요청으로 넘어가서, long-poll 이 다른 요청을 막지 않게 함
+/// 응답은 writer 태스크가 요청 순서대로 내보냄 (클라이언트는 순서대로 응답을 기대함)
 async fn handle_connection(
-    mut stream: TcpStream,
+    stream: TcpStream,
     message_handler: Arc<dyn MessageHandler>,
     protocol_parser: KafkaProtocolParser,
+    socket_request_max_bytes: i32,
 ) -> Result<()> {
     println!("Accepted new connection");
-    
-    loop {
-        // 1. 요청 크기 읽기
-        let mut size_bytes = [0u8; 4];
-        if let Err(e) = stream.read_exact(&mut size_bytes).await {
-            if e.kind() == std::io::ErrorKind::UnexpectedEof {
-                println!("Client closed connection");
+
+    let (mut reader, mut writer) = stream.into_split();
+    let (pending_tx, mut pending_rx) = mpsc::channel::<JoinHandle<Result<Option<KafkaResponse>>>>(MAX_IN_FLIGHT_REQUESTS);
+
+    let response_parser = protocol_parser.clone();
+    let writer_task = tokio::spawn(async move {
+        while let Some(pending) = pending_rx.recv().await {
+            let response = pending.await
+                .map_err(|e| ApplicationError::Io(std::io::Error::other(e)))??;
+
+            // 응답 인코딩 및 전송 (acks=0 Produce 는 응답 없음)
+            if let Some(response) = response {
+                let encoded = response_parser.encode_response(response);
+                writer.write_all(&encoded).await.map_err(ApplicationError::Io)?;
+            }
+        }
+        Ok::<(), ApplicationError>(())
+    });
+
+    let read_result = async {
+        loop {
+            // 1. 요청 크기 읽기
+            let mut size_bytes = [0u8; 4];
+            if let Err(e) = reader.read_exact(&mut size_bytes).await {
+                if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                    println!("Client closed connection");
+                    return Ok(());
+                }
+                return Err(ApplicationError::Io(e));
+            }
+            let message_size = i32::from_be_bytes(size_bytes);
+            let message_size = check_message_size(message_size, socket_request_max_bytes)?;
+
+            // 2. 요청 데이터 읽기
+            let mut request_data = vec![0; message_size];
+            reader.read_exact(&mut request_data).await.map_err(ApplicationError::Io)?;
+
+            // 3. 프로토콜 파싱
+            let request = protocol_parser.parse_request(&request_data)?;
+
+            // 4. 비즈니스 로직 처리. 끝나거나 purgatory 에 걸릴 때까지 다음 요청을 읽지 않음
+            let message_handler = Arc::clone(&message_handler);
+            let (parked, ready) = ParkSignal::new();
+            let pending = tokio::spawn(async move { message_handler.handle_parkable_request(request, parked).await });
+            let _ = ready.await;
+            if pending_tx.send(pending).await.is_err() {
+                // writer 가 에러로 끝났음
                 return Ok(());
             }
-            return Err(ApplicationError::Io(e));
         }
-        let message_size = i32::from_be_bytes(size_bytes);
-        
-        // 2. 요청 데이터 읽기
-        let mut request_data = vec![0; message_size as usize];
-        stream.read_exact(&mut request_data).await.map_err(ApplicationError::Io)?;
-        
-        // 3. 프로토콜 파싱
-        let request = protocol_parser.parse_request(&request_data)?;
-        
-        // 4. 비즈니스 로직 처리
-        let response = message_handler.handle_request(request).await?;
-        
-        // 5. 응답 인코딩 및 전송
-        let encoded = protocol_parser.encode_response(response);
-        stream.write_all(&encoded).await.map_err(ApplicationError::Io)?;
+    }.await;
+
+    // 읽기가 끝나면 남은 응답을 모두 보낸 뒤 종료
+    drop(pending_tx);
+    let write_result = writer_task.await
+        .map_err(|e| ApplicationError::Io(std::io::Error::other(e)))?;
+    read_result.and(write_result)
+}
+
+/// 음수이거나 socket.request.max.bytes 를 넘는 크기는 거부 (Kafka 처럼 커넥션을 닫음)
+fn check_message_size(message_size: i32, max_bytes: i32) -> Result<usize> {
+    if message_size < 0 || message_size > max_bytes {
+        return Err(ApplicationError::Protocol(format!(
+            "Invalid request size {} (socket.request.max.bytes = {})", message_size, max_bytes
+        )));
     }
-} 
\ No newline at end of file
+    Ok(message_size as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::incoming::protocol::constants::PRODUCE_KEY;
+    use crate::adapters::incoming::protocol::messages::{
+        KafkaRequest, ProducePartitionResponse, ProduceResponse, ProduceTopicResponse, RequestPayload, ResponsePayload,
+    };
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    /// 파티션 하나짜리 로그. 첫 요청은 append 전에 잠깐 멈춰서, 동시에 처리되면 offset 이 꼬이게 함
+    struct SlowLogHandler {
+        end_offset: Mutex<i64>,
+    }
+
+    #[async_trait::async_trait]
+    impl MessageHandler for SlowLogHandler {
+        async fn handle_request(&self, request: KafkaRequest) -> Result<Option<KafkaResponse>> {
+            let RequestPayload::Produce(produce) = &request.payload else {
+                unimplemented!("Mock implementation")
+            };
+            let base_offset = *self.end_offset.lock().unwrap();
+            if request.header.correlation_id == 1 {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            *self.end_offset.lock().unwrap() = base_offset + 1;
+
+            Ok(Some(KafkaResponse::new(
+                request.header.correlation_id,
+                request.header.api_version,
+                0,
+                ResponsePayload::Produce(ProduceResponse {
+                    responses: vec![ProduceTopicResponse {
+                        name: produce.topics[0].name.clone(),
+                        partitions: vec![ProducePartitionResponse {
+                            index: 0,
+                            error_code: 0,
+                            base_offset,
+                            log_append_time_ms: -1,
+                            log_start_offset: 0,
+                        }],
+                    }],
+                    throttle_time_ms: 0,
+                }),
+            )))
+        }
+    }
+
+    /// foo-0 에 보내는 Produce v3 요청 (크기 포함)
+    fn produce_request(correlation_id: i32) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&PRODUCE_KEY.to_be_bytes());  // API Key
+        data.extend_from_slice(&3i16.to_be_bytes());  // API Version
+        data.extend_from_slice(&correlation_id.to_be_bytes());  // Correlation ID
+        data.extend_from_slice(&(-1i16).to_be_bytes());  // Client ID (null)
+        data.extend_from_slice(&(-1i16).to_be_bytes());  // transactional_id (null)
+        data.extend_from_slice(&1i16.to_be_bytes());  // acks
+        data.extend_from_slice(&1500i32.to_be_bytes());  // timeout_ms
+        data.extend_from_slice(&1i32.to_be_bytes());  // topics array length
+        data.extend_from_slice(&3i16.to_be_bytes());  // topic name length
+        data.extend_from_slice(b"foo");
+        data.extend_from_slice(&1i32.to_be_bytes());  // partitions array length
+        data.extend_from_slice(&0i32.to_be_bytes());  // partition index
+        data.extend_from_slice(&3i32.to_be_bytes());  // records length
+        data.extend_from_slice(&[1, 2, 3]);
+
+        let mut framed = (data.len() as i32).to_be_bytes().to_vec();
+        framed.extend_from_slice(&data);
+        framed
+    }
+
+    #[tokio::test]
+    async fn test_pipelined_produce_requests_are_handled_in_order() -> Result<()> {
+        let handler = Arc::new(SlowLogHandler { end_offset: Mutex::new(0) });
+        let adapter = TcpAdapter::new("127.0.0.1:0", handler, KafkaProtocolParser::new(), DEFAULT_SOCKET_REQUEST_MAX_BYTES).await?;
+        let addr = adapter.listener.local_addr().map_err(ApplicationError::Io)?;
+        tokio::spawn(async move { adapter.run().await });
+
+        let mut stream = TcpStream::connect(addr).await.map_err(ApplicationError::Io)?;
+        let mut requests = produce_request(1);
+        requests.extend(produce_request(2));
+        stream.write_all(&requests).await.map_err(ApplicationError::Io)?;
+
+        let mut responses = Vec::new();
+        for _ in 0..2 {
+            let mut size = [0u8; 4];
+            stream.read_exact(&mut size).await.map_err(ApplicationError::Io)?;
+            let mut body = vec![0; i32::from_be_bytes(size) as usize];
+            stream.read_exact(&mut body).await.map_err(ApplicationError::Io)?;
+            // correlation id, responses 길이, 토픽 이름, partitions 길이, index, error code 다음이 base offset
+            let correlation_id = i32::from_be_bytes(body[0..4].try_into().unwrap());
+            let base_offset = i64::from_be_bytes(body[23..31].try_into().unwrap());
+            responses.push((correlation_id, base_offset));
+        }
+        assert_eq!(responses, vec![(1, 0), (2, 1)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_message_size() {
+        assert_eq!(check_message_size(0, 100).unwrap(), 0);
+        assert_eq!(check_message_size(100, 100).unwrap(), 100);
+        assert!(check_message_size(101, 100).is_err());
+        assert!(check_message_size(-1, 100).is_err());
+        assert!(check_message_size(i32::MIN, DEFAULT_SOCKET_REQUEST_MAX_BYTES).is_err());
+    }
+}
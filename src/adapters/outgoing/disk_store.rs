@@ -1,46 +1,452 @@
-use crate::adapters::incoming::protocol::messages::KafkaMessage;
+use crate::adapters::outgoing::log::partition_log::{LogConfig, PartitionLog};
+use crate::domain::message::{LogAppendInfo, PartitionOffsets, TimestampOffset};
 use crate::ports::outgoing::message_store::MessageStore;
+use crate::application::error::ApplicationError;
+use crate::domain::error::DomainError;
 use crate::Result;
 use async_trait::async_trait;
-use hex;
-use std::path::PathBuf;
-use tokio::fs::{self};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::fs;
+use tokio::sync::Mutex;
+
+/// 지우는 중인 파티션 디렉토리의 접미사
+/// 먼저 이름을 바꾸고 나중에 지우므로 도중에 죽어도 토픽 데이터가 되살아나지 않고, 다음 시작 때 마저 지움
+const DELETED_DIR_SUFFIX: &str = ".deleted";
 
 pub struct DiskMessageStore {
     log_dir: PathBuf,
+    config: LogConfig,
+    /// 토픽 단위로 덮어쓴 설정 (compression.type 등)
+    topic_configs: RwLock<HashMap<String, LogConfig>>,
+    /// 파티션 디렉토리 -> 열린 파티션 로그. retention 검사 태스크와 공유
+    logs: Arc<Mutex<HashMap<PathBuf, Arc<Mutex<PartitionLog>>>>>,
+    /// 지워진 뒤 다시 만들어지지 않은 토픽. logs 잠금을 쥐고 바꿈
+    deleted_topics: RwLock<HashSet<String>>,
 }
 
 impl DiskMessageStore {
     pub fn new(log_dir: PathBuf) -> Self {
-        Self { log_dir }
+        Self::with_config(log_dir, LogConfig::default())
+    }
+
+    pub fn with_config(log_dir: PathBuf, config: LogConfig) -> Self {
+        Self {
+            log_dir,
+            config,
+            topic_configs: RwLock::new(HashMap::new()),
+            logs: Arc::new(Mutex::new(HashMap::new())),
+            deleted_topics: RwLock::new(HashSet::new()),
+        }
+    }
+
+    pub fn with_topic_config(mut self, topic_name: &str, config: LogConfig) -> Self {
+        self.topic_configs.get_mut().unwrap().insert(topic_name.to_string(), config);
+        self
+    }
+
+    fn get_partition_dir(&self, topic_name: &str, partition: i32) -> PathBuf {
+        self.log_dir.join(format!("{}-{}", topic_name, partition))
+    }
+
+    /// 지난 실행에서 다 지우지 못한 `.deleted` 디렉토리들을 백그라운드에서 지움
+    pub fn spawn_deleted_log_cleanup(&self) {
+        let log_dir = self.log_dir.clone();
+        tokio::spawn(async move {
+            match list_dirs(&log_dir, |name| name.ends_with(DELETED_DIR_SUFFIX)).await {
+                Ok(dirs) => remove_dirs(dirs).await,
+                Err(e) => println!("[DEBUG] Failed to list deleted logs in {:?}: {}", log_dir, e),
+            }
+        });
+    }
+
+    /// interval 마다 열린 파티션 로그들에서 retention.ms / retention.bytes 를 넘은 세그먼트를 지움
+    pub fn spawn_retention_check(&self, interval: Duration) {
+        let logs = self.logs.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                delete_expired_segments(&logs).await;
+            }
+        });
+    }
+
+    fn topic_config(&self, topic_name: &str) -> LogConfig {
+        self.topic_configs.read().unwrap().get(topic_name).unwrap_or(&self.config).clone()
+    }
+
+    /// 파티션 로그를 처음 접근할 때 디스크에서 복구하고 이후엔 캐시된 것을 사용
+    /// 파티션마다 잠금이 따로라서 다른 파티션의 append 를 막지 않음
+    /// 지워진 토픽이면 디렉토리를 다시 만들지 않도록 DomainError::UnknownTopicOrPartition
+    async fn get_partition_log(&self, topic_name: &str, partition: i32) -> Result<Arc<Mutex<PartitionLog>>> {
+        let dir = self.get_partition_dir(topic_name, partition);
+        let mut logs = self.logs.lock().await;
+        if self.deleted_topics.read().unwrap().contains(topic_name) {
+            return Err(DomainError::UnknownTopicOrPartition(topic_name.to_string()).into());
+        }
+        if let Some(log) = logs.get(&dir) {
+            return Ok(log.clone());
+        }
+
+        let log = Arc::new(Mutex::new(PartitionLog::open(dir.clone(), self.topic_config(topic_name)).await?));
+        logs.insert(dir, log.clone());
+        Ok(log)
+    }
+}
+
+/// 파티션 디렉토리 이름이 `{topic}-{partition}` 인지 여부
+fn is_partition_dir_of(dir_name: &str, topic_name: &str) -> bool {
+    dir_name.strip_prefix(topic_name)
+        .and_then(|rest| rest.strip_prefix('-'))
+        .is_some_and(|partition| !partition.is_empty() && partition.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// log_dir 바로 아래에서 이름이 조건에 맞는 디렉토리들. log_dir 이 없으면 빈 목록
+async fn list_dirs(log_dir: &Path, matches: impl Fn(&str) -> bool) -> Result<Vec<PathBuf>> {
+    let mut entries = match fs::read_dir(log_dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(ApplicationError::Io(e)),
+    };
+    let mut dirs = Vec::new();
+    while let Some(entry) = entries.next_entry().await.map_err(ApplicationError::Io)? {
+        if entry.file_name().to_str().is_some_and(&matches) && entry.file_type().await.map_err(ApplicationError::Io)?.is_dir() {
+            dirs.push(entry.path());
+        }
+    }
+    Ok(dirs)
+}
+
+/// 열린 파티션 로그마다 오래된 세그먼트를 지우고 지운 개수의 합을 반환
+/// 목록만 복사하고 잠금을 풀어서 검사하는 동안 다른 파티션을 여는 요청을 막지 않음
+async fn delete_expired_segments(logs: &Mutex<HashMap<PathBuf, Arc<Mutex<PartitionLog>>>>) -> usize {
+    let opened: Vec<(PathBuf, Arc<Mutex<PartitionLog>>)> = logs.lock().await
+        .iter()
+        .map(|(dir, log)| (dir.clone(), log.clone()))
+        .collect();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as i64);
+
+    let mut deleted = 0;
+    for (dir, log) in opened {
+        match log.lock().await.delete_expired_segments(now).await {
+            Ok(count) => deleted += count,
+            Err(e) => println!("[DEBUG] Failed to apply retention to {:?}: {}", dir, e),
+        }
     }
+    deleted
+}
 
-    fn get_topic_log_path(&self, topic_name: &str, partition: i32) -> PathBuf {
-        self.log_dir.join(format!("{}-{}", topic_name, partition)).join("00000000000000000000.log")
+async fn remove_dirs(dirs: Vec<PathBuf>) {
+    for dir in dirs {
+        match fs::remove_dir_all(&dir).await {
+            Ok(()) => println!("[DEBUG] Removed deleted log {:?}", dir),
+            Err(e) => println!("[DEBUG] Failed to remove deleted log {:?}: {}", dir, e),
+        }
     }
 }
 
 #[async_trait]
 impl MessageStore for DiskMessageStore {
-    async fn store_message(&self, message: KafkaMessage) -> Result<()> {
-        // TODO: Implement message storing logic
+    async fn create_topic(&self, topic_name: &str, num_partitions: i32, configs: &[(String, String)]) -> Result<()> {
+        let config = self.config.with_topic_configs(configs)?;
+        self.topic_configs.write().unwrap().insert(topic_name.to_string(), config);
+        {
+            let _logs = self.logs.lock().await;
+            self.deleted_topics.write().unwrap().remove(topic_name);
+        }
+        for partition in 0..num_partitions {
+            fs::create_dir_all(self.get_partition_dir(topic_name, partition)).await.map_err(ApplicationError::Io)?;
+        }
+        println!("[DEBUG] Created {} partition directories for {} with configs {:?}", num_partitions, topic_name, configs);
+        Ok(())
+    }
+
+    async fn create_partitions(&self, topic_name: &str, partitions: Range<i32>) -> Result<()> {
+        for partition in partitions.clone() {
+            fs::create_dir_all(self.get_partition_dir(topic_name, partition)).await.map_err(ApplicationError::Io)?;
+        }
+        println!("[DEBUG] Created partition directories {:?} for {}", partitions, topic_name);
+        Ok(())
+    }
+
+    async fn delete_topic(&self, topic_name: &str) -> Result<()> {
+        self.topic_configs.write().unwrap().remove(topic_name);
+
+        // 캐시된 로그를 버리는 동안 같은 파티션을 다시 열지 못하게 잠금을 쥔 채로 이름을 바꿈
+        let mut logs = self.logs.lock().await;
+        self.deleted_topics.write().unwrap().insert(topic_name.to_string());
+        let cached: Vec<PathBuf> = logs.keys()
+            .filter(|dir| dir.file_name().and_then(|name| name.to_str()).is_some_and(|name| is_partition_dir_of(name, topic_name)))
+            .cloned()
+            .collect();
+        for dir in cached {
+            // 진행 중인 append 가 끝나길 기다렸다가, 이미 로그를 쥔 요청이 디렉토리를 다시 만들지 못하게 표시
+            if let Some(log) = logs.remove(&dir) {
+                log.lock().await.mark_deleted();
+            }
+        }
+
+        let mut deleted = Vec::new();
+        for dir in list_dirs(&self.log_dir, |name| is_partition_dir_of(name, topic_name)).await? {
+            let mut name = dir.file_name().unwrap_or_default().to_os_string();
+            name.push(format!(".{}{}", uuid::Uuid::new_v4().simple(), DELETED_DIR_SUFFIX));
+            let renamed = dir.with_file_name(name);
+            fs::rename(&dir, &renamed).await.map_err(ApplicationError::Io)?;
+            deleted.push(renamed);
+        }
+        drop(logs);
+
+        println!("[DEBUG] Scheduled deletion of {} partition directories for {}", deleted.len(), topic_name);
+        tokio::spawn(remove_dirs(deleted));
+        Ok(())
+    }
+
+    async fn store_message(&self, topic_name: &str, partition: i32, records: &[u8]) -> Result<LogAppendInfo> {
+        let log = self.get_partition_log(topic_name, partition).await?;
+        let mut log = log.lock().await;
+        log.append(records).await
+    }
+
+    async fn read_messages(&self, topic_id: &str, partition: i32, offset: i64, max_bytes: i32) -> Result<Option<Vec<u8>>> {
+        let log = self.get_partition_log(topic_id, partition).await?;
+        let log = log.lock().await;
+        let records = log.read(offset, max_bytes.max(0) as usize).await?;
+        println!("[DEBUG] Read {:?} bytes from {}-{} at offset {}", records.as_ref().map(|r| r.len()), topic_id, partition, offset);
+        Ok(records)
+    }
+
+    async fn get_partition_offsets(&self, topic_name: &str, partition: i32) -> Result<PartitionOffsets> {
+        let log = self.get_partition_log(topic_name, partition).await?;
+        let log = log.lock().await;
+        Ok(PartitionOffsets::new(log.log_start_offset(), log.next_offset()))
+    }
+
+    async fn find_offset_by_timestamp(&self, topic_name: &str, partition: i32, timestamp: i64) -> Result<Option<TimestampOffset>> {
+        let log = self.get_partition_log(topic_name, partition).await?;
+        let log = log.lock().await;
+        Ok(log.find_offset_by_timestamp(timestamp).await?
+            .map(|(timestamp, offset)| TimestampOffset { timestamp, offset }))
+    }
+
+    async fn find_max_timestamp_offset(&self, topic_name: &str, partition: i32) -> Result<Option<TimestampOffset>> {
+        let log = self.get_partition_log(topic_name, partition).await?;
+        let log = log.lock().await;
+        Ok(log.find_max_timestamp_offset().await?
+            .map(|(timestamp, offset)| TimestampOffset { timestamp, offset }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::outgoing::log::batch::tests::record_batch;
+    use crate::adapters::outgoing::log::batch::{read_i64, BATCH_OVERHEAD};
+    use crate::adapters::outgoing::protocol::compression::CompressionConfig;
+    use crate::adapters::outgoing::protocol::record_batch::{CompressionType, Header, Record, RecordBatch};
+
+    #[tokio::test]
+    async fn test_store_message_assigns_sequential_offsets() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let store = DiskMessageStore::new(dir.path().to_path_buf());
+
+        let mut records = record_batch(1, 0);
+        records.extend(record_batch(0, 0));
+        assert_eq!(store.store_message("foo", 0, &records).await?.base_offset, 0);
+        assert_eq!(store.store_message("foo", 0, &record_batch(2, 0)).await?.base_offset, 3);
+
+        let log = store.read_messages("foo", 0, 0, i32::MAX).await?.unwrap();
+        assert_eq!(log.len(), BATCH_OVERHEAD * 3);
+        assert_eq!(read_i64(&log, BATCH_OVERHEAD), 2);
+        assert_eq!(read_i64(&log, BATCH_OVERHEAD * 2), 3);
+
+        // offset 을 주면 그 offset 을 포함하는 배치부터 돌려줌
+        let log = store.read_messages("foo", 0, 4, i32::MAX).await?.unwrap();
+        assert_eq!(log.len(), BATCH_OVERHEAD);
+        assert_eq!(read_i64(&log, 0), 3);
+        assert!(store.read_messages("foo", 0, 6, i32::MAX).await?.is_none());
+        assert_eq!(store.get_partition_offsets("foo", 0).await?, PartitionOffsets::new(0, 6));
+
+        // 새 인스턴스는 기존 로그에서 다음 offset 을 복구해야 함
+        let reopened = DiskMessageStore::new(dir.path().to_path_buf());
+        assert_eq!(reopened.store_message("foo", 0, &record_batch(0, 0)).await?.base_offset, 6);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_store_message_rejects_truncated_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = DiskMessageStore::new(dir.path().to_path_buf());
+
+        let records = record_batch(0, 0);
+        let result = store.store_message("foo", 0, &records[..BATCH_OVERHEAD - 1]).await;
+        assert!(matches!(result, Err(ApplicationError::Protocol(_))));
+
+        // crc 가 맞지 않는 배치는 로그에 쓰지 않음
+        let mut corrupted = records.clone();
+        corrupted[BATCH_OVERHEAD - 1] ^= 0x01;
+        let result = store.store_message("foo", 0, &corrupted).await;
+        assert!(matches!(result, Err(ApplicationError::Protocol(_))));
+        assert_eq!(store.get_partition_offsets("foo", 0).await.unwrap(), PartitionOffsets::new(0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_user_records_round_trip() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let store = DiskMessageStore::new(dir.path().to_path_buf());
+
+        let record = Record {
+            headers: vec![Header { key: "content-type".to_string(), value: Some(b"json".to_vec()) }],
+            ..Record::new(0, Some(b"user-1".to_vec()), Some(b"{}".to_vec()))
+        };
+        let batch = RecordBatch::new(0, 0, 1000, vec![record, Record::new(1, None, None)]);
+        store.store_message("foo", 0, &record_batch(0, 0)).await?;
+        assert_eq!(store.store_message("foo", 0, &batch.to_bytes()?).await?.base_offset, 1);
+
+        let log = store.read_messages("foo", 0, 1, i32::MAX).await?.unwrap();
+        let stored = RecordBatch::from_bytes(&mut bytes::Bytes::from(log))?;
+        assert_eq!(stored.base_offset, 1);
+        assert_eq!(stored.next_offset(), 3);
+        assert_eq!(stored.records, batch.records);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_topic_compression_type_override() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let store = DiskMessageStore::new(dir.path().to_path_buf())
+            .with_topic_config("bar", LogConfig {
+                compression_type: CompressionConfig::Codec(CompressionType::Lz4),
+                ..LogConfig::default()
+            });
+
+        let batch = RecordBatch::new(0, 0, 1000, vec![Record::new(0, None, Some(b"value".to_vec()))]);
+        for (topic, compression) in [("foo", CompressionType::None), ("bar", CompressionType::Lz4)] {
+            store.store_message(topic, 0, &batch.to_bytes()?).await?;
+            let log = store.read_messages(topic, 0, 0, i32::MAX).await?.unwrap();
+            let stored = RecordBatch::from_bytes(&mut bytes::Bytes::from(log))?;
+            assert_eq!(stored.compression_type()?, compression);
+            assert_eq!(stored.records, batch.records);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_topic_makes_partition_dirs_and_applies_configs() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let store = DiskMessageStore::new(dir.path().to_path_buf());
+
+        store.create_topic("foo", 2, &[("compression.type".to_string(), "gzip".to_string())]).await?;
+        assert!(dir.path().join("foo-0").is_dir());
+        assert!(dir.path().join("foo-1").is_dir());
+        assert!(!dir.path().join("foo-2").exists());
+
+        let batch = RecordBatch::new(0, 0, 1000, vec![Record::new(0, None, Some(b"value".to_vec()))]);
+        store.store_message("foo", 1, &batch.to_bytes()?).await?;
+        let log = store.read_messages("foo", 1, 0, i32::MAX).await?.unwrap();
+        let stored = RecordBatch::from_bytes(&mut bytes::Bytes::from(log))?;
+        assert_eq!(stored.compression_type()?, CompressionType::Gzip);
+
+        let result = store.create_topic("bar", 1, &[("segment.bytes".to_string(), "abc".to_string())]).await;
+        assert!(matches!(result, Err(ApplicationError::Config(_))));
+        assert!(!dir.path().join("bar-0").exists());
+        Ok(())
+    }
+
+    /// 백그라운드 삭제가 끝날 때까지 잠깐 기다림
+    async fn wait_for_deleted_dirs(log_dir: &Path) -> Result<bool> {
+        for _ in 0..100 {
+            if list_dirs(log_dir, |name| name.ends_with(DELETED_DIR_SUFFIX)).await?.is_empty() {
+                return Ok(true);
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        Ok(false)
+    }
+
+    #[tokio::test]
+    async fn test_delete_topic_renames_and_removes_partition_dirs() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let store = DiskMessageStore::new(dir.path().to_path_buf());
+        store.create_topic("foo", 2, &[("compression.type".to_string(), "gzip".to_string())]).await?;
+        store.create_topic("foo-1", 1, &[]).await?;
+        store.store_message("foo", 0, &record_batch(1, 0)).await?;
+
+        store.delete_topic("foo").await?;
+        assert!(!dir.path().join("foo-0").exists());
+        assert!(!dir.path().join("foo-1").exists());
+        // 이름이 겹치는 다른 토픽의 파티션은 건드리지 않음
+        assert!(dir.path().join("foo-1-0").is_dir());
+        assert!(wait_for_deleted_dirs(dir.path()).await?);
+
+        // 다시 만들기 전에는 쓸 수 없고, 같은 이름으로 다시 만든 토픽은 빈 로그와 기본 설정으로 시작
+        let result = store.store_message("foo", 0, &record_batch(0, 0)).await;
+        assert!(matches!(result, Err(ApplicationError::Domain(DomainError::UnknownTopicOrPartition(_)))));
+        assert!(!dir.path().join("foo-0").exists());
+        store.create_topic("foo", 1, &[]).await?;
+        assert_eq!(store.store_message("foo", 0, &RecordBatch::new(0, 0, 1000, vec![Record::new(0, None, None)]).to_bytes()?).await?.base_offset, 0);
+        let log = store.read_messages("foo", 0, 0, i32::MAX).await?.unwrap();
+        let stored = RecordBatch::from_bytes(&mut bytes::Bytes::from(log))?;
+        assert_eq!(stored.compression_type()?, CompressionType::None);
         Ok(())
     }
 
-    async fn read_messages(&self, topic_id: &str, partition: i32, offset: i64) -> Result<Option<Vec<u8>>> {
-        let path = self.get_topic_log_path(topic_id, partition);
-        println!("[DEBUG] Looking for file at path: {:?}", path);
-        
-        match fs::read(&path).await {
-            Ok(content) => {
-                println!("[DEBUG] Successfully read {} bytes from file", content.len());
-                println!("[DEBUG] File content (hex): {:02x?}", content);
-                Ok(Some(content))
-            },
-            Err(e) => {
-                println!("[DEBUG] Failed to read file: {}", e);
-                Ok(None)
-            },
+    #[tokio::test]
+    async fn test_append_racing_delete_topic_does_not_recreate_partition_dir() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let store = DiskMessageStore::new(dir.path().to_path_buf());
+        store.create_topic("foo", 1, &[]).await?;
+        store.store_message("foo", 0, &record_batch(0, 0)).await?;
+
+        // 삭제 전에 로그를 꺼내 둔 Produce 가 이름이 바뀐 뒤에 append 하는 경우
+        let racing = store.get_partition_log("foo", 0).await?;
+        store.delete_topic("foo").await?;
+        let result = racing.lock().await.append(&record_batch(0, 0)).await;
+        assert!(matches!(result, Err(ApplicationError::Domain(DomainError::UnknownTopicOrPartition(_)))));
+        assert!(!dir.path().join("foo-0").exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_retention_deletes_old_segments_of_open_logs() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let store = DiskMessageStore::with_config(dir.path().to_path_buf(), LogConfig {
+            segment_bytes: BATCH_OVERHEAD as u64,
+            retention_ms: -1,
+            retention_bytes: BATCH_OVERHEAD as i64 * 2,
+            ..LogConfig::default()
+        });
+        // 토픽 설정의 retention.bytes 가 브로커 설정보다 우선
+        store.create_topic("bar", 1, &[("retention.bytes".to_string(), "-1".to_string())]).await?;
+        for topic in ["foo", "bar"] {
+            for _ in 0..5 {
+                store.store_message(topic, 0, &record_batch(0, 0)).await?;
+            }
         }
+
+        assert_eq!(delete_expired_segments(&store.logs).await, 3);
+        assert_eq!(store.get_partition_offsets("foo", 0).await?, PartitionOffsets::new(3, 5));
+        assert_eq!(store.get_partition_offsets("bar", 0).await?, PartitionOffsets::new(0, 5));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_leftover_deleted_dirs_are_removed() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let deleted = dir.path().join(format!("foo-0.abc{}", DELETED_DIR_SUFFIX));
+        std::fs::create_dir_all(&deleted).map_err(ApplicationError::Io)?;
+        std::fs::write(deleted.join("00000000000000000000.log"), b"data").map_err(ApplicationError::Io)?;
+        std::fs::create_dir_all(dir.path().join("bar-0")).map_err(ApplicationError::Io)?;
+
+        let store = DiskMessageStore::new(dir.path().to_path_buf());
+        store.spawn_deleted_log_cleanup();
+        assert!(wait_for_deleted_dirs(dir.path()).await?);
+        assert!(dir.path().join("bar-0").is_dir());
+        Ok(())
     }
-} 
\ No newline at end of file
+}
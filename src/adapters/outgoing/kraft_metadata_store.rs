@@ -1,85 +1,209 @@
 use crate::adapters::incoming::protocol::messages::ErrorCode;
-use crate::adapters::outgoing::protocol::kraft_record::{RecordBatch, RecordValue};
+use crate::adapters::outgoing::metadata_image::{MetadataDelta, MetadataImage, TopicImage};
+use crate::adapters::outgoing::protocol::kraft_record::{ConfigValue, PartitionValue, RecordValue, RemoveTopicValue, TopicValue, TOPIC_RESOURCE_TYPE};
+use crate::adapters::outgoing::protocol::record_batch::{Record, RecordBatch};
 use crate::application::error::ApplicationError;
-use crate::domain::message::TopicMetadata;
-use crate::domain::message::{Partition};
+use crate::domain::error::DomainError;
+use crate::domain::message::{Partition, TopicMetadata};
 use crate::ports::outgoing::metadata_store::MetadataStore;
 use async_trait::async_trait;
-use bytes::{Buf, BytesMut};
-use std::path::PathBuf;
-use std::collections::HashMap;
-use tokio::fs::read;
+use bytes::{Buf, Bytes};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::fs::{self, read, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{Mutex, OnceCell};
+
+const METADATA_LOG_SUFFIX: &str = ".log";
+const SNAPSHOT_SUFFIX: &str = ".checkpoint";
 
 pub struct KraftMetadataStore {
     log_dir: PathBuf,
+    /// 처음 접근할 때 스냅샷과 로그 꼬리로 만든 append 위치. 잠금이 append 를 직렬화
+    log: OnceCell<Mutex<MetadataLog>>,
+    /// 마지막 append 까지 반영된 이미지. 읽기는 Arc 만 복사해 가고, append 가 새 이미지로 통째로 바꿔 끼움
+    image: RwLock<Arc<MetadataImage>>,
 }
 
-#[derive(Default)]
-struct TopicInfo {
-    name: String,
-    id: String,
-    partitions: Vec<Partition>,
+/// 다음 배치를 붙일 위치
+struct MetadataLog {
+    /// 배치를 붙일 마지막 세그먼트
+    segment: PathBuf,
+    /// 다음 배치의 base offset
+    next_offset: i64,
+    /// 온전한 배치들이 차지하는 크기. 파일이 이보다 크면 꼬리가 깨진 것
+    valid_size: u64,
+    file_size: u64,
 }
 
 impl KraftMetadataStore {
     pub fn new(log_dir: PathBuf) -> Self {
-        Self { log_dir }
+        Self {
+            log_dir,
+            log: OnceCell::new(),
+            image: RwLock::new(Arc::new(MetadataImage::default())),
+        }
+    }
+
+    fn get_metadata_dir(&self) -> PathBuf {
+        self.log_dir.join("__cluster_metadata-0")
     }
 
     fn get_metadata_log_path(&self) -> PathBuf {
-        let path = self.log_dir.join("__cluster_metadata-0").join("00000000000000000000.log");
-        path
-    }
-
-    async fn load_metadata(&self) -> Result<HashMap<String, TopicInfo>, ApplicationError> {
-        let path = self.get_metadata_log_path();
-        let content = read(&path).await.map_err(ApplicationError::Io)?;
-        let mut data = BytesMut::with_capacity(content.len());
-        data.extend_from_slice(&content);
-        let mut data = data.freeze();
-
-        let mut topics_by_name: HashMap<String, TopicInfo> = HashMap::new();
-        let mut topics_by_id: HashMap<String, String> = HashMap::new(); // topic_id -> topic_name mapping
-
-        while data.remaining() > 0 {
-            let record_batch = RecordBatch::from_bytes(&mut data)?;
-            
-            // First pass: Collect all topics
-            for rec in &record_batch.records {
-                if let RecordValue::Topic(topic) = &rec.value {
-                    let topic_info = topics_by_name.entry(topic.topic_name.clone())
-                        .or_insert_with(|| TopicInfo {
-                            name: topic.topic_name.clone(),
-                            id: topic.topic_id.clone(),
-                            partitions: Vec::new(),
-                        });
-                    topics_by_id.insert(topic.topic_id.clone(), topic.topic_name.clone());
-                }
+        self.get_metadata_dir().join(format!("{:020}{}", 0, METADATA_LOG_SUFFIX))
+    }
+
+    /// 처음 부르면 디스크에서 이미지를 만들어 게시하고, 이후엔 만들어 둔 append 위치를 돌려줌
+    async fn log(&self) -> Result<&Mutex<MetadataLog>, ApplicationError> {
+        self.log.get_or_try_init(|| async {
+            let (log, image) = self.load().await?;
+            *self.image.write().unwrap() = Arc::new(image);
+            Ok(Mutex::new(log))
+        }).await
+    }
+
+    /// 지금 게시된 이미지
+    async fn image(&self) -> Result<Arc<MetadataImage>, ApplicationError> {
+        self.log().await?;
+        Ok(self.image.read().unwrap().clone())
+    }
+
+    /// 가장 최근 스냅샷을 읽고 그 뒤의 로그 배치들을 델타로 쌓아서 이미지를 만듦
+    /// 로그가 아직 없으면 빈 클러스터로 취급하고, 마지막 세그먼트의 잘리거나 crc 가 맞지 않는 꼬리는 무시함
+    async fn load(&self) -> Result<(MetadataLog, MetadataImage), ApplicationError> {
+        let dir = self.get_metadata_dir();
+        let (snapshot, segments) = list_metadata_files(&dir).await?;
+        let mut delta = MetadataDelta::new(Arc::new(MetadataImage::default()));
+
+        // 스냅샷 이름의 offset 은 스냅샷에 들어있지 않은 첫 offset
+        let mut snapshot_end = 0;
+        if let Some((end_offset, path)) = snapshot {
+            println!("[DEBUG] Loading metadata snapshot {:?}", path);
+            let mut data = Bytes::from(read(&path).await.map_err(ApplicationError::Io)?);
+            while data.remaining() > 0 {
+                replay_batch(&mut delta, &RecordBatch::from_bytes(&mut data)?)?;
             }
+            snapshot_end = end_offset;
+        }
 
-            // Second pass: Add partitions to corresponding topics
-            for rec in &record_batch.records {
-                if let RecordValue::Partition(p) = &rec.value {
-                    if let Some(topic_name) = topics_by_id.get(&p.topic_id) {
-                        if let Some(topic_info) = topics_by_name.get_mut(topic_name) {
-                            topic_info.partitions.push(Partition::new(
-                                i16::from(ErrorCode::None),
-                                p.partition_id,
-                                p.leader_id,
-                                p.leader_epoch,
-                                p.replicas.clone(),
-                                p.in_sync_replicas.clone(),
-                                p.adding_replicas.clone(),
-                                Vec::new(),
-                                p.removing_replicas.clone(),
-                            ));
-                        }
+        let mut log = MetadataLog {
+            segment: self.get_metadata_log_path(),
+            next_offset: snapshot_end,
+            valid_size: 0,
+            file_size: 0,
+        };
+        for (_, path) in segments {
+            let content = read(&path).await.map_err(ApplicationError::Io)?;
+            let file_size = content.len() as u64;
+            let mut data = Bytes::from(content);
+            let mut valid_size = 0;
+            while data.remaining() > 0 {
+                let record_batch = match RecordBatch::from_bytes(&mut data) {
+                    Ok(record_batch) => record_batch,
+                    Err(e) => {
+                        println!("[DEBUG] Ignoring metadata log tail of {:?} at {} bytes: {}", path, valid_size, e);
+                        break;
                     }
+                };
+                valid_size = file_size - data.remaining() as u64;
+                // 스냅샷에 이미 들어간 배치는 건너뜀
+                if record_batch.next_offset() <= snapshot_end {
+                    continue;
                 }
+                log.next_offset = record_batch.next_offset();
+                replay_batch(&mut delta, &record_batch)?;
             }
+            log.segment = path;
+            log.valid_size = valid_size;
+            log.file_size = file_size;
         }
 
-        Ok(topics_by_name)
+        Ok((log, delta.apply()))
+    }
+
+    /// 레코드들을 한 배치로 붙이고 (깨진 꼬리가 있으면 먼저 잘라냄), 반영한 새 이미지를 게시함
+    async fn append(&self, log: &mut MetadataLog, values: Vec<RecordValue>) -> Result<Arc<MetadataImage>, ApplicationError> {
+        let mut delta = MetadataDelta::new(self.image.read().unwrap().clone());
+        for value in &values {
+            delta.replay(value);
+        }
+        let record_batch = RecordBatch::metadata(log.next_offset, 1, current_time_millis(), values);
+        let batch = record_batch.to_bytes()?;
+
+        fs::create_dir_all(self.get_metadata_dir()).await.map_err(ApplicationError::Io)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log.segment)
+            .await
+            .map_err(ApplicationError::Io)?;
+        if log.valid_size < log.file_size {
+            println!("[DEBUG] Truncating {:?} from {} to {} bytes", log.segment, log.file_size, log.valid_size);
+            file.set_len(log.valid_size).await.map_err(ApplicationError::Io)?;
+            log.file_size = log.valid_size;
+        }
+        file.write_all(&batch).await.map_err(ApplicationError::Io)?;
+        file.flush().await.map_err(ApplicationError::Io)?;
+
+        log.next_offset = record_batch.next_offset();
+        log.valid_size += batch.len() as u64;
+        log.file_size = log.valid_size;
+
+        let image = Arc::new(delta.apply());
+        *self.image.write().unwrap() = image.clone();
+        Ok(image)
+    }
+
+    /// TopicRecord, 토픽의 ConfigRecord 들, 파티션마다 PartitionRecord 를 한 배치로 붙임
+    async fn append_topic(&self, log: &mut MetadataLog, topic_name: &str, assignments: Vec<Vec<u32>>, configs: &[(String, String)]) -> Result<TopicMetadata, ApplicationError> {
+        let topic_id = uuid::Uuid::new_v4().to_string();
+
+        let mut values = vec![RecordValue::Topic(TopicValue {
+            topic_name: topic_name.to_string(),
+            topic_id: topic_id.clone(),
+        })];
+        values.extend(configs.iter().map(|(name, value)| RecordValue::Config(ConfigValue {
+            resource_type: TOPIC_RESOURCE_TYPE,
+            resource_name: topic_name.to_string(),
+            name: name.clone(),
+            value: Some(value.clone()),
+        })));
+        Self::append_partition_records(&mut values, &topic_id, 0, assignments);
+
+        let image = self.append(log, values).await?;
+        let topic = image.topic_by_id(&topic_id)
+            .ok_or_else(|| ApplicationError::Domain(DomainError::UnknownTopicId(topic_id.clone())))?;
+        println!("[DEBUG] Created topic {} ({}) with {} partitions and configs {:?}", topic_name, topic_id, topic.partitions.len(), configs);
+        Ok(self.topic_metadata(topic))
+    }
+
+    /// first_partition 부터 파티션마다 PartitionRecord 를 values 에 붙임
+    /// 각 파티션의 리더는 replica 목록의 첫 브로커
+    fn append_partition_records(values: &mut Vec<RecordValue>, topic_id: &str, first_partition: u32, assignments: Vec<Vec<u32>>) {
+        for (i, replicas) in assignments.into_iter().enumerate() {
+            values.push(RecordValue::Partition(PartitionValue {
+                partition_id: first_partition + i as u32,
+                topic_id: topic_id.to_string(),
+                leader_id: replicas.first().copied().unwrap_or(0),
+                in_sync_replicas: replicas.clone(),
+                replicas,
+                removing_replicas: Vec::new(),
+                adding_replicas: Vec::new(),
+                leader_epoch: 0,
+                partition_epoch: 0,
+                directories: Vec::new(),
+            }));
+        }
+    }
+
+    fn topic_metadata(&self, topic: &TopicImage) -> TopicMetadata {
+        self.create_topic_metadata(
+            topic.name.clone(),
+            topic.id.clone(),
+            topic.partitions.values().cloned().collect(),
+            i16::from(ErrorCode::None),
+        )
     }
 
     fn create_topic_metadata(&self, name: String, id: String, partitions: Vec<Partition>, error_code: i16) -> TopicMetadata {
@@ -94,30 +218,68 @@ impl KraftMetadataStore {
     }
 }
 
+/// 메타데이터 디렉토리의 가장 최근 스냅샷과, base offset 순으로 정렬한 로그 세그먼트들
+/// 파일 이름은 스냅샷이 `{끝 offset}-{epoch}.checkpoint`, 세그먼트가 `{base offset}.log`
+async fn list_metadata_files(dir: &Path) -> Result<(Option<(i64, PathBuf)>, Vec<(i64, PathBuf)>), ApplicationError> {
+    let mut entries = match fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((None, Vec::new())),
+        Err(e) => return Err(ApplicationError::Io(e)),
+    };
+
+    let mut snapshot: Option<(i64, PathBuf)> = None;
+    let mut segments = Vec::new();
+    while let Some(entry) = entries.next_entry().await.map_err(ApplicationError::Io)? {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if let Some(offset) = name.strip_suffix(METADATA_LOG_SUFFIX).and_then(|stem| stem.parse::<i64>().ok()) {
+            segments.push((offset, entry.path()));
+        } else if let Some(offset) = name.strip_suffix(SNAPSHOT_SUFFIX)
+            .and_then(|stem| stem.split_once('-'))
+            .and_then(|(offset, _epoch)| offset.parse::<i64>().ok())
+        {
+            if snapshot.as_ref().is_none_or(|(latest, _)| offset > *latest) {
+                snapshot = Some((offset, entry.path()));
+            }
+        }
+    }
+    segments.sort();
+    Ok((snapshot, segments))
+}
+
+/// 리더 변경이나 스냅샷 헤더 같은 컨트롤 배치에는 메타데이터 레코드가 없음
+fn replay_batch(delta: &mut MetadataDelta, record_batch: &RecordBatch) -> Result<(), ApplicationError> {
+    if record_batch.is_control() {
+        return Ok(());
+    }
+    for record in &record_batch.records {
+        delta.replay(&Record::metadata_value(record)?);
+    }
+    Ok(())
+}
+
+fn current_time_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
 #[async_trait]
 impl MetadataStore for KraftMetadataStore {
     async fn get_topic_metadata_by_names(&self, topic_names: Vec<String>) -> Result<Option<Vec<TopicMetadata>>, ApplicationError> {
-        let topics_by_name = self.load_metadata().await?;
-        
-        let mut result = Vec::new();
-        for name in topic_names {
-            let metadata = if let Some(info) = topics_by_name.get(&name) {
-                self.create_topic_metadata(
-                    name.clone(),
-                    info.id.clone(),
-                    info.partitions.clone(),
-                    i16::from(ErrorCode::None),
-                )
-            } else {
-                self.create_topic_metadata(
-                    name.clone(),
+        let image = self.image().await?;
+
+        let result: Vec<TopicMetadata> = topic_names.into_iter()
+            .map(|name| match image.topic_by_name(&name) {
+                Some(topic) => self.topic_metadata(topic),
+                None => self.create_topic_metadata(
                     name.clone(),
+                    name,
                     Vec::new(),
                     i16::from(ErrorCode::UnknownTopicOrPartition),
-                )
-            };
-            result.push(metadata);
-        }
+                ),
+            })
+            .collect();
 
         if result.is_empty() {
             Ok(None)
@@ -127,27 +289,19 @@ impl MetadataStore for KraftMetadataStore {
     }
 
     async fn get_topic_metadata_by_ids(&self, topic_ids: Vec<String>) -> Result<Option<Vec<TopicMetadata>>, ApplicationError> {
-        let topics_by_name = self.load_metadata().await?;
-        
-        let mut result = Vec::new();
-        for id in topic_ids {
-            let metadata = if let Some(info) = topics_by_name.values().find(|info| info.id == id) {
-                self.create_topic_metadata(
-                    info.name.clone(),
-                    id.clone(),
-                    info.partitions.clone(),
-                    i16::from(ErrorCode::None),
-                )
-            } else {
-                self.create_topic_metadata(
-                    id.clone(),
+        let image = self.image().await?;
+
+        let result: Vec<TopicMetadata> = topic_ids.into_iter()
+            .map(|id| match image.topic_by_id(&id) {
+                Some(topic) => self.topic_metadata(topic),
+                None => self.create_topic_metadata(
                     id.clone(),
+                    id,
                     Vec::new(),
                     i16::from(ErrorCode::UnknownTopicOrPartition),
-                )
-            };
-            result.push(metadata);
-        }
+                ),
+            })
+            .collect();
 
         if result.is_empty() {
             Ok(None)
@@ -155,4 +309,265 @@ impl MetadataStore for KraftMetadataStore {
             Ok(Some(result))
         }
     }
+
+    async fn get_all_topic_metadata(&self) -> Result<Vec<TopicMetadata>, ApplicationError> {
+        let image = self.image().await?;
+
+        let mut result: Vec<TopicMetadata> = image.topics()
+            .map(|topic| self.topic_metadata(topic))
+            .collect();
+        result.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(result)
+    }
+
+    async fn get_topic_configs(&self, topic_name: &str) -> Result<Vec<(String, String)>, ApplicationError> {
+        Ok(self.image().await?.configs(TOPIC_RESOURCE_TYPE, topic_name))
+    }
+
+    async fn get_cluster_id(&self) -> Result<Option<String>, ApplicationError> {
+        let path = self.log_dir.join("meta.properties");
+        let content = match fs::read_to_string(&path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(ApplicationError::Io(e)),
+        };
+
+        Ok(content.lines()
+            .filter_map(|line| line.trim().strip_prefix("cluster.id="))
+            .map(|id| id.trim().to_string())
+            .next())
+    }
+
+    async fn create_topic(&self, topic_name: &str, num_partitions: u32, replicas: Vec<u32>) -> Result<TopicMetadata, ApplicationError> {
+        let mut log = self.log().await?.lock().await;
+
+        if let Some(topic) = self.image().await?.topic_by_name(topic_name) {
+            return Ok(self.topic_metadata(topic));
+        }
+        self.append_topic(&mut log, topic_name, vec![replicas; num_partitions as usize], &[]).await
+    }
+
+    async fn create_topic_with_assignments(&self, topic_name: &str, assignments: Vec<Vec<u32>>, configs: &[(String, String)]) -> Result<TopicMetadata, ApplicationError> {
+        let mut log = self.log().await?.lock().await;
+
+        if self.image().await?.topic_by_name(topic_name).is_some() {
+            return Err(ApplicationError::Domain(DomainError::TopicAlreadyExists(topic_name.to_string())));
+        }
+        self.append_topic(&mut log, topic_name, assignments, configs).await
+    }
+
+    async fn create_partitions(&self, topic_name: &str, assignments: Vec<Vec<u32>>) -> Result<TopicMetadata, ApplicationError> {
+        let mut log = self.log().await?.lock().await;
+
+        let image = self.image().await?;
+        let Some(topic) = image.topic_by_name(topic_name) else {
+            return Err(ApplicationError::Domain(DomainError::UnknownTopicOrPartition(topic_name.to_string())));
+        };
+        let mut values = Vec::with_capacity(assignments.len());
+        Self::append_partition_records(&mut values, &topic.id, topic.partitions.len() as u32, assignments);
+
+        let image = self.append(&mut log, values).await?;
+        let topic = image.topic_by_name(topic_name)
+            .ok_or_else(|| ApplicationError::Domain(DomainError::UnknownTopicOrPartition(topic_name.to_string())))?;
+        println!("[DEBUG] Increased partitions of {} ({}) to {}", topic_name, topic.id, topic.partitions.len());
+        Ok(self.topic_metadata(topic))
+    }
+
+    async fn delete_topic(&self, topic_id: &str) -> Result<(), ApplicationError> {
+        let mut log = self.log().await?.lock().await;
+
+        let Some(topic) = self.image().await?.topic_by_id(topic_id).map(|topic| topic.name.clone()) else {
+            return Err(ApplicationError::Domain(DomainError::UnknownTopicId(topic_id.to_string())));
+        };
+        println!("[DEBUG] Removing topic {} ({})", topic, topic_id);
+
+        let values = vec![RecordValue::RemoveTopic(RemoveTopicValue {
+            topic_id: topic_id.to_string(),
+        })];
+        self.append(&mut log, values).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_create_topic_is_persisted() -> Result<(), ApplicationError> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let store = KraftMetadataStore::new(dir.path().to_path_buf());
+
+        assert!(store.get_all_topic_metadata().await?.is_empty());
+
+        let created = store.create_topic("foo", 2, vec![1]).await?;
+        assert_eq!(created.partitions.len(), 2);
+
+        // 두 번째 생성은 기존 토픽을 그대로 돌려줘야 함
+        let again = store.create_topic("foo", 5, vec![1]).await?;
+        assert_eq!(again.topic_id, created.topic_id);
+        assert_eq!(again.partitions.len(), 2);
+
+        store.create_topic("bar", 1, vec![1]).await?;
+
+        let reopened = KraftMetadataStore::new(dir.path().to_path_buf());
+        let topics = reopened.get_all_topic_metadata().await?;
+        assert_eq!(topics.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), vec!["bar", "foo"]);
+
+        let foo = reopened.get_topic_metadata_by_ids(vec![created.topic_id.clone()]).await?.unwrap();
+        assert_eq!(foo[0].name, "foo");
+        assert_eq!(foo[0].partitions[1].partition_index, 1);
+        assert_eq!(foo[0].partitions[1].leader_id, 1);
+        assert_eq!(foo[0].partitions[1].replicas, vec![1]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_topic_with_assignments_and_configs() -> Result<(), ApplicationError> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let store = KraftMetadataStore::new(dir.path().to_path_buf());
+
+        let configs = vec![("cleanup.policy".to_string(), "compact".to_string())];
+        let created = store.create_topic_with_assignments("foo", vec![vec![2, 1], vec![1, 2]], &configs).await?;
+        assert_eq!(created.partitions[0].leader_id, 2);
+        assert_eq!(created.partitions[1].replicas, vec![1, 2]);
+
+        let result = store.create_topic_with_assignments("foo", vec![vec![1]], &[]).await;
+        assert!(matches!(result, Err(ApplicationError::Domain(DomainError::TopicAlreadyExists(_)))));
+
+        // 토픽, 설정, 파티션 레코드가 한 배치에 순서대로 기록됨
+        let content = std::fs::read(store.get_metadata_log_path()).map_err(ApplicationError::Io)?;
+        let batch = RecordBatch::from_bytes(&mut bytes::Bytes::from(content))?;
+        let values = batch.records.iter().map(Record::metadata_value).collect::<Result<Vec<_>, _>>()?;
+        assert!(matches!(&values[..], [RecordValue::Topic(_), RecordValue::Config(_), RecordValue::Partition(_), RecordValue::Partition(_)]));
+        match &values[1] {
+            RecordValue::Config(config) => {
+                assert_eq!((config.resource_type, config.resource_name.as_str()), (TOPIC_RESOURCE_TYPE, "foo"));
+                assert_eq!((config.name.as_str(), config.value.as_deref()), ("cleanup.policy", Some("compact")));
+            }
+            _ => unreachable!(),
+        }
+
+        let reopened = KraftMetadataStore::new(dir.path().to_path_buf());
+        let foo = reopened.get_topic_metadata_by_names(vec!["foo".to_string()]).await?.unwrap();
+        assert_eq!(foo[0].topic_id, created.topic_id);
+        assert_eq!(foo[0].partitions, created.partitions);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_partitions_appends_partition_records() -> Result<(), ApplicationError> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let store = KraftMetadataStore::new(dir.path().to_path_buf());
+        let created = store.create_topic("foo", 2, vec![1]).await?;
+
+        let grown = store.create_partitions("foo", vec![vec![1], vec![1]]).await?;
+        assert_eq!(grown.topic_id, created.topic_id);
+        assert_eq!(grown.partitions.iter().map(|p| p.partition_index).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+
+        let result = store.create_partitions("bar", vec![vec![1]]).await;
+        assert!(matches!(result, Err(ApplicationError::Domain(DomainError::UnknownTopicOrPartition(_)))));
+
+        let reopened = KraftMetadataStore::new(dir.path().to_path_buf());
+        let foo = reopened.get_topic_metadata_by_names(vec!["foo".to_string()]).await?.unwrap();
+        assert_eq!(foo[0].partitions, grown.partitions);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delete_topic_is_persisted() -> Result<(), ApplicationError> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let store = KraftMetadataStore::new(dir.path().to_path_buf());
+        let foo = store.create_topic("foo", 2, vec![1]).await?;
+        store.create_topic("bar", 1, vec![1]).await?;
+
+        store.delete_topic(&foo.topic_id).await?;
+        let result = store.delete_topic(&foo.topic_id).await;
+        assert!(matches!(result, Err(ApplicationError::Domain(DomainError::UnknownTopicId(_)))));
+
+        let reopened = KraftMetadataStore::new(dir.path().to_path_buf());
+        let topics = reopened.get_all_topic_metadata().await?;
+        assert_eq!(topics.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), vec!["bar"]);
+        let by_id = reopened.get_topic_metadata_by_ids(vec![foo.topic_id.clone()]).await?.unwrap();
+        assert_eq!(by_id[0].error_code, i16::from(ErrorCode::UnknownTopicOrPartition));
+
+        // 같은 이름으로 다시 만들면 새 토픽 ID 와 파티션으로 시작함
+        let recreated = reopened.create_topic("foo", 1, vec![1]).await?;
+        assert_ne!(recreated.topic_id, foo.topic_id);
+        let foo = reopened.get_topic_metadata_by_names(vec!["foo".to_string()]).await?.unwrap();
+        assert_eq!(foo[0].topic_id, recreated.topic_id);
+        assert_eq!(foo[0].partitions.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_metadata_log_tail_is_truncated() -> Result<(), ApplicationError> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let store = KraftMetadataStore::new(dir.path().to_path_buf());
+        store.create_topic("foo", 1, vec![1]).await?;
+        store.create_topic("bar", 1, vec![1]).await?;
+
+        // 두 번째 배치의 마지막 바이트를 망가뜨림
+        let path = store.get_metadata_log_path();
+        let mut content = std::fs::read(&path).map_err(ApplicationError::Io)?;
+        *content.last_mut().unwrap() ^= 0xff;
+        std::fs::write(&path, &content).map_err(ApplicationError::Io)?;
+
+        let store = KraftMetadataStore::new(dir.path().to_path_buf());
+        let topics = store.get_all_topic_metadata().await?;
+        assert_eq!(topics.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), vec!["foo"]);
+
+        // 다음 쓰기 전에 깨진 배치를 잘라내야 새 배치를 읽을 수 있음
+        store.create_topic("baz", 1, vec![1]).await?;
+        let reopened = KraftMetadataStore::new(dir.path().to_path_buf());
+        let topics = reopened.get_all_topic_metadata().await?;
+        assert_eq!(topics.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), vec!["baz", "foo"]);
+        let (log, _) = reopened.load().await?;
+        assert_eq!(log.valid_size, log.file_size);
+        assert_eq!(log.next_offset, 4);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_image_is_rebuilt_from_snapshot_and_log_tail() -> Result<(), ApplicationError> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let store = KraftMetadataStore::new(dir.path().to_path_buf());
+        let foo = store.create_topic_with_assignments("foo", vec![vec![1]], &[("retention.ms".to_string(), "1000".to_string())]).await?;
+        let content = std::fs::read(store.get_metadata_log_path()).map_err(ApplicationError::Io)?;
+
+        // foo 까지 담은 스냅샷. 같은 배치가 로그에도 있지만 두 번 반영되지 않아야 함
+        std::fs::write(
+            store.get_metadata_dir().join("00000000000000000003-0000000001.checkpoint"),
+            &content,
+        ).map_err(ApplicationError::Io)?;
+        store.create_topic("bar", 1, vec![1]).await?;
+
+        let reopened = KraftMetadataStore::new(dir.path().to_path_buf());
+        let topics = reopened.get_all_topic_metadata().await?;
+        assert_eq!(topics.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), vec!["bar", "foo"]);
+        assert_eq!(topics[1].partitions, foo.partitions);
+        assert_eq!(reopened.get_topic_configs("foo").await?, vec![("retention.ms".to_string(), "1000".to_string())]);
+        assert!(reopened.get_topic_configs("bar").await?.is_empty());
+
+        // 새 배치는 로그 꼬리 다음 offset 에 붙음
+        reopened.create_topic("baz", 1, vec![1]).await?;
+        let (log, image) = KraftMetadataStore::new(dir.path().to_path_buf()).load().await?;
+        assert_eq!(log.next_offset, 7);
+        assert_eq!(image.topics().count(), 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_cluster_id_from_meta_properties() -> Result<(), ApplicationError> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let store = KraftMetadataStore::new(dir.path().to_path_buf());
+        assert_eq!(store.get_cluster_id().await?, None);
+
+        std::fs::write(
+            dir.path().join("meta.properties"),
+            "#\nnode.id=1\nversion=1\ncluster.id=abc-123\n",
+        ).map_err(ApplicationError::Io)?;
+        assert_eq!(store.get_cluster_id().await?, Some("abc-123".to_string()));
+        Ok(())
+    }
 }
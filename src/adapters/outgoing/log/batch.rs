@@ -0,0 +1,344 @@
+use crate::adapters::outgoing::protocol::record_batch::RecordBatch;
+use crate::application::error::ApplicationError;
+use crate::Result;
+use bytes::Bytes;
+
+/// base_offset(8) + batch_length(4)
+pub const BATCH_HEADER_SIZE: usize = 12;
+/// RecordBatch 헤더 (base_offset ~ records count) 의 전체 크기
+pub const BATCH_OVERHEAD: usize = 61;
+
+const MAGIC_OFFSET: usize = 16;
+const CRC_OFFSET: usize = 17;
+/// CRC-32C 는 attributes 부터 배치 끝까지를 덮음 (base_offset 은 브로커가 바꾸므로 제외)
+pub const CRC_COVERED_START: usize = 21;
+const LAST_OFFSET_DELTA_OFFSET: usize = 23;
+const BASE_TIMESTAMP_OFFSET: usize = 27;
+const MAX_TIMESTAMP_OFFSET: usize = 35;
+const ATTRIBUTES_OFFSET: usize = 21;
+const RECORDS_COUNT_OFFSET: usize = 57;
+
+const COMPRESSION_CODEC_MASK: i16 = 0x07;
+const LOG_APPEND_TIME_FLAG: i16 = 0x08;
+
+/// 로그를 다룰 때 필요한 RecordBatch 헤더 필드들. 레코드는 파싱하지 않음
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatchHeader {
+    pub base_offset: i64,
+    pub batch_length: i32,
+    pub magic: i8,
+    pub crc: u32,
+    pub attributes: i16,
+    pub last_offset_delta: i32,
+    pub base_timestamp: i64,
+    pub max_timestamp: i64,
+}
+
+impl BatchHeader {
+    /// 헤더 전체가 들어있지 않으면 None
+    pub fn parse(buf: &[u8]) -> Option<Self> {
+        if buf.len() < BATCH_OVERHEAD {
+            return None;
+        }
+
+        Some(Self {
+            base_offset: read_i64(buf, 0),
+            batch_length: read_i32(buf, 8),
+            magic: buf[MAGIC_OFFSET] as i8,
+            crc: read_i32(buf, CRC_OFFSET) as u32,
+            attributes: i16::from_be_bytes([buf[ATTRIBUTES_OFFSET], buf[ATTRIBUTES_OFFSET + 1]]),
+            last_offset_delta: read_i32(buf, LAST_OFFSET_DELTA_OFFSET),
+            base_timestamp: read_i64(buf, BASE_TIMESTAMP_OFFSET),
+            max_timestamp: read_i64(buf, MAX_TIMESTAMP_OFFSET),
+        })
+    }
+
+    /// base_offset 과 batch_length 를 포함한 배치 전체 크기
+    pub fn size(&self) -> usize {
+        BATCH_HEADER_SIZE + self.batch_length.max(0) as usize
+    }
+
+    pub fn last_offset(&self) -> i64 {
+        self.base_offset.saturating_add(self.last_offset_delta as i64)
+    }
+
+    pub fn next_offset(&self) -> i64 {
+        self.last_offset().saturating_add(1)
+    }
+}
+
+/// 배치 하나 (헤더 포함) 의 CRC-32C
+pub fn compute_crc(batch: &[u8]) -> u32 {
+    crc32c::crc32c(&batch[CRC_COVERED_START.min(batch.len())..])
+}
+
+/// 배치 하나에 기록된 crc 가 내용과 맞는지
+pub fn has_valid_crc(batch: &[u8]) -> bool {
+    BatchHeader::parse(batch).is_some_and(|header| header.crc == compute_crc(batch))
+}
+
+/// 배치 하나의 crc 를 내용에 맞게 다시 씀
+pub fn write_crc(batch: &mut [u8]) {
+    let crc = compute_crc(batch);
+    batch[CRC_OFFSET..CRC_OFFSET + 4].copy_from_slice(&crc.to_be_bytes());
+}
+
+/// 배치마다 LogAppendTime 표시를 하고 max timestamp 를 브로커의 append 시각으로 바꾼 뒤 crc 를 다시 씀
+/// assign_offsets 로 검증한 버퍼에만 사용
+pub fn set_log_append_time(records: &mut [u8], timestamp: i64) {
+    let starts: Vec<(usize, usize)> = batches(records).map(|(start, header)| (start, header.size())).collect();
+    for (start, size) in starts {
+        let batch = &mut records[start..start + size];
+        let attributes = i16::from_be_bytes([batch[ATTRIBUTES_OFFSET], batch[ATTRIBUTES_OFFSET + 1]]) | LOG_APPEND_TIME_FLAG;
+        batch[ATTRIBUTES_OFFSET..ATTRIBUTES_OFFSET + 2].copy_from_slice(&attributes.to_be_bytes());
+        batch[MAX_TIMESTAMP_OFFSET..MAX_TIMESTAMP_OFFSET + 8].copy_from_slice(&timestamp.to_be_bytes());
+        write_crc(batch);
+    }
+}
+
+/// 배치 안에서 timestamp 가 target 이상인 첫 레코드의 (timestamp, offset)
+/// LogAppendTime 배치는 레코드를 보지 않고 배치의 (max timestamp, base offset) 으로 대신함
+pub fn find_record_by_timestamp(batch: &[u8], target: i64) -> Option<(i64, i64)> {
+    let header = BatchHeader::parse(batch)?;
+    if header.max_timestamp < target {
+        return None;
+    }
+    if header.attributes & LOG_APPEND_TIME_FLAG != 0 {
+        return Some((header.max_timestamp, header.base_offset));
+    }
+    if header.attributes & COMPRESSION_CODEC_MASK != 0 {
+        return find_compressed_record_by_timestamp(batch, &header, target);
+    }
+
+    let count = read_i32(batch, RECORDS_COUNT_OFFSET);
+    let end = header.size().min(batch.len());
+    let mut pos = BATCH_OVERHEAD;
+    for _ in 0..count {
+        let length = read_varint(batch, &mut pos)?;
+        let record_end = pos.checked_add(usize::try_from(length).ok()?)?;
+        pos += 1; // attributes
+        let timestamp_delta = read_varint(batch, &mut pos)?;
+        let offset_delta = read_varint(batch, &mut pos)?;
+        if record_end > end {
+            return None;
+        }
+
+        let timestamp = header.base_timestamp.saturating_add(timestamp_delta);
+        if timestamp >= target {
+            return Some((timestamp, header.base_offset.saturating_add(offset_delta)));
+        }
+        pos = record_end;
+    }
+    None
+}
+
+/// 압축된 배치는 풀어서 레코드를 찾고, 풀 수 없으면 배치의 (max timestamp, base offset) 으로 대신함
+fn find_compressed_record_by_timestamp(batch: &[u8], header: &BatchHeader, target: i64) -> Option<(i64, i64)> {
+    let end = header.size().min(batch.len());
+    let Ok(decoded) = RecordBatch::from_bytes(&mut Bytes::copy_from_slice(&batch[..end])) else {
+        return Some((header.max_timestamp, header.base_offset));
+    };
+    decoded.records.iter()
+        .map(|record| (
+            header.base_timestamp.saturating_add(record.timestamp_delta),
+            header.base_offset.saturating_add(record.offset_delta as i64),
+        ))
+        .find(|(timestamp, _)| *timestamp >= target)
+}
+
+/// zigzag 인코딩된 varint / varlong
+fn read_varint(buf: &[u8], pos: &mut usize) -> Option<i64> {
+    let mut value: u64 = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = *buf.get(*pos)?;
+        *pos += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some((value >> 1) as i64 ^ -((value & 1) as i64));
+        }
+    }
+    None
+}
+
+/// 버퍼 앞에서부터 온전한 배치들의 (시작 위치, 헤더) 를 차례로 돌려줌
+/// 잘리거나 길이가 잘못된 배치를 만나면 멈춤
+pub fn batches(buf: &[u8]) -> impl Iterator<Item = (usize, BatchHeader)> + '_ {
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        let header = BatchHeader::parse(&buf[pos..])?;
+        if header.size() < BATCH_OVERHEAD || pos + header.size() > buf.len() {
+            return None;
+        }
+        let start = pos;
+        pos += header.size();
+        Some((start, header))
+    })
+}
+
+/// 배치마다 base_offset 을 덮어써서 offset 을 할당하고, 마지막 배치 다음 offset 을 반환
+pub fn assign_offsets(records: &mut [u8], mut next_offset: i64) -> Result<i64> {
+    if records.is_empty() {
+        return Err(ApplicationError::Protocol("empty record set".to_string()));
+    }
+
+    let mut pos = 0;
+    while pos < records.len() {
+        let header = BatchHeader::parse(&records[pos..])
+            .ok_or_else(|| ApplicationError::Protocol("buffer too short for record batch header".to_string()))?;
+
+        if header.batch_length < 0 || header.size() < BATCH_OVERHEAD || pos + header.size() > records.len() {
+            return Err(ApplicationError::Protocol(format!("invalid batch length: {}", header.batch_length)));
+        }
+        if header.magic != 2 {
+            return Err(ApplicationError::Protocol(format!("unsupported magic: {}", header.magic)));
+        }
+        if header.last_offset_delta < 0 {
+            return Err(ApplicationError::Protocol(format!("invalid last offset delta: {}", header.last_offset_delta)));
+        }
+        let crc = compute_crc(&records[pos..pos + header.size()]);
+        if header.crc != crc {
+            return Err(ApplicationError::Protocol(format!(
+                "record batch is corrupt (stored crc = {}, computed crc = {})", header.crc, crc
+            )));
+        }
+
+        records[pos..pos + 8].copy_from_slice(&next_offset.to_be_bytes());
+        next_offset = next_offset.saturating_add(header.last_offset_delta as i64 + 1);
+        pos += header.size();
+    }
+
+    Ok(next_offset)
+}
+
+pub fn read_i32(buf: &[u8], at: usize) -> i32 {
+    i32::from_be_bytes([buf[at], buf[at + 1], buf[at + 2], buf[at + 3]])
+}
+
+pub fn read_i64(buf: &[u8], at: usize) -> i64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&buf[at..at + 8]);
+    i64::from_be_bytes(bytes)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// 레코드 없이 헤더만 있는 배치 (테스트용)
+    pub(crate) fn record_batch(last_offset_delta: i32, max_timestamp: i64) -> Vec<u8> {
+        let mut batch = Vec::new();
+        batch.extend_from_slice(&0i64.to_be_bytes());                          // base offset
+        batch.extend_from_slice(&((BATCH_OVERHEAD - BATCH_HEADER_SIZE) as i32).to_be_bytes()); // batch length
+        batch.extend_from_slice(&0i32.to_be_bytes());                          // partition leader epoch
+        batch.push(2);                                                          // magic
+        batch.extend_from_slice(&0u32.to_be_bytes());                          // crc
+        batch.extend_from_slice(&0i16.to_be_bytes());                          // attributes
+        batch.extend_from_slice(&last_offset_delta.to_be_bytes());             // last offset delta
+        batch.extend_from_slice(&max_timestamp.to_be_bytes());                 // base timestamp
+        batch.extend_from_slice(&max_timestamp.to_be_bytes());                 // max timestamp
+        batch.extend_from_slice(&(-1i64).to_be_bytes());                       // producer id
+        batch.extend_from_slice(&(-1i16).to_be_bytes());                       // producer epoch
+        batch.extend_from_slice(&(-1i32).to_be_bytes());                       // base sequence
+        batch.extend_from_slice(&0i32.to_be_bytes());                          // records count
+        write_crc(&mut batch);
+        batch
+    }
+
+    /// timestamp 마다 (키/값 없는) 레코드 하나씩 들어있는 배치 (테스트용)
+    pub(crate) fn record_batch_with_timestamps(timestamps: &[i64]) -> Vec<u8> {
+        let base_timestamp = timestamps.first().copied().unwrap_or(-1);
+        let mut records = Vec::new();
+        for (offset_delta, timestamp) in timestamps.iter().enumerate() {
+            let mut record = vec![0]; // attributes
+            put_varint(&mut record, timestamp - base_timestamp);
+            put_varint(&mut record, offset_delta as i64);
+            put_varint(&mut record, -1); // key
+            put_varint(&mut record, -1); // value
+            put_varint(&mut record, 0); // headers
+            put_varint(&mut records, record.len() as i64);
+            records.extend(record);
+        }
+
+        let mut batch = record_batch(timestamps.len() as i32 - 1, timestamps.iter().copied().max().unwrap_or(-1));
+        batch[8..12].copy_from_slice(&((BATCH_OVERHEAD - BATCH_HEADER_SIZE + records.len()) as i32).to_be_bytes());
+        batch[BASE_TIMESTAMP_OFFSET..BASE_TIMESTAMP_OFFSET + 8].copy_from_slice(&base_timestamp.to_be_bytes());
+        batch[RECORDS_COUNT_OFFSET..].copy_from_slice(&(timestamps.len() as i32).to_be_bytes());
+        batch.extend(records);
+        write_crc(&mut batch);
+        batch
+    }
+
+    fn put_varint(buf: &mut Vec<u8>, value: i64) {
+        let mut value = ((value << 1) ^ (value >> 63)) as u64;
+        while value >= 0x80 {
+            buf.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        buf.push(value as u8);
+    }
+
+    #[test]
+    fn test_find_record_by_timestamp() {
+        let batch = record_batch_with_timestamps(&[100, 300, 200, 400]);
+        assert_eq!(find_record_by_timestamp(&batch, 50), Some((100, 0)));
+        assert_eq!(find_record_by_timestamp(&batch, 150), Some((300, 1)));
+        assert_eq!(find_record_by_timestamp(&batch, 400), Some((400, 3)));
+        assert_eq!(find_record_by_timestamp(&batch, 401), None);
+    }
+
+    #[test]
+    fn test_assign_offsets() {
+        let mut records = record_batch(1, 10);
+        records.extend(record_batch(0, 20));
+
+        assert_eq!(assign_offsets(&mut records, 5).unwrap(), 8);
+
+        let headers: Vec<BatchHeader> = batches(&records).map(|(_, h)| h).collect();
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers[0].base_offset, 5);
+        assert_eq!(headers[0].last_offset(), 6);
+        assert_eq!(headers[1].base_offset, 7);
+        assert_eq!(headers[1].max_timestamp, 20);
+    }
+
+    #[test]
+    fn test_assign_offsets_rejects_invalid_batches() {
+        let records = record_batch(0, 0);
+        assert!(assign_offsets(&mut records[..BATCH_OVERHEAD - 1].to_vec(), 0).is_err());
+        assert!(assign_offsets(&mut [], 0).is_err());
+
+        let mut legacy = record_batch(0, 0);
+        legacy[MAGIC_OFFSET] = 1;
+        assert!(assign_offsets(&mut legacy, 0).is_err());
+
+        assert!(assign_offsets(&mut record_batch(-5, 0), 0).is_err());
+
+        // attributes 이후가 바뀌면 crc 가 맞지 않음
+        let mut corrupted = record_batch_with_timestamps(&[100, 200]);
+        assert!(has_valid_crc(&corrupted));
+        *corrupted.last_mut().unwrap() ^= 0x01;
+        assert!(!has_valid_crc(&corrupted));
+        assert!(matches!(assign_offsets(&mut corrupted, 0), Err(ApplicationError::Protocol(_))));
+    }
+
+    #[test]
+    fn test_find_record_by_timestamp_with_corrupted_records() {
+        let mut batch = record_batch_with_timestamps(&[i64::MAX - 1, i64::MAX]);
+        batch[BASE_TIMESTAMP_OFFSET..BASE_TIMESTAMP_OFFSET + 8].copy_from_slice(&i64::MAX.to_be_bytes());
+        assert_eq!(find_record_by_timestamp(&batch, i64::MAX), Some((i64::MAX, 0)));
+
+        // 레코드 길이가 말도 안 되게 크면 찾지 못한 것으로 취급
+        let mut batch = record_batch_with_timestamps(&[100]);
+        batch[BATCH_OVERHEAD] = 0xfe;
+        batch.truncate(BATCH_OVERHEAD + 1);
+        batch.extend_from_slice(&[0xff; 9]);
+        assert_eq!(find_record_by_timestamp(&batch, 0), None);
+    }
+
+    #[test]
+    fn test_batches_stops_at_partial_batch() {
+        let mut records = record_batch(0, 0);
+        records.extend(&record_batch(0, 0)[..30]);
+        assert_eq!(batches(&records).count(), 1);
+    }
+}
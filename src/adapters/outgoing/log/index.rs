@@ -0,0 +1,225 @@
+use crate::application::error::ApplicationError;
+use crate::Result;
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+
+/// 희소 offset 인덱스 (`.index`)
+/// 엔트리: relative offset (i32) + 로그 파일 내 position (i32), 총 8 바이트
+pub struct OffsetIndex {
+    path: PathBuf,
+    base_offset: i64,
+    entries: Vec<(i32, i32)>,
+}
+
+/// 희소 timestamp 인덱스 (`.timeindex`)
+/// 엔트리: 어떤 배치 이전까지의 최대 timestamp (i64) + 그 배치의 relative offset (i32), 총 12 바이트
+pub struct TimeIndex {
+    path: PathBuf,
+    base_offset: i64,
+    entries: Vec<(i64, i32)>,
+}
+
+impl OffsetIndex {
+    const ENTRY_SIZE: usize = 8;
+
+    /// 파일이 없거나 형식이 깨졌으면 빈 인덱스를 돌려주고 false 를 함께 반환
+    pub async fn open(path: PathBuf, base_offset: i64, log_size: u64) -> Result<(Self, bool)> {
+        let content = read_index_file(&path).await?;
+        let mut entries = Vec::with_capacity(content.len() / Self::ENTRY_SIZE);
+        for entry in content.chunks_exact(Self::ENTRY_SIZE) {
+            entries.push((
+                i32::from_be_bytes([entry[0], entry[1], entry[2], entry[3]]),
+                i32::from_be_bytes([entry[4], entry[5], entry[6], entry[7]]),
+            ));
+        }
+
+        let valid = content.len() % Self::ENTRY_SIZE == 0
+            && entries.windows(2).all(|w| w[0].0 < w[1].0 && w[0].1 < w[1].1)
+            && entries.iter().all(|(_, position)| *position >= 0 && (*position as u64) < log_size);
+        if !valid {
+            entries.clear();
+        }
+
+        Ok((Self { path, base_offset, entries }, valid))
+    }
+
+    /// offset 이하인 가장 큰 엔트리의 position. 없으면 세그먼트 처음 (0)
+    pub fn lookup(&self, offset: i64) -> u64 {
+        let relative = offset.saturating_sub(self.base_offset).clamp(i32::MIN as i64, i32::MAX as i64) as i32;
+        let idx = self.entries.partition_point(|(entry_offset, _)| *entry_offset <= relative);
+        if idx == 0 {
+            0
+        } else {
+            self.entries[idx - 1].1 as u64
+        }
+    }
+
+    pub fn last_position(&self) -> u64 {
+        self.entries.last().map(|(_, position)| *position as u64).unwrap_or(0)
+    }
+
+    pub async fn append(&mut self, offset: i64, position: u64) -> Result<()> {
+        let entry = ((offset - self.base_offset) as i32, position as i32);
+        let mut bytes = Vec::with_capacity(Self::ENTRY_SIZE);
+        bytes.extend_from_slice(&entry.0.to_be_bytes());
+        bytes.extend_from_slice(&entry.1.to_be_bytes());
+        append_index_file(&self.path, &bytes).await?;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// 로그를 다시 훑어서 만든 엔트리로 인덱스 파일을 통째로 교체
+    pub async fn rebuild(&mut self, entries: Vec<(i64, u64)>) -> Result<()> {
+        self.entries = entries.into_iter()
+            .map(|(offset, position)| ((offset - self.base_offset) as i32, position as i32))
+            .collect();
+        let mut bytes = Vec::with_capacity(self.entries.len() * Self::ENTRY_SIZE);
+        for (offset, position) in &self.entries {
+            bytes.extend_from_slice(&offset.to_be_bytes());
+            bytes.extend_from_slice(&position.to_be_bytes());
+        }
+        fs::write(&self.path, bytes).await.map_err(ApplicationError::Io)
+    }
+}
+
+impl TimeIndex {
+    const ENTRY_SIZE: usize = 12;
+
+    pub async fn open(path: PathBuf, base_offset: i64) -> Result<(Self, bool)> {
+        let content = read_index_file(&path).await?;
+        let mut entries = Vec::with_capacity(content.len() / Self::ENTRY_SIZE);
+        for entry in content.chunks_exact(Self::ENTRY_SIZE) {
+            let mut timestamp = [0u8; 8];
+            timestamp.copy_from_slice(&entry[..8]);
+            entries.push((
+                i64::from_be_bytes(timestamp),
+                i32::from_be_bytes([entry[8], entry[9], entry[10], entry[11]]),
+            ));
+        }
+
+        let valid = content.len() % Self::ENTRY_SIZE == 0
+            && entries.windows(2).all(|w| w[0].0 <= w[1].0 && w[0].1 < w[1].1);
+        if !valid {
+            entries.clear();
+        }
+
+        Ok((Self { path, base_offset, entries }, valid))
+    }
+
+    /// timestamp 보다 작은 최대 timestamp 를 가진 마지막 엔트리의 offset
+    /// 그 offset 의 배치까지는 모두 timestamp 미만이므로 거기서부터 훑으면 됨
+    pub fn lookup(&self, timestamp: i64) -> i64 {
+        let idx = self.entries.partition_point(|(entry_timestamp, _)| *entry_timestamp < timestamp);
+        if idx == 0 {
+            self.base_offset
+        } else {
+            self.base_offset + self.entries[idx - 1].1 as i64
+        }
+    }
+
+    pub fn last_timestamp(&self) -> Option<i64> {
+        self.entries.last().map(|(timestamp, _)| *timestamp)
+    }
+
+    pub async fn append(&mut self, timestamp: i64, offset: i64) -> Result<()> {
+        // timestamp 가 늘어나지 않으면 기록할 필요 없음
+        if self.last_timestamp().is_some_and(|last| last >= timestamp) {
+            return Ok(());
+        }
+
+        let entry = (timestamp, (offset - self.base_offset) as i32);
+        let mut bytes = Vec::with_capacity(Self::ENTRY_SIZE);
+        bytes.extend_from_slice(&entry.0.to_be_bytes());
+        bytes.extend_from_slice(&entry.1.to_be_bytes());
+        append_index_file(&self.path, &bytes).await?;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    pub async fn rebuild(&mut self, entries: Vec<(i64, i64)>) -> Result<()> {
+        self.entries.clear();
+        for (timestamp, offset) in entries {
+            if self.entries.last().is_none_or(|(last, _)| *last < timestamp) {
+                self.entries.push((timestamp, (offset - self.base_offset) as i32));
+            }
+        }
+        let mut bytes = Vec::with_capacity(self.entries.len() * Self::ENTRY_SIZE);
+        for (timestamp, offset) in &self.entries {
+            bytes.extend_from_slice(&timestamp.to_be_bytes());
+            bytes.extend_from_slice(&offset.to_be_bytes());
+        }
+        fs::write(&self.path, bytes).await.map_err(ApplicationError::Io)
+    }
+}
+
+async fn read_index_file(path: &Path) -> Result<Vec<u8>> {
+    match fs::read(path).await {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(ApplicationError::Io(e)),
+    }
+}
+
+async fn append_index_file(path: &Path, bytes: &[u8]) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .map_err(ApplicationError::Io)?;
+    file.write_all(bytes).await.map_err(ApplicationError::Io)?;
+    file.flush().await.map_err(ApplicationError::Io)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_offset_index_lookup() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let path = dir.path().join("00000000000000000100.index");
+
+        let (mut index, _) = OffsetIndex::open(path.clone(), 100, 0).await?;
+        index.append(110, 4096).await?;
+        index.append(125, 8192).await?;
+
+        assert_eq!(index.lookup(100), 0);
+        assert_eq!(index.lookup(109), 0);
+        assert_eq!(index.lookup(110), 4096);
+        assert_eq!(index.lookup(124), 4096);
+        assert_eq!(index.lookup(1000), 8192);
+
+        let (reopened, valid) = OffsetIndex::open(path.clone(), 100, 10000).await?;
+        assert!(valid);
+        assert_eq!(reopened.lookup(126), 8192);
+
+        // position 이 로그 크기를 넘으면 깨진 인덱스로 취급
+        let (broken, valid) = OffsetIndex::open(path, 100, 5000).await?;
+        assert!(!valid);
+        assert_eq!(broken.lookup(126), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_time_index_lookup() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let path = dir.path().join("00000000000000000000.timeindex");
+
+        let (mut index, _) = TimeIndex::open(path.clone(), 0).await?;
+        index.append(1000, 10).await?;
+        index.append(900, 15).await?; // 줄어든 timestamp 는 무시
+        index.append(2000, 20).await?;
+
+        assert_eq!(index.lookup(500), 0);
+        assert_eq!(index.lookup(1000), 0);
+        assert_eq!(index.lookup(1001), 10);
+        assert_eq!(index.lookup(5000), 20);
+
+        let (reopened, valid) = TimeIndex::open(path, 0).await?;
+        assert!(valid);
+        assert_eq!(reopened.last_timestamp(), Some(2000));
+        Ok(())
+    }
+}
@@ -0,0 +1,4 @@
+pub mod batch;
+pub mod index;
+pub mod partition_log;
+pub mod segment;
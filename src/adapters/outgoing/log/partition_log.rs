@@ -0,0 +1,545 @@
+use crate::adapters::outgoing::log::batch::{assign_offsets, batches, set_log_append_time};
+use crate::adapters::outgoing::log::segment::LogSegment;
+use crate::adapters::outgoing::protocol::compression::CompressionConfig;
+use crate::adapters::outgoing::protocol::legacy_message::{is_legacy_message_set, up_convert};
+use crate::adapters::outgoing::protocol::record_batch::{CompressionType, RecordBatch, TimestampType};
+use crate::application::error::ApplicationError;
+use crate::domain::error::DomainError;
+use crate::domain::message::LogAppendInfo;
+use crate::Result;
+use bytes::{Buf, Bytes};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tokio::fs;
+
+/// log.segment.bytes / segment.bytes 의 최솟값 (magic v0 메시지 하나의 크기)
+pub const MIN_SEGMENT_BYTES: u64 = 14;
+/// log.segment.bytes / segment.bytes 의 최댓값. offset index 의 position 이 i32 라서 그 이상은 가리킬 수 없음
+pub const MAX_SEGMENT_BYTES: u64 = i32::MAX as u64;
+
+/// Kafka 가 받아들이는 토픽 설정 이름 (org.apache.kafka.storage.internals.log.LogConfig)
+/// 이 중 로그에 적용되는 것은 segment.bytes / segment.ms / index.interval.bytes / compression.type / message.timestamp.type / retention.ms / retention.bytes 뿐
+pub const TOPIC_CONFIG_NAMES: &[&str] = &[
+    "cleanup.policy",
+    "compression.gzip.level",
+    "compression.lz4.level",
+    "compression.type",
+    "compression.zstd.level",
+    "delete.retention.ms",
+    "file.delete.delay.ms",
+    "flush.messages",
+    "flush.ms",
+    "follower.replication.throttled.replicas",
+    "index.interval.bytes",
+    "leader.replication.throttled.replicas",
+    "local.retention.bytes",
+    "local.retention.ms",
+    "max.compaction.lag.ms",
+    "max.message.bytes",
+    "message.timestamp.after.max.ms",
+    "message.timestamp.before.max.ms",
+    "message.timestamp.type",
+    "min.cleanable.dirty.ratio",
+    "min.compaction.lag.ms",
+    "min.insync.replicas",
+    "preallocate",
+    "remote.storage.enable",
+    "retention.bytes",
+    "retention.ms",
+    "segment.bytes",
+    "segment.index.bytes",
+    "segment.jitter.ms",
+    "segment.ms",
+    "unclean.leader.election.enable",
+];
+
+/// 세그먼트 롤링과 인덱싱, 압축, timestamp, retention 설정
+/// (log.segment.bytes / log.roll.ms / log.index.interval.bytes / compression.type / message.timestamp.type / log.retention.ms / log.retention.bytes)
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    pub segment_bytes: u64,
+    pub segment_ms: i64,
+    pub index_interval_bytes: u64,
+    pub compression_type: CompressionConfig,
+    pub timestamp_type: TimestampType,
+    /// -1 이면 무제한
+    pub retention_ms: i64,
+    /// -1 이면 무제한
+    pub retention_bytes: i64,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            segment_bytes: 1024 * 1024 * 1024,
+            segment_ms: 7 * 24 * 60 * 60 * 1000,
+            index_interval_bytes: 4096,
+            compression_type: CompressionConfig::Producer,
+            timestamp_type: TimestampType::CreateTime,
+            retention_ms: 7 * 24 * 60 * 60 * 1000,
+            retention_bytes: -1,
+        }
+    }
+}
+
+impl LogConfig {
+    /// 토픽 설정 (CreateTopics 의 configs) 으로 덮어쓴 설정
+    /// 모르는 설정 이름이나 잘못된 값이면 ApplicationError::Config
+    pub fn with_topic_configs(&self, configs: &[(String, String)]) -> Result<Self> {
+        let mut config = self.clone();
+        for (name, value) in configs {
+            let invalid = || ApplicationError::Config(format!("invalid value for {}: {}", name, value));
+            match name.as_str() {
+                "segment.bytes" => {
+                    config.segment_bytes = value.parse().ok()
+                        .filter(|bytes| (MIN_SEGMENT_BYTES..=MAX_SEGMENT_BYTES).contains(bytes))
+                        .ok_or_else(invalid)?;
+                },
+                "segment.ms" => config.segment_ms = value.parse().ok().filter(|ms| *ms >= 1).ok_or_else(invalid)?,
+                "index.interval.bytes" => config.index_interval_bytes = value.parse().map_err(|_| invalid())?,
+                "compression.type" => config.compression_type = value.parse().map_err(|_| invalid())?,
+                "message.timestamp.type" => config.timestamp_type = value.parse().map_err(|_| invalid())?,
+                "retention.ms" => config.retention_ms = value.parse().ok().filter(|ms| *ms >= -1).ok_or_else(invalid)?,
+                "retention.bytes" => config.retention_bytes = value.parse().ok().filter(|bytes| *bytes >= -1).ok_or_else(invalid)?,
+                name if TOPIC_CONFIG_NAMES.contains(&name) => {},
+                name => return Err(ApplicationError::Config(format!("unknown topic config: {}", name))),
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// 하나의 파티션 디렉토리 (`{topic}-{partition}`) 에 있는 세그먼트들
+pub struct PartitionLog {
+    dir: PathBuf,
+    config: LogConfig,
+    /// base offset -> 세그먼트. 마지막 세그먼트가 active
+    segments: BTreeMap<i64, LogSegment>,
+    /// 토픽이 지워져서 더는 쓰면 안 되는 로그
+    deleted: bool,
+}
+
+impl PartitionLog {
+    /// 디렉토리의 `*.log` 파일들로 세그먼트를 복구. 디렉토리가 없으면 빈 로그
+    pub async fn open(dir: PathBuf, config: LogConfig) -> Result<Self> {
+        let mut base_offsets = Vec::new();
+        match fs::read_dir(&dir).await {
+            Ok(mut entries) => {
+                while let Some(entry) = entries.next_entry().await.map_err(ApplicationError::Io)? {
+                    let path = entry.path();
+                    if path.extension().is_some_and(|ext| ext == "log") {
+                        if let Some(base_offset) = path.file_stem()
+                            .and_then(|stem| stem.to_str())
+                            .and_then(|stem| stem.parse::<i64>().ok())
+                        {
+                            base_offsets.push(base_offset);
+                        }
+                    }
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {},
+            Err(e) => return Err(ApplicationError::Io(e)),
+        }
+
+        // 깨끗한 종료를 기록하지 않으므로 active 세그먼트는 항상 비정상 종료 뒤처럼 전부 검사
+        let active_base_offset = base_offsets.iter().max().copied();
+        let mut segments = BTreeMap::new();
+        for base_offset in base_offsets {
+            let segment = if Some(base_offset) == active_base_offset {
+                LogSegment::recover_active(&dir, base_offset, config.index_interval_bytes).await?
+            } else {
+                LogSegment::open(&dir, base_offset, config.index_interval_bytes).await?
+            };
+            segments.insert(base_offset, segment);
+        }
+        println!("[DEBUG] Opened partition log {:?} with {} segments", dir, segments.len());
+
+        Ok(Self { dir, config, segments, deleted: false })
+    }
+
+    /// 토픽 삭제 중에 표시. 이후 append 는 디렉토리를 다시 만들지 않고 실패
+    pub fn mark_deleted(&mut self) {
+        self.deleted = true;
+    }
+
+    pub fn next_offset(&self) -> i64 {
+        self.segments.values().next_back().map(|segment| segment.next_offset()).unwrap_or(0)
+    }
+
+    /// 가장 오래된 세그먼트의 base offset. 세그먼트가 없으면 log end 와 같음
+    pub fn log_start_offset(&self) -> i64 {
+        self.segments.keys().next().copied().unwrap_or_else(|| self.next_offset())
+    }
+
+    /// offset 을 할당해서 active 세그먼트에 추가하고 첫 배치의 base offset 을 반환
+    /// LogAppendTime 토픽이면 배치의 max timestamp 를 지금 시각으로 찍음
+    /// 크기나 시간이 한도를 넘으면 새 세그먼트로 롤링
+    pub async fn append(&mut self, records: &[u8]) -> Result<LogAppendInfo> {
+        if self.deleted {
+            return Err(DomainError::UnknownTopicOrPartition(self.dir.display().to_string()).into());
+        }
+        let base_offset = self.next_offset();
+        // magic v0 / v1 메시지 셋은 v2 배치로 바꿔서 저장
+        let records: Cow<[u8]> = if is_legacy_message_set(records) {
+            up_convert(records)?.into()
+        } else {
+            records.into()
+        };
+        let mut records = match self.config.compression_type {
+            CompressionConfig::Producer => records.into_owned(),
+            CompressionConfig::Codec(codec) => recompress(&records, codec)?,
+        };
+        assign_offsets(&mut records, base_offset)?;
+        let log_append_time = match self.config.timestamp_type {
+            TimestampType::CreateTime => -1,
+            TimestampType::LogAppendTime => {
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |elapsed| elapsed.as_millis() as i64);
+                set_log_append_time(&mut records, now);
+                now
+            },
+        };
+
+        let max_timestamp = batches(&records).map(|(_, header)| header.max_timestamp).max().unwrap_or(-1);
+        if self.should_roll(records.len() as u64, max_timestamp) {
+            println!("[DEBUG] Rolling new segment {:?} at offset {}", self.dir, base_offset);
+            fs::create_dir_all(&self.dir).await.map_err(ApplicationError::Io)?;
+            let segment = LogSegment::open(&self.dir, base_offset, self.config.index_interval_bytes).await?;
+            self.segments.insert(base_offset, segment);
+        }
+
+        let active = self.segments.values_mut().next_back()
+            .ok_or_else(|| ApplicationError::Protocol("no active segment".to_string()))?;
+        active.append(&records).await?;
+
+        println!("[DEBUG] Appended {} bytes to {:?} at offset {}", records.len(), self.dir, base_offset);
+        Ok(LogAppendInfo { base_offset, log_append_time })
+    }
+
+    fn should_roll(&self, incoming_size: u64, incoming_max_timestamp: i64) -> bool {
+        let Some(active) = self.segments.values().next_back() else {
+            return true;
+        };
+        if active.size() == 0 {
+            return false;
+        }
+
+        active.size() + incoming_size > self.config.segment_bytes
+            || active.rolling_timestamp()
+                .is_some_and(|rolling| incoming_max_timestamp.saturating_sub(rolling) > self.config.segment_ms)
+    }
+
+    /// retention.ms / retention.bytes 를 넘은 오래된 세그먼트를 앞에서부터 지우고 지운 개수를 반환
+    /// active 세그먼트는 지우지 않음. 지운 만큼 log start offset 이 올라감
+    pub async fn delete_expired_segments(&mut self, now: i64) -> Result<usize> {
+        if self.deleted {
+            return Ok(0);
+        }
+        let mut total_size: u64 = self.segments.values().map(|segment| segment.size()).sum();
+        let mut deleted = 0;
+        while self.segments.len() > 1 {
+            let Some(oldest) = self.segments.values().next() else {
+                break;
+            };
+            let expired_by_time = self.config.retention_ms >= 0
+                && oldest.max_timestamp() >= 0
+                && now.saturating_sub(oldest.max_timestamp()) > self.config.retention_ms;
+            let expired_by_size = self.config.retention_bytes >= 0
+                && total_size - oldest.size() >= self.config.retention_bytes as u64;
+            if !expired_by_time && !expired_by_size {
+                break;
+            }
+
+            let Some((base_offset, segment)) = self.segments.pop_first() else {
+                break;
+            };
+            total_size -= segment.size();
+            segment.delete().await?;
+            deleted += 1;
+            println!("[DEBUG] Deleted segment {:?} at offset {} by retention", self.dir, base_offset);
+        }
+        Ok(deleted)
+    }
+
+    /// timestamp 가 target 이상인 첫 레코드의 (timestamp, offset)
+    pub async fn find_offset_by_timestamp(&self, target: i64) -> Result<Option<(i64, i64)>> {
+        for segment in self.segments.values() {
+            if let Some(found) = segment.find_offset_by_timestamp(target).await? {
+                return Ok(Some(found));
+            }
+        }
+        Ok(None)
+    }
+
+    /// 로그 전체에서 timestamp 가 가장 큰 (같으면 가장 앞의) 레코드의 (timestamp, offset)
+    pub async fn find_max_timestamp_offset(&self) -> Result<Option<(i64, i64)>> {
+        let mut max_segment: Option<&LogSegment> = None;
+        for segment in self.segments.values() {
+            if segment.max_timestamp() >= 0 && max_segment.is_none_or(|max| segment.max_timestamp() > max.max_timestamp()) {
+                max_segment = Some(segment);
+            }
+        }
+        match max_segment {
+            Some(segment) => segment.find_offset_by_timestamp(segment.max_timestamp()).await,
+            None => Ok(None),
+        }
+    }
+
+    /// offset 을 포함하는 세그먼트에서 그 offset 의 배치부터 max_bytes 까지 읽음
+    /// log end 와 같은 offset 은 빈 결과, 범위 밖이면 OffsetOutOfRange
+    pub async fn read(&self, offset: i64, max_bytes: usize) -> Result<Option<Vec<u8>>> {
+        if offset < self.log_start_offset() || offset > self.next_offset() {
+            return Err(ApplicationError::Domain(DomainError::OffsetOutOfRange(offset)));
+        }
+
+        match self.segments.range(..=offset).next_back() {
+            Some((_, segment)) => segment.read(offset, max_bytes).await,
+            None => Ok(None),
+        }
+    }
+}
+
+/// compression.type 과 다른 코덱으로 온 배치를 풀어서 그 코덱으로 다시 압축. 같은 코덱이면 그대로 둠
+fn recompress(records: &[u8], codec: CompressionType) -> Result<Vec<u8>> {
+    let mut src = Bytes::copy_from_slice(records);
+    let mut recompressed = Vec::with_capacity(records.len());
+    while src.has_remaining() {
+        let start = records.len() - src.remaining();
+        let mut batch = RecordBatch::from_bytes(&mut src)?;
+        if batch.compression_type()? == codec {
+            recompressed.extend_from_slice(&records[start..records.len() - src.remaining()]);
+        } else {
+            batch.set_compression_type(codec);
+            recompressed.extend(batch.to_bytes()?);
+        }
+    }
+    Ok(recompressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::outgoing::log::batch::tests::{record_batch, record_batch_with_timestamps};
+    use crate::adapters::outgoing::log::batch::{BatchHeader, BATCH_OVERHEAD};
+
+    fn small_config() -> LogConfig {
+        LogConfig {
+            segment_bytes: BATCH_OVERHEAD as u64 * 3,
+            segment_ms: 10_000,
+            index_interval_bytes: 1,
+            compression_type: CompressionConfig::Producer,
+            timestamp_type: TimestampType::CreateTime,
+            retention_ms: -1,
+            retention_bytes: -1,
+        }
+    }
+
+    fn segment_files(dir: &std::path::Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir).unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .filter(|name| name.ends_with(".log"))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn test_partition_log_rolls_by_size_and_time() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let log_dir = dir.path().join("foo-0");
+        let mut log = PartitionLog::open(log_dir.clone(), small_config()).await?;
+
+        for i in 0..7 {
+            assert_eq!(log.append(&record_batch(0, 1000 + i)).await?.base_offset, i);
+        }
+        // 시간 한도를 넘는 배치는 새 세그먼트에서 시작
+        assert_eq!(log.append(&record_batch(0, 20_000)).await?.base_offset, 7);
+
+        assert_eq!(segment_files(&log_dir), vec![
+            "00000000000000000000.log",
+            "00000000000000000003.log",
+            "00000000000000000006.log",
+            "00000000000000000007.log",
+        ]);
+
+        let records = log.read(4, usize::MAX).await?.unwrap();
+        assert_eq!(BatchHeader::parse(&records).unwrap().base_offset, 4);
+        assert_eq!(records.len(), BATCH_OVERHEAD * 2);
+        assert!(log.read(8, usize::MAX).await?.is_none());
+        assert!(matches!(
+            log.read(9, usize::MAX).await,
+            Err(ApplicationError::Domain(DomainError::OffsetOutOfRange(9)))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_partition_log_deletes_expired_segments() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let log_dir = dir.path().join("foo-0");
+        let mut log = PartitionLog::open(log_dir.clone(), LogConfig { retention_ms: 1000, ..small_config() }).await?;
+        for i in 0..7 {
+            log.append(&record_batch(0, 1000 + i)).await?;
+        }
+
+        // 시간: max timestamp (1002 / 1005) 에서 retention.ms 가 지난 세그먼트만 지움
+        assert_eq!(log.delete_expired_segments(1500).await?, 0);
+        assert_eq!(log.delete_expired_segments(2004).await?, 1);
+        assert_eq!((log.log_start_offset(), log.next_offset()), (3, 7));
+        assert!(matches!(
+            log.read(2, usize::MAX).await,
+            Err(ApplicationError::Domain(DomainError::OffsetOutOfRange(2)))
+        ));
+
+        // 크기: 지워도 retention.bytes 이상 남는 세그먼트만 지우고, active 세그먼트는 남김
+        let mut log = PartitionLog::open(log_dir.clone(), LogConfig { retention_bytes: BATCH_OVERHEAD as i64, ..small_config() }).await?;
+        assert_eq!(log.delete_expired_segments(i64::MAX).await?, 1);
+        assert_eq!(segment_files(&log_dir), vec!["00000000000000000006.log"]);
+        assert_eq!(log.log_start_offset(), 6);
+        assert_eq!(log.delete_expired_segments(i64::MAX).await?, 0);
+        assert_eq!(log.append(&record_batch(0, 1007)).await?.base_offset, 7);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_partition_log_reopen() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let log_dir = dir.path().join("foo-0");
+        let mut log = PartitionLog::open(log_dir.clone(), small_config()).await?;
+        for i in 0..5 {
+            log.append(&record_batch(1, 1000 + i)).await?;
+        }
+
+        let mut reopened = PartitionLog::open(log_dir, small_config()).await?;
+        assert_eq!(reopened.next_offset(), 10);
+        assert_eq!(reopened.append(&record_batch(0, 2000)).await?.base_offset, 10);
+
+        let records = reopened.read(7, usize::MAX).await?.unwrap();
+        assert_eq!(BatchHeader::parse(&records).unwrap().base_offset, 6);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_partition_log_reopen_checks_whole_active_segment() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let log_dir = dir.path().join("foo-0");
+        let mut log = PartitionLog::open(log_dir.clone(), LogConfig {
+            segment_bytes: BATCH_OVERHEAD as u64 * 4,
+            ..small_config()
+        }).await?;
+        for i in 0..6 {
+            log.append(&record_batch(0, 1000 + i)).await?;
+        }
+
+        // active 세그먼트 (base offset 4) 의 첫 배치를 망가뜨림. 인덱스 엔트리가 그 뒤에 있어도 잡아야 함
+        let active_path = log_dir.join("00000000000000000004.log");
+        let mut content = std::fs::read(&active_path).map_err(ApplicationError::Io)?;
+        content[40] ^= 0xff;
+        std::fs::write(&active_path, content).map_err(ApplicationError::Io)?;
+
+        let reopened = PartitionLog::open(log_dir, small_config()).await?;
+        assert_eq!(reopened.next_offset(), 4);
+        assert_eq!(std::fs::metadata(&active_path).map_err(ApplicationError::Io)?.len(), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_partition_log_timestamp_lookup() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let mut log = PartitionLog::open(dir.path().join("foo-0"), LogConfig {
+            segment_bytes: 1,
+            ..small_config()
+        }).await?;
+        assert_eq!(log.find_max_timestamp_offset().await?, None);
+
+        // 세그먼트마다 배치 하나
+        log.append(&record_batch_with_timestamps(&[100, 500])).await?;
+        log.append(&record_batch_with_timestamps(&[200, 300])).await?;
+        log.append(&record_batch_with_timestamps(&[500, 400])).await?;
+
+        assert_eq!(log.find_offset_by_timestamp(150).await?, Some((500, 1)));
+        assert_eq!(log.find_offset_by_timestamp(501).await?, None);
+        assert_eq!(log.find_max_timestamp_offset().await?, Some((500, 1)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_partition_log_recompresses_to_topic_codec() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let mut log = PartitionLog::open(dir.path().join("foo-0"), LogConfig {
+            compression_type: CompressionConfig::Codec(CompressionType::Zstd),
+            ..small_config()
+        }).await?;
+
+        let mut gzip = RecordBatch::from_bytes(&mut Bytes::from(record_batch_with_timestamps(&[100, 200])))?;
+        gzip.set_compression_type(CompressionType::Gzip);
+        log.append(&gzip.to_bytes()?).await?;
+        log.append(&record_batch_with_timestamps(&[300])).await?;
+
+        let mut records = Bytes::from(log.read(0, usize::MAX).await?.unwrap());
+        for (base_offset, timestamps) in [(0, vec![100, 200]), (2, vec![300])] {
+            let batch = RecordBatch::from_bytes(&mut records)?;
+            assert_eq!(batch.compression_type()?, CompressionType::Zstd);
+            assert_eq!(batch.base_offset, base_offset);
+            let record_timestamps: Vec<i64> = batch.records.iter()
+                .map(|record| batch.base_timestamp + record.timestamp_delta)
+                .collect();
+            assert_eq!(record_timestamps, timestamps);
+        }
+        assert!(!records.has_remaining());
+
+        // 압축된 배치 안의 레코드 timestamp 로 찾음
+        assert_eq!(log.find_offset_by_timestamp(150).await?, Some((200, 1)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_partition_log_stamps_log_append_time() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let mut log = PartitionLog::open(dir.path().join("foo-0"), LogConfig {
+            timestamp_type: TimestampType::LogAppendTime,
+            ..small_config()
+        }).await?;
+
+        let info = log.append(&record_batch_with_timestamps(&[100, 200])).await?;
+        assert_eq!(info.base_offset, 0);
+        assert!(info.log_append_time > 200);
+
+        // 배치에 LogAppendTime 표시와 append 시각이 찍히고, 레코드는 그대로 crc 가 맞아야 함
+        let batch = RecordBatch::from_bytes(&mut Bytes::from(log.read(0, usize::MAX).await?.unwrap()))?;
+        assert_eq!(batch.timestamp_type(), TimestampType::LogAppendTime);
+        assert_eq!(batch.max_timestamp, info.log_append_time);
+        assert_eq!(batch.records.len(), 2);
+        assert_eq!(log.find_offset_by_timestamp(300).await?, Some((info.log_append_time, 0)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_log_config_with_topic_configs() -> Result<()> {
+        let configs = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+        };
+
+        let config = small_config().with_topic_configs(&configs(&[
+            ("segment.bytes", "1048576"),
+            ("compression.type", "lz4"),
+            ("cleanup.policy", "compact"),
+            ("message.timestamp.type", "LogAppendTime"),
+            ("retention.ms", "60000"),
+        ]))?;
+        assert_eq!(config.segment_bytes, 1048576);
+        assert_eq!((config.retention_ms, config.retention_bytes), (60000, -1));
+        assert_eq!(config.timestamp_type, TimestampType::LogAppendTime);
+        assert_eq!(config.compression_type, CompressionConfig::Codec(CompressionType::Lz4));
+        assert_eq!(config.segment_ms, small_config().segment_ms);
+
+        for invalid in [("segment.bytes", "10"), ("segment.bytes", "2147483648"), ("segment.ms", "0"), ("compression.type", "brotli"),
+            ("message.timestamp.type", "NoTimestamp"), ("retention.ms", "-2"), ("retention.bytes", "big"), ("no.such.config", "1")] {
+            let result = small_config().with_topic_configs(&configs(&[invalid]));
+            assert!(matches!(result, Err(ApplicationError::Config(_))), "{:?}", invalid);
+        }
+        Ok(())
+    }
+}
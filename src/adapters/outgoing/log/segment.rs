@@ -0,0 +1,410 @@
+use crate::adapters::outgoing::log::batch::{batches, find_record_by_timestamp, has_valid_crc, BatchHeader, BATCH_OVERHEAD};
+use crate::adapters::outgoing::log::index::{OffsetIndex, TimeIndex};
+use crate::application::error::ApplicationError;
+use crate::Result;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+/// 세그먼트 파일 이름: base offset 을 20 자리로 0 패딩 (예: 00000000000000000042.log)
+pub fn segment_file_name(base_offset: i64, extension: &str) -> String {
+    format!("{:020}.{}", base_offset, extension)
+}
+
+/// 하나의 로그 세그먼트: `.log` + 희소 `.index` / `.timeindex`
+pub struct LogSegment {
+    base_offset: i64,
+    log_path: PathBuf,
+    offset_index: OffsetIndex,
+    time_index: TimeIndex,
+    size: u64,
+    next_offset: i64,
+    max_timestamp: i64,
+    /// 첫 배치의 max timestamp. segment.ms 롤링 기준
+    rolling_timestamp: Option<i64>,
+    bytes_since_last_index: u64,
+    index_interval_bytes: u64,
+}
+
+impl LogSegment {
+    /// 세그먼트를 열고 (없으면 빈 세그먼트), 인덱스 검증과 잘린 꼬리 정리를 수행
+    pub async fn open(dir: &Path, base_offset: i64, index_interval_bytes: u64) -> Result<Self> {
+        Self::open_with_recovery(dir, base_offset, index_interval_bytes, false).await
+    }
+
+    /// 비정상 종료 뒤의 active 세그먼트용. 인덱스를 믿지 않고 처음부터 모든 배치의 crc 를 검사한 뒤 인덱스를 다시 만듦
+    pub async fn recover_active(dir: &Path, base_offset: i64, index_interval_bytes: u64) -> Result<Self> {
+        Self::open_with_recovery(dir, base_offset, index_interval_bytes, true).await
+    }
+
+    async fn open_with_recovery(dir: &Path, base_offset: i64, index_interval_bytes: u64, recover_all: bool) -> Result<Self> {
+        let log_path = dir.join(segment_file_name(base_offset, "log"));
+        let log_size = match fs::metadata(&log_path).await {
+            Ok(metadata) => metadata.len(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(ApplicationError::Io(e)),
+        };
+
+        let (offset_index, offset_index_valid) = OffsetIndex::open(
+            dir.join(segment_file_name(base_offset, "index")),
+            base_offset,
+            log_size,
+        ).await?;
+        let (time_index, time_index_valid) = TimeIndex::open(
+            dir.join(segment_file_name(base_offset, "timeindex")),
+            base_offset,
+        ).await?;
+
+        let mut segment = Self {
+            base_offset,
+            log_path,
+            offset_index,
+            time_index,
+            size: 0,
+            next_offset: base_offset,
+            max_timestamp: -1,
+            rolling_timestamp: None,
+            bytes_since_last_index: 0,
+            index_interval_bytes,
+        };
+        segment.recover(log_size, recover_all || !(offset_index_valid && time_index_valid)).await?;
+        Ok(segment)
+    }
+
+    /// 마지막 인덱스 엔트리 이후 (인덱스를 다시 만들면 처음부터) 를 훑어서
+    /// next offset, 크기, max timestamp 를 복구하고 잘리거나 crc 가 맞지 않는 배치부터 잘라냄
+    async fn recover(&mut self, log_size: u64, rebuild_indexes: bool) -> Result<()> {
+        if log_size == 0 {
+            if rebuild_indexes {
+                self.offset_index.rebuild(Vec::new()).await?;
+                self.time_index.rebuild(Vec::new()).await?;
+            }
+            return Ok(());
+        }
+
+        let start = if rebuild_indexes { 0 } else { self.offset_index.last_position() };
+        let content = self.read_range(start, log_size).await?;
+
+        let mut offset_entries = Vec::new();
+        let mut time_entries = Vec::new();
+        let mut valid_end = start;
+        self.max_timestamp = if rebuild_indexes { -1 } else { self.time_index.last_timestamp().unwrap_or(-1) };
+        for (position, header) in batches(&content) {
+            if !has_valid_crc(&content[position..position + header.size()]) {
+                println!("[DEBUG] Corrupt batch at offset {} in {:?}", header.base_offset, self.log_path);
+                break;
+            }
+            let position = start + position as u64;
+            if rebuild_indexes && self.bytes_since_last_index > self.index_interval_bytes {
+                offset_entries.push((header.base_offset, position));
+                if self.max_timestamp >= 0 {
+                    time_entries.push((self.max_timestamp, header.base_offset));
+                }
+                self.bytes_since_last_index = 0;
+            }
+            self.track_batch(&header);
+            valid_end = position + header.size() as u64;
+        }
+
+        if rebuild_indexes {
+            println!("[DEBUG] Rebuilding indexes for {:?}", self.log_path);
+            self.offset_index.rebuild(offset_entries).await?;
+            self.time_index.rebuild(time_entries).await?;
+        }
+
+        if valid_end < log_size {
+            println!("[DEBUG] Truncating {:?} from {} to {} bytes", self.log_path, log_size, valid_end);
+            let file = OpenOptions::new()
+                .write(true)
+                .open(&self.log_path)
+                .await
+                .map_err(ApplicationError::Io)?;
+            file.set_len(valid_end).await.map_err(ApplicationError::Io)?;
+        }
+        self.size = valid_end;
+
+        if self.size > 0 {
+            let first = self.read_range(0, self.size.min(BATCH_OVERHEAD as u64)).await?;
+            self.rolling_timestamp = BatchHeader::parse(&first).map(|h| h.max_timestamp);
+        }
+        Ok(())
+    }
+
+    fn track_batch(&mut self, header: &BatchHeader) {
+        self.max_timestamp = self.max_timestamp.max(header.max_timestamp);
+        self.next_offset = header.next_offset();
+        self.bytes_since_last_index += header.size() as u64;
+        if self.rolling_timestamp.is_none() {
+            self.rolling_timestamp = Some(header.max_timestamp);
+        }
+    }
+
+    async fn read_range(&self, start: u64, end: u64) -> Result<Vec<u8>> {
+        let mut file = fs::File::open(&self.log_path).await.map_err(ApplicationError::Io)?;
+        file.seek(SeekFrom::Start(start)).await.map_err(ApplicationError::Io)?;
+        let mut content = vec![0; end.saturating_sub(start) as usize];
+        file.read_exact(&mut content).await.map_err(ApplicationError::Io)?;
+        Ok(content)
+    }
+
+    pub fn base_offset(&self) -> i64 {
+        self.base_offset
+    }
+
+    pub fn next_offset(&self) -> i64 {
+        self.next_offset
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn max_timestamp(&self) -> i64 {
+        self.max_timestamp
+    }
+
+    pub fn rolling_timestamp(&self) -> Option<i64> {
+        self.rolling_timestamp
+    }
+
+    /// offset 이 이미 할당된 배치들을 세그먼트 끝에 추가하고 인덱스를 갱신
+    pub async fn append(&mut self, records: &[u8]) -> Result<()> {
+        if let Some(parent) = self.log_path.parent() {
+            fs::create_dir_all(parent).await.map_err(ApplicationError::Io)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_path)
+            .await
+            .map_err(ApplicationError::Io)?;
+        file.write_all(records).await.map_err(ApplicationError::Io)?;
+        file.flush().await.map_err(ApplicationError::Io)?;
+
+        let start = self.size;
+        for (position, header) in batches(records) {
+            if self.bytes_since_last_index > self.index_interval_bytes {
+                self.offset_index.append(header.base_offset, start + position as u64).await?;
+                if self.max_timestamp >= 0 {
+                    self.time_index.append(self.max_timestamp, header.base_offset).await?;
+                }
+                self.bytes_since_last_index = 0;
+            }
+            self.track_batch(&header);
+        }
+        self.size += records.len() as u64;
+        Ok(())
+    }
+
+    /// offset 을 포함하는 배치부터 max_bytes 안에 들어가는 온전한 배치들
+    /// 첫 배치가 max_bytes 보다 커도 그 배치 하나는 돌려줘서 컨슈머가 진행할 수 있게 함
+    /// 인덱스로 시작 위치를 찾고 그 뒤로는 배치 헤더만 따라감
+    pub async fn read(&self, offset: i64, max_bytes: usize) -> Result<Option<Vec<u8>>> {
+        if offset >= self.next_offset || self.size == 0 {
+            return Ok(None);
+        }
+
+        let mut file = fs::File::open(&self.log_path).await.map_err(ApplicationError::Io)?;
+        let mut position = self.offset_index.lookup(offset);
+        let mut start = None;
+        let mut end = position;
+        while let Some(header) = self.read_header_at(&mut file, position).await? {
+            let next_position = position + header.size() as u64;
+            match start {
+                None if header.last_offset() < offset => {
+                    position = next_position;
+                    continue;
+                },
+                None => start = Some(position),
+                Some(start) if (next_position - start) as usize > max_bytes => break,
+                Some(_) => {},
+            }
+            position = next_position;
+            end = position;
+        }
+
+        match start {
+            Some(start) => Ok(Some(self.read_range(start, end.min(self.size)).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// timestamp 가 target 이상인 첫 레코드의 (timestamp, offset)
+    /// time index 로 시작 offset 을 찾고, offset index 로 위치를 찾은 뒤 배치를 훑음
+    pub async fn find_offset_by_timestamp(&self, target: i64) -> Result<Option<(i64, i64)>> {
+        if self.size == 0 || self.max_timestamp < target {
+            return Ok(None);
+        }
+
+        let mut file = fs::File::open(&self.log_path).await.map_err(ApplicationError::Io)?;
+        let mut position = self.offset_index.lookup(self.time_index.lookup(target));
+        while let Some(header) = self.read_header_at(&mut file, position).await? {
+            if header.max_timestamp >= target {
+                let batch = self.read_range(position, position + header.size() as u64).await?;
+                if let Some(found) = find_record_by_timestamp(&batch, target) {
+                    return Ok(Some(found));
+                }
+            }
+            position += header.size() as u64;
+        }
+        Ok(None)
+    }
+
+    /// retention 으로 지울 때 `.log` / `.index` / `.timeindex` 파일을 지움. 이미 없는 파일은 무시
+    pub async fn delete(self) -> Result<()> {
+        for extension in ["log", "index", "timeindex"] {
+            match fs::remove_file(self.log_path.with_extension(extension)).await {
+                Ok(()) => {},
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {},
+                Err(e) => return Err(ApplicationError::Io(e)),
+            }
+        }
+        Ok(())
+    }
+
+    /// position 에 있는 배치 헤더. 세그먼트 끝이거나 헤더가 깨졌으면 None
+    async fn read_header_at(&self, file: &mut fs::File, position: u64) -> Result<Option<BatchHeader>> {
+        if position + BATCH_OVERHEAD as u64 > self.size {
+            return Ok(None);
+        }
+        let mut header_buf = [0u8; BATCH_OVERHEAD];
+        file.seek(SeekFrom::Start(position)).await.map_err(ApplicationError::Io)?;
+        file.read_exact(&mut header_buf).await.map_err(ApplicationError::Io)?;
+        Ok(BatchHeader::parse(&header_buf).filter(|header| header.size() >= BATCH_OVERHEAD))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::outgoing::log::batch::assign_offsets;
+    use crate::adapters::outgoing::log::batch::tests::{record_batch, record_batch_with_timestamps};
+
+    async fn append_batch(segment: &mut LogSegment, last_offset_delta: i32, timestamp: i64) -> Result<()> {
+        let mut records = record_batch(last_offset_delta, timestamp);
+        assign_offsets(&mut records, segment.next_offset())?;
+        segment.append(&records).await
+    }
+
+    #[tokio::test]
+    async fn test_segment_read_uses_index() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        // 배치마다 인덱스 엔트리가 생기도록 간격을 작게 설정
+        let mut segment = LogSegment::open(dir.path(), 0, 1).await?;
+        for i in 0..10 {
+            append_batch(&mut segment, 1, 1000 + i).await?;
+        }
+        assert_eq!(segment.next_offset(), 20);
+        assert_eq!(segment.size(), BATCH_OVERHEAD as u64 * 10);
+
+        let records = segment.read(7, usize::MAX).await?.unwrap();
+        assert_eq!(BatchHeader::parse(&records).unwrap().base_offset, 6);
+        assert_eq!(records.len(), BATCH_OVERHEAD * 7);
+
+        // 온전한 배치만 max_bytes 까지, 그리고 최소 한 배치는 돌려줌
+        assert_eq!(segment.read(7, BATCH_OVERHEAD * 2 + 10).await?.unwrap().len(), BATCH_OVERHEAD * 2);
+        assert_eq!(segment.read(7, 1).await?.unwrap().len(), BATCH_OVERHEAD);
+
+        assert!(segment.read(20, usize::MAX).await?.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_segment_recovers_after_reopen() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let mut segment = LogSegment::open(dir.path(), 0, 1).await?;
+        for i in 0..5 {
+            append_batch(&mut segment, 0, 1000 + i).await?;
+        }
+
+        // 잘린 배치를 꼬리에 붙여서 비정상 종료를 흉내냄
+        let log_path = dir.path().join(segment_file_name(0, "log"));
+        let mut content = std::fs::read(&log_path).map_err(ApplicationError::Io)?;
+        content.extend_from_slice(&record_batch(0, 0)[..20]);
+        std::fs::write(&log_path, content).map_err(ApplicationError::Io)?;
+
+        let reopened = LogSegment::open(dir.path(), 0, 1).await?;
+        assert_eq!(reopened.next_offset(), 5);
+        assert_eq!(reopened.size(), BATCH_OVERHEAD as u64 * 5);
+        assert_eq!(reopened.max_timestamp(), 1004);
+        assert_eq!(reopened.rolling_timestamp(), Some(1000));
+        assert_eq!(std::fs::metadata(&log_path).map_err(ApplicationError::Io)?.len(), BATCH_OVERHEAD as u64 * 5);
+
+        // 인덱스를 지워도 로그에서 다시 만들어야 함
+        std::fs::write(dir.path().join(segment_file_name(0, "index")), [0xff; 3]).map_err(ApplicationError::Io)?;
+        let rebuilt = LogSegment::open(dir.path(), 0, 1).await?;
+        assert_eq!(rebuilt.next_offset(), 5);
+        let records = rebuilt.read(3, usize::MAX).await?.unwrap();
+        assert_eq!(BatchHeader::parse(&records).unwrap().base_offset, 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_segment_truncates_corrupt_tail() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let mut segment = LogSegment::open(dir.path(), 0, 4096).await?;
+        for i in 0..4 {
+            append_batch(&mut segment, 0, 1000 + i).await?;
+        }
+
+        // 세 번째 배치의 max timestamp 를 망가뜨림 (길이는 그대로라 crc 로만 알 수 있음)
+        let log_path = dir.path().join(segment_file_name(0, "log"));
+        let mut content = std::fs::read(&log_path).map_err(ApplicationError::Io)?;
+        content[BATCH_OVERHEAD * 2 + 40] ^= 0xff;
+        std::fs::write(&log_path, content).map_err(ApplicationError::Io)?;
+
+        let reopened = LogSegment::open(dir.path(), 0, 4096).await?;
+        assert_eq!(reopened.next_offset(), 2);
+        assert_eq!(reopened.max_timestamp(), 1001);
+        assert_eq!(std::fs::metadata(&log_path).map_err(ApplicationError::Io)?.len(), BATCH_OVERHEAD as u64 * 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_active_segment_recovery_checks_every_batch() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let mut segment = LogSegment::open(dir.path(), 0, 1).await?;
+        for i in 0..6 {
+            append_batch(&mut segment, 0, 1000 + i).await?;
+        }
+
+        // 마지막 인덱스 엔트리보다 앞의 배치를 망가뜨림
+        let log_path = dir.path().join(segment_file_name(0, "log"));
+        let mut content = std::fs::read(&log_path).map_err(ApplicationError::Io)?;
+        content[BATCH_OVERHEAD * 2 + 40] ^= 0xff;
+        std::fs::write(&log_path, content).map_err(ApplicationError::Io)?;
+
+        // 인덱스를 믿으면 마지막 엔트리 뒤만 검사해서 놓침
+        assert_eq!(LogSegment::open(dir.path(), 0, 1).await?.next_offset(), 6);
+
+        let mut recovered = LogSegment::recover_active(dir.path(), 0, 1).await?;
+        assert_eq!(recovered.next_offset(), 2);
+        assert_eq!(recovered.max_timestamp(), 1001);
+        assert_eq!(std::fs::metadata(&log_path).map_err(ApplicationError::Io)?.len(), BATCH_OVERHEAD as u64 * 2);
+
+        // 잘린 뒤를 가리키던 인덱스 엔트리도 사라져서 이어 쓴 배치를 제대로 찾음
+        append_batch(&mut recovered, 0, 2000).await?;
+        let records = recovered.read(2, usize::MAX).await?.unwrap();
+        assert_eq!(BatchHeader::parse(&records).unwrap().base_offset, 2);
+        assert_eq!(records.len(), BATCH_OVERHEAD);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_segment_find_offset_by_timestamp() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let mut segment = LogSegment::open(dir.path(), 0, 1).await?;
+        for timestamps in [[100, 150], [120, 300], [250, 400]] {
+            let mut records = record_batch_with_timestamps(&timestamps);
+            assign_offsets(&mut records, segment.next_offset())?;
+            segment.append(&records).await?;
+        }
+
+        assert_eq!(segment.find_offset_by_timestamp(0).await?, Some((100, 0)));
+        assert_eq!(segment.find_offset_by_timestamp(151).await?, Some((300, 3)));
+        assert_eq!(segment.find_offset_by_timestamp(301).await?, Some((400, 5)));
+        assert_eq!(segment.find_offset_by_timestamp(401).await?, None);
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
 use async_trait::async_trait;
 use crate::ports::outgoing::message_store::MessageStore;
-use crate::adapters::incoming::protocol::messages::KafkaMessage;
+use crate::domain::message::{LogAppendInfo, PartitionOffsets, TimestampOffset};
 use crate::Result;
+use std::ops::Range;
 
 pub struct MemoryMessageStore {
 }
@@ -12,13 +13,43 @@ impl MemoryMessageStore {
     }
 }
 
+impl Default for MemoryMessageStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[async_trait]
 impl MessageStore for MemoryMessageStore {
-    async fn store_message(&self, _message: KafkaMessage) -> Result<()> {
+    async fn create_topic(&self, _topic_name: &str, _num_partitions: i32, _configs: &[(String, String)]) -> Result<()> {
+        Ok(())
+    }
+
+    async fn create_partitions(&self, _topic_name: &str, _partitions: Range<i32>) -> Result<()> {
         Ok(())
     }
 
-    async fn read_messages(&self, _topic_id: &str, _partition: i32, _offset: i64) -> Result<Option<Vec<u8>>> {
+    async fn delete_topic(&self, _topic_name: &str) -> Result<()> {
+        Ok(())
+    }
+
+    async fn store_message(&self, _topic_name: &str, _partition: i32, _records: &[u8]) -> Result<LogAppendInfo> {
+        Ok(LogAppendInfo { base_offset: 0, log_append_time: -1 })
+    }
+
+    async fn read_messages(&self, _topic_id: &str, _partition: i32, _offset: i64, _max_bytes: i32) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    async fn get_partition_offsets(&self, _topic_name: &str, _partition: i32) -> Result<PartitionOffsets> {
+        Ok(PartitionOffsets::new(0, 0))
+    }
+
+    async fn find_offset_by_timestamp(&self, _topic_name: &str, _partition: i32, _timestamp: i64) -> Result<Option<TimestampOffset>> {
         Ok(None)
     }
-} 
\ No newline at end of file
+
+    async fn find_max_timestamp_offset(&self, _topic_name: &str, _partition: i32) -> Result<Option<TimestampOffset>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,421 @@
+use crate::adapters::incoming::protocol::messages::ErrorCode;
+use crate::adapters::outgoing::protocol::kraft_record::{RecordValue, NO_LEADER_CHANGE, TOPIC_RESOURCE_TYPE};
+use crate::domain::message::Partition;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// 메타데이터 로그를 끝까지 반영한 클러스터 상태
+/// 한 번 만든 이미지는 바뀌지 않고, 레코드는 MetadataDelta 에 모았다가 새 이미지로 만듦
+#[derive(Debug, Clone, Default)]
+pub struct MetadataImage {
+    /// feature 이름 -> 레벨 (metadata.version 등)
+    features: HashMap<String, u16>,
+    brokers: BTreeMap<i32, BrokerRegistration>,
+    /// 토픽 ID -> 토픽. 바뀌지 않은 토픽은 이미지끼리 공유함
+    topics: HashMap<String, Arc<TopicImage>>,
+    topic_ids_by_name: HashMap<String, String>,
+    /// (resource_type, resource_name) -> 설정 이름 -> 값
+    configs: HashMap<(i8, String), BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopicImage {
+    pub name: String,
+    pub id: String,
+    pub partitions: BTreeMap<u32, Partition>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokerRegistration {
+    pub broker_id: i32,
+    pub epoch: i64,
+    pub rack: Option<String>,
+    pub fenced: bool,
+}
+
+impl MetadataImage {
+    pub fn topic_by_name(&self, name: &str) -> Option<&TopicImage> {
+        self.topic_ids_by_name.get(name).and_then(|id| self.topic_by_id(id))
+    }
+
+    pub fn topic_by_id(&self, id: &str) -> Option<&TopicImage> {
+        self.topics.get(id).map(Arc::as_ref)
+    }
+
+    pub fn topics(&self) -> impl Iterator<Item = &TopicImage> {
+        self.topics.values().map(Arc::as_ref)
+    }
+
+    pub fn feature_level(&self, name: &str) -> Option<u16> {
+        self.features.get(name).copied()
+    }
+
+    pub fn brokers(&self) -> impl Iterator<Item = &BrokerRegistration> {
+        self.brokers.values()
+    }
+
+    /// 리소스에 지정된 설정. 없으면 빈 목록
+    pub fn configs(&self, resource_type: i8, resource_name: &str) -> Vec<(String, String)> {
+        self.configs.get(&(resource_type, resource_name.to_string()))
+            .map(|configs| configs.iter().map(|(name, value)| (name.clone(), value.clone())).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// 이미지 위에 쌓이는 변경분. 바뀐 항목만 새 값으로 들고 있다가 apply 에서 이미지에 덮어씀
+pub struct MetadataDelta {
+    base: Arc<MetadataImage>,
+    /// 레벨 0 은 feature 를 지움
+    features: HashMap<String, u16>,
+    /// None 이면 브로커를 지움
+    brokers: HashMap<i32, Option<BrokerRegistration>>,
+    /// 토픽 ID -> 바뀐 토픽. None 이면 토픽을 지움
+    topics: HashMap<String, Option<TopicImage>>,
+    /// 바뀐 리소스의 설정 전체. 비었으면 리소스를 지움
+    configs: HashMap<(i8, String), BTreeMap<String, String>>,
+}
+
+impl MetadataDelta {
+    pub fn new(base: Arc<MetadataImage>) -> Self {
+        Self {
+            base,
+            features: HashMap::new(),
+            brokers: HashMap::new(),
+            topics: HashMap::new(),
+            configs: HashMap::new(),
+        }
+    }
+
+    /// 레코드 하나를 변경분에 반영. 이 델타가 다루지 않는 레코드는 무시함
+    pub fn replay(&mut self, value: &RecordValue) {
+        match value {
+            RecordValue::RegisterBroker(broker) => {
+                self.brokers.insert(broker.broker_id, Some(BrokerRegistration {
+                    broker_id: broker.broker_id,
+                    epoch: broker.broker_epoch,
+                    rack: broker.rack.clone(),
+                    fenced: broker.fenced,
+                }));
+            }
+            RecordValue::UnregisterBroker(unregister) => {
+                // 다시 등록된 브로커를 이전 epoch 의 해제로 지우지 않음
+                match self.broker(unregister.broker_id) {
+                    Some(broker) if broker.epoch == unregister.broker_epoch => {
+                        self.brokers.insert(unregister.broker_id, None);
+                    }
+                    _ => println!("[DEBUG] Ignoring unregistration of broker {} epoch {}", unregister.broker_id, unregister.broker_epoch),
+                }
+            }
+            RecordValue::BrokerRegistrationChange(change) => {
+                let Some(mut broker) = self.broker(change.broker_id).filter(|broker| broker.epoch == change.broker_epoch) else {
+                    println!("[DEBUG] Ignoring change of broker {} epoch {}", change.broker_id, change.broker_epoch);
+                    return;
+                };
+                match change.fenced {
+                    1 => broker.fenced = true,
+                    -1 => broker.fenced = false,
+                    _ => {}
+                }
+                self.brokers.insert(change.broker_id, Some(broker));
+            }
+            RecordValue::FeatureLevel(feature) => {
+                self.features.insert(feature.name.clone(), feature.level);
+            }
+            RecordValue::Topic(topic) => {
+                self.topics.insert(topic.topic_id.clone(), Some(TopicImage {
+                    name: topic.topic_name.clone(),
+                    id: topic.topic_id.clone(),
+                    partitions: BTreeMap::new(),
+                }));
+            }
+            RecordValue::Partition(p) => {
+                let Some(mut topic) = self.topic(&p.topic_id) else {
+                    println!("[DEBUG] Ignoring partition {} of unknown topic {}", p.partition_id, p.topic_id);
+                    return;
+                };
+                topic.partitions.insert(p.partition_id, Partition::new(
+                    i16::from(ErrorCode::None),
+                    p.partition_id,
+                    p.leader_id,
+                    p.leader_epoch,
+                    p.replicas.clone(),
+                    p.in_sync_replicas.clone(),
+                    Vec::new(),
+                    Vec::new(),
+                    Vec::new(),
+                ));
+                self.topics.insert(p.topic_id.clone(), Some(topic));
+            }
+            RecordValue::PartitionChange(change) => {
+                let Some(mut topic) = self.topic(&change.topic_id) else {
+                    println!("[DEBUG] Ignoring change of partition {} of unknown topic {}", change.partition_id, change.topic_id);
+                    return;
+                };
+                let Some(partition) = topic.partitions.get_mut(&change.partition_id) else {
+                    println!("[DEBUG] Ignoring change of unknown partition {}-{}", change.topic_id, change.partition_id);
+                    return;
+                };
+                if let Some(replicas) = &change.replicas {
+                    partition.replicas = replicas.clone();
+                }
+                if let Some(in_sync_replicas) = &change.in_sync_replicas {
+                    partition.in_sync_replicas = in_sync_replicas.clone();
+                }
+                if let Some(elr) = &change.eligible_leader_replicas {
+                    partition.eligible_leader_replicas = elr.clone();
+                }
+                if let Some(last_known_elr) = &change.last_known_elr {
+                    partition.last_known_eligible_leader_replicas = last_known_elr.clone();
+                }
+                // 리더가 바뀌면 leader epoch 가 올라감
+                if change.leader_id != NO_LEADER_CHANGE {
+                    partition.leader_id = change.leader_id as u32;
+                    partition.leader_epoch += 1;
+                }
+                self.topics.insert(change.topic_id.clone(), Some(topic));
+            }
+            RecordValue::Config(config) => {
+                let mut configs = self.resource_configs(config.resource_type, &config.resource_name);
+                match &config.value {
+                    Some(value) => configs.insert(config.name.clone(), value.clone()),
+                    None => configs.remove(&config.name),
+                };
+                self.configs.insert((config.resource_type, config.resource_name.clone()), configs);
+            }
+            RecordValue::RemoveTopic(remove) => {
+                // 토픽을 지우면 토픽 설정도 같이 사라짐
+                if let Some(topic) = self.topic(&remove.topic_id) {
+                    self.configs.insert((TOPIC_RESOURCE_TYPE, topic.name), BTreeMap::new());
+                }
+                self.topics.insert(remove.topic_id.clone(), None);
+            }
+            RecordValue::UserScramCredential(_)
+            | RecordValue::ClientQuota(_)
+            | RecordValue::ProducerIds(_)
+            | RecordValue::AccessControlEntry(_)
+            | RecordValue::RemoveAccessControlEntry(_)
+            | RecordValue::NoOp
+            | RecordValue::ZkMigrationState(_)
+            | RecordValue::BeginTransaction(_)
+            | RecordValue::EndTransaction => {}
+        }
+    }
+
+    /// 변경분을 덮어쓴 새 이미지
+    pub fn apply(self) -> MetadataImage {
+        let mut image = MetadataImage::clone(&self.base);
+        for (name, level) in self.features {
+            if level == 0 {
+                image.features.remove(&name);
+            } else {
+                image.features.insert(name, level);
+            }
+        }
+        for (broker_id, broker) in self.brokers {
+            match broker {
+                Some(broker) => image.brokers.insert(broker_id, broker),
+                None => image.brokers.remove(&broker_id),
+            };
+        }
+        for (id, topic) in self.topics {
+            if let Some(old) = image.topics.remove(&id) {
+                if image.topic_ids_by_name.get(&old.name) == Some(&id) {
+                    image.topic_ids_by_name.remove(&old.name);
+                }
+            }
+            if let Some(topic) = topic {
+                image.topic_ids_by_name.insert(topic.name.clone(), id.clone());
+                image.topics.insert(id, Arc::new(topic));
+            }
+        }
+        for (resource, configs) in self.configs {
+            if configs.is_empty() {
+                image.configs.remove(&resource);
+            } else {
+                image.configs.insert(resource, configs);
+            }
+        }
+        image
+    }
+
+    /// 이 델타까지 반영한 토픽
+    fn topic(&self, id: &str) -> Option<TopicImage> {
+        match self.topics.get(id) {
+            Some(topic) => topic.clone(),
+            None => self.base.topic_by_id(id).cloned(),
+        }
+    }
+
+    fn broker(&self, broker_id: i32) -> Option<BrokerRegistration> {
+        match self.brokers.get(&broker_id) {
+            Some(broker) => broker.clone(),
+            None => self.base.brokers.get(&broker_id).cloned(),
+        }
+    }
+
+    fn resource_configs(&self, resource_type: i8, resource_name: &str) -> BTreeMap<String, String> {
+        let resource = (resource_type, resource_name.to_string());
+        self.configs.get(&resource)
+            .or_else(|| self.base.configs.get(&resource))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::outgoing::protocol::kraft_record::{BrokerRegistrationChangeValue, ConfigValue, FeatureLevelValue, PartitionChangeValue, PartitionValue, RegisterBrokerValue, RemoveTopicValue, TopicValue, UnregisterBrokerValue};
+
+    fn topic(name: &str, id: &str) -> RecordValue {
+        RecordValue::Topic(TopicValue { topic_name: name.to_string(), topic_id: id.to_string() })
+    }
+
+    fn partition(id: &str, partition_id: u32, leader_id: u32) -> RecordValue {
+        RecordValue::Partition(PartitionValue {
+            partition_id,
+            topic_id: id.to_string(),
+            replicas: vec![leader_id],
+            in_sync_replicas: vec![leader_id],
+            removing_replicas: vec![],
+            adding_replicas: vec![],
+            leader_id,
+            leader_epoch: 0,
+            partition_epoch: 0,
+            directories: vec![],
+        })
+    }
+
+    fn config(name: &str, value: Option<&str>) -> RecordValue {
+        RecordValue::Config(ConfigValue {
+            resource_type: TOPIC_RESOURCE_TYPE,
+            resource_name: "foo".to_string(),
+            name: name.to_string(),
+            value: value.map(str::to_string),
+        })
+    }
+
+    fn replay(base: MetadataImage, values: &[RecordValue]) -> MetadataImage {
+        let mut delta = MetadataDelta::new(Arc::new(base));
+        for value in values {
+            delta.replay(value);
+        }
+        delta.apply()
+    }
+
+    #[test]
+    fn test_delta_builds_topics_and_configs() {
+        let image = replay(MetadataImage::default(), &[
+            RecordValue::FeatureLevel(FeatureLevelValue { name: "metadata.version".to_string(), level: 20 }),
+            topic("foo", "id-foo"),
+            config("cleanup.policy", Some("compact")),
+            config("retention.ms", Some("1000")),
+            partition("id-foo", 0, 1),
+            partition("unknown", 0, 1),
+        ]);
+        assert_eq!(image.feature_level("metadata.version"), Some(20));
+        assert_eq!(image.topic_by_name("foo").unwrap().id, "id-foo");
+        assert_eq!(image.topic_by_id("id-foo").unwrap().partitions.len(), 1);
+        assert_eq!(image.topics().count(), 1);
+
+        // 다음 델타는 이전 이미지를 바꾸지 않음
+        let next = replay(image.clone(), &[
+            partition("id-foo", 1, 2),
+            partition("id-foo", 0, 2),
+            config("retention.ms", None),
+        ]);
+        assert_eq!(image.topic_by_name("foo").unwrap().partitions[&0].leader_id, 1);
+        let foo = next.topic_by_name("foo").unwrap();
+        assert_eq!(foo.partitions.keys().copied().collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(foo.partitions[&0].leader_id, 2);
+        assert_eq!(next.configs(TOPIC_RESOURCE_TYPE, "foo"), vec![("cleanup.policy".to_string(), "compact".to_string())]);
+        assert_eq!(image.configs(TOPIC_RESOURCE_TYPE, "foo").len(), 2);
+    }
+
+    #[test]
+    fn test_delta_removes_topic_and_recreates_name() {
+        let image = replay(MetadataImage::default(), &[
+            topic("foo", "id-1"),
+            config("cleanup.policy", Some("compact")),
+            partition("id-1", 0, 1),
+        ]);
+
+        // 같은 델타 안에서 지우고 같은 이름으로 다시 만듦
+        let image = replay(image, &[
+            RecordValue::RemoveTopic(RemoveTopicValue { topic_id: "id-1".to_string() }),
+            topic("foo", "id-2"),
+        ]);
+        assert!(image.topic_by_id("id-1").is_none());
+        let foo = image.topic_by_name("foo").unwrap();
+        assert_eq!(foo.id, "id-2");
+        assert!(foo.partitions.is_empty());
+        assert!(image.configs(TOPIC_RESOURCE_TYPE, "foo").is_empty());
+
+        let image = replay(image, &[RecordValue::RemoveTopic(RemoveTopicValue { topic_id: "id-2".to_string() })]);
+        assert!(image.topic_by_name("foo").is_none());
+        assert_eq!(image.topics().count(), 0);
+    }
+
+    fn register_broker(broker_id: i32, broker_epoch: i64) -> RecordValue {
+        RecordValue::RegisterBroker(RegisterBrokerValue {
+            broker_id,
+            is_migrating_zk_broker: false,
+            incarnation_id: "00000000-0000-0000-0000-000000000001".to_string(),
+            broker_epoch,
+            end_points: vec![],
+            features: vec![],
+            rack: None,
+            fenced: true,
+            in_controlled_shutdown: false,
+            log_dirs: vec![],
+        })
+    }
+
+    #[test]
+    fn test_delta_tracks_broker_registrations() {
+        let image = replay(MetadataImage::default(), &[
+            register_broker(1, 10),
+            register_broker(2, 20),
+            RecordValue::BrokerRegistrationChange(BrokerRegistrationChangeValue {
+                broker_id: 1,
+                broker_epoch: 10,
+                fenced: -1,
+                in_controlled_shutdown: 0,
+                log_dirs: None,
+            }),
+        ]);
+        let fenced = image.brokers().map(|b| (b.broker_id, b.fenced)).collect::<Vec<_>>();
+        assert_eq!(fenced, vec![(1, false), (2, true)]);
+
+        // 이전 epoch 의 해제는 무시함
+        let image = replay(image, &[
+            RecordValue::UnregisterBroker(UnregisterBrokerValue { broker_id: 1, broker_epoch: 9 }),
+            RecordValue::UnregisterBroker(UnregisterBrokerValue { broker_id: 2, broker_epoch: 20 }),
+        ]);
+        assert_eq!(image.brokers().map(|b| b.broker_id).collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn test_delta_applies_partition_change() {
+        let image = replay(MetadataImage::default(), &[topic("foo", "id-foo"), partition("id-foo", 0, 1)]);
+        let image = replay(image, &[RecordValue::PartitionChange(PartitionChangeValue {
+            partition_id: 0,
+            topic_id: "id-foo".to_string(),
+            in_sync_replicas: Some(vec![1, 2]),
+            leader_id: 2,
+            replicas: Some(vec![1, 2]),
+            removing_replicas: None,
+            adding_replicas: None,
+            leader_recovery_state: -1,
+            eligible_leader_replicas: Some(vec![1]),
+            last_known_elr: Some(vec![]),
+            directories: None,
+        })]);
+        let partition = &image.topic_by_name("foo").unwrap().partitions[&0];
+        assert_eq!((partition.leader_id, partition.leader_epoch), (2, 1));
+        assert_eq!(partition.replicas, vec![1, 2]);
+        assert_eq!(partition.in_sync_replicas, vec![1, 2]);
+        assert_eq!(partition.eligible_leader_replicas, vec![1]);
+        assert!(partition.last_known_eligible_leader_replicas.is_empty());
+    }
+}
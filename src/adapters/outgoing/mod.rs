@@ -1,4 +1,6 @@
 pub mod kraft_metadata_store;
+pub mod log;
+pub mod metadata_image;
 pub mod memory_store;
 pub mod protocol;
-pub mod disk_store; 
\ No newline at end of file
+pub mod disk_store;
@@ -0,0 +1,260 @@
+use crate::adapters::outgoing::protocol::record_batch::CompressionType;
+use crate::application::error::ApplicationError;
+use std::io::{Read, Write};
+use std::str::FromStr;
+
+/// xerial snappy-java 스트림 헤더 (magic + version 1 + compatible version 1)
+const XERIAL_HEADER: [u8; 16] = [0x82, b'S', b'N', b'A', b'P', b'P', b'Y', 0, 0, 0, 0, 1, 0, 0, 0, 1];
+/// Kafka 자바 클라이언트의 SnappyOutputStream 블록 크기
+const XERIAL_BLOCK_SIZE: usize = 32 * 1024;
+
+const LZ4_FRAME_MAGIC: [u8; 4] = [0x04, 0x22, 0x4d, 0x18];
+const LZ4_CONTENT_SIZE_FLAG: u8 = 0x08;
+const LZ4_DICT_ID_FLAG: u8 = 0x01;
+
+/// Kafka 기본값과 같은 zstd 레벨
+const ZSTD_LEVEL: i32 = 3;
+
+/// 배치 하나를 풀었을 때 허용하는 최대 크기
+/// 압축된 배치 한도 (max.message.bytes 기본값 약 1MB) 에 넉넉한 압축률 64 배를 곱한 값
+/// 작은 배치가 수 GB 로 풀려서 브로커 메모리를 다 쓰지 못하게 막음
+pub const MAX_DECOMPRESSED_BYTES: usize = 64 * 1024 * 1024;
+
+/// broker / topic 의 compression.type
+/// Producer 면 프로듀서가 보낸 코덱을 그대로 두고, 아니면 append 할 때 그 코덱으로 다시 압축
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompressionConfig {
+    #[default]
+    Producer,
+    Codec(CompressionType),
+}
+
+impl FromStr for CompressionConfig {
+    type Err = ApplicationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "producer" => Ok(CompressionConfig::Producer),
+            "uncompressed" => Ok(CompressionConfig::Codec(CompressionType::None)),
+            "gzip" => Ok(CompressionConfig::Codec(CompressionType::Gzip)),
+            "snappy" => Ok(CompressionConfig::Codec(CompressionType::Snappy)),
+            "lz4" => Ok(CompressionConfig::Codec(CompressionType::Lz4)),
+            "zstd" => Ok(CompressionConfig::Codec(CompressionType::Zstd)),
+            _ => Err(ApplicationError::Protocol(format!("invalid compression.type: {}", value))),
+        }
+    }
+}
+
+/// 배치의 records 부분을 압축. snappy 는 자바 클라이언트처럼 xerial 프레이밍을 씀
+pub fn compress(compression: CompressionType, data: &[u8]) -> Result<Vec<u8>, ApplicationError> {
+    match compression {
+        CompressionType::None => Ok(data.to_vec()),
+        CompressionType::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(data).map_err(ApplicationError::Io)?;
+            encoder.finish().map_err(ApplicationError::Io)
+        }
+        CompressionType::Snappy => {
+            let mut encoder = snap::raw::Encoder::new();
+            let mut buf = XERIAL_HEADER.to_vec();
+            for chunk in data.chunks(XERIAL_BLOCK_SIZE) {
+                let block = encoder.compress_vec(chunk).map_err(compression_error)?;
+                buf.extend_from_slice(&(block.len() as i32).to_be_bytes());
+                buf.extend_from_slice(&block);
+            }
+            Ok(buf)
+        }
+        CompressionType::Lz4 => {
+            // KafkaLZ4BlockOutputStream 과 같은 설정: 64KB 독립 블록, 체크섬 없음
+            let frame_info = lz4_flex::frame::FrameInfo::new()
+                .block_size(lz4_flex::frame::BlockSize::Max64KB)
+                .block_mode(lz4_flex::frame::BlockMode::Independent);
+            let mut encoder = lz4_flex::frame::FrameEncoder::with_frame_info(frame_info, Vec::new());
+            encoder.write_all(data).map_err(ApplicationError::Io)?;
+            encoder.finish().map_err(compression_error)
+        }
+        CompressionType::Zstd => zstd::encode_all(data, ZSTD_LEVEL).map_err(ApplicationError::Io),
+    }
+}
+
+/// 배치의 records 부분을 풀어냄. 풀린 크기가 max_size 를 넘으면 ApplicationError::Protocol
+pub fn decompress(compression: CompressionType, data: &[u8], max_size: usize) -> Result<Vec<u8>, ApplicationError> {
+    match compression {
+        CompressionType::None => {
+            check_decompressed_size(data.len(), max_size)?;
+            Ok(data.to_vec())
+        }
+        CompressionType::Gzip => read_limited(flate2::read::GzDecoder::new(data), max_size),
+        CompressionType::Snappy => decompress_snappy(data, max_size),
+        CompressionType::Lz4 => {
+            if !data.starts_with(&LZ4_FRAME_MAGIC) {
+                return Err(ApplicationError::Protocol("lz4: missing frame magic".to_string()));
+            }
+            let data = fix_lz4_header_checksum(data);
+            read_limited(lz4_flex::frame::FrameDecoder::new(&data[..]), max_size)
+        }
+        CompressionType::Zstd => read_limited(zstd::stream::read::Decoder::new(data).map_err(compression_error)?, max_size),
+    }
+}
+
+/// max_size 보다 한 바이트 더 읽어보고 넘치면 에러. 그 이상은 메모리에 올리지 않음
+fn read_limited(reader: impl Read, max_size: usize) -> Result<Vec<u8>, ApplicationError> {
+    let mut buf = Vec::new();
+    reader.take(max_size as u64 + 1).read_to_end(&mut buf).map_err(compression_error)?;
+    check_decompressed_size(buf.len(), max_size)?;
+    Ok(buf)
+}
+
+fn check_decompressed_size(size: usize, max_size: usize) -> Result<(), ApplicationError> {
+    if size > max_size {
+        return Err(ApplicationError::Protocol(format!("decompressed records exceed {} bytes", max_size)));
+    }
+    Ok(())
+}
+
+/// xerial 프레이밍 (자바 / librdkafka) 이면 블록마다 풀고, 아니면 (sarama 등) raw snappy 로 취급
+/// 블록마다 풀기 전에 헤더의 원래 길이로 max_size 를 검사
+fn decompress_snappy(data: &[u8], max_size: usize) -> Result<Vec<u8>, ApplicationError> {
+    let mut decoder = snap::raw::Decoder::new();
+    let Some(mut blocks) = data.strip_prefix(&XERIAL_HEADER[..8]) else {
+        check_decompressed_size(snap::raw::decompress_len(data).map_err(compression_error)?, max_size)?;
+        return decoder.decompress_vec(data).map_err(compression_error);
+    };
+    if blocks.len() < 8 {
+        return Err(ApplicationError::Protocol("snappy: truncated xerial header".to_string()));
+    }
+    blocks = &blocks[8..]; // version, compatible version
+
+    let mut buf = Vec::new();
+    while !blocks.is_empty() {
+        if blocks.len() < 4 {
+            return Err(ApplicationError::Protocol("snappy: truncated block length".to_string()));
+        }
+        let len = i32::from_be_bytes([blocks[0], blocks[1], blocks[2], blocks[3]]);
+        let block = usize::try_from(len).ok()
+            .and_then(|len| blocks.get(4..4 + len))
+            .ok_or_else(|| ApplicationError::Protocol(format!("snappy: invalid block length {}", len)))?;
+        check_decompressed_size(buf.len() + snap::raw::decompress_len(block).map_err(compression_error)?, max_size)?;
+        buf.extend(decoder.decompress_vec(block).map_err(compression_error)?);
+        blocks = &blocks[4 + block.len()..];
+    }
+    Ok(buf)
+}
+
+/// magic v0 / v1 메시지 셋의 wrapper value 를 압축
+/// magic v0 의 lz4 는 자바 클라이언트가 기대하는 대로 헤더 체크섬을 magic 까지 포함해서 계산 (KAFKA-3160)
+pub fn compress_legacy(compression: CompressionType, magic: i8, data: &[u8]) -> Result<Vec<u8>, ApplicationError> {
+    let mut compressed = compress(compression, data)?;
+    if compression == CompressionType::Lz4 && magic == 0 {
+        if let Some(checksum_at) = lz4_header_checksum_position(&compressed) {
+            compressed[checksum_at] = lz4_header_checksum(&compressed[..checksum_at]);
+        }
+    }
+    Ok(compressed)
+}
+
+/// magic v0 메시지의 lz4 는 헤더 체크섬을 magic 까지 포함해서 잘못 계산함 (KAFKA-3160)
+/// 그런 프레임이면 표준 체크섬으로 고쳐서 디코더가 읽을 수 있게 함
+fn fix_lz4_header_checksum(data: &[u8]) -> std::borrow::Cow<'_, [u8]> {
+    let Some(checksum_at) = lz4_header_checksum_position(data) else {
+        return data.into();
+    };
+    let checksum = lz4_header_checksum(&data[4..checksum_at]);
+    if data[checksum_at] != checksum && data[checksum_at] == lz4_header_checksum(&data[..checksum_at]) {
+        let mut fixed = data.to_vec();
+        fixed[checksum_at] = checksum;
+        return fixed.into();
+    }
+    data.into()
+}
+
+/// 프레임 헤더 (magic, flags, bd, 선택 필드들) 바로 뒤에 있는 헤더 체크섬 위치
+fn lz4_header_checksum_position(data: &[u8]) -> Option<usize> {
+    let flags = *data.get(4)?;
+    let mut checksum_at = 6;
+    if flags & LZ4_CONTENT_SIZE_FLAG != 0 {
+        checksum_at += 8;
+    }
+    if flags & LZ4_DICT_ID_FLAG != 0 {
+        checksum_at += 4;
+    }
+    (checksum_at < data.len()).then_some(checksum_at)
+}
+
+fn lz4_header_checksum(descriptor: &[u8]) -> u8 {
+    (twox_hash::XxHash32::oneshot(0, descriptor) >> 8) as u8
+}
+
+fn compression_error(e: impl std::fmt::Display) -> ApplicationError {
+    ApplicationError::Protocol(format!("invalid compressed records: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODECS: [CompressionType; 5] = [
+        CompressionType::None,
+        CompressionType::Gzip,
+        CompressionType::Snappy,
+        CompressionType::Lz4,
+        CompressionType::Zstd,
+    ];
+
+    #[test]
+    fn test_compress_round_trip() -> Result<(), ApplicationError> {
+        // 블록 크기 (snappy 32KB, lz4 64KB) 를 넘는 입력
+        let data: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        for codec in CODECS {
+            let compressed = compress(codec, &data)?;
+            assert_eq!(decompress(codec, &compressed, MAX_DECOMPRESSED_BYTES)?, data, "{:?}", codec);
+            assert_eq!(decompress(codec, &compress(codec, &[])?, MAX_DECOMPRESSED_BYTES)?, Vec::<u8>::new(), "{:?}", codec);
+        }
+        assert!(compress(CompressionType::Snappy, &data)?.starts_with(&XERIAL_HEADER));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decompress_framing_quirks() -> Result<(), ApplicationError> {
+        let data = b"hello hello hello kafka".repeat(10);
+
+        // raw snappy (xerial 헤더 없음)
+        let raw = snap::raw::Encoder::new().compress_vec(&data).unwrap();
+        assert_eq!(decompress(CompressionType::Snappy, &raw, MAX_DECOMPRESSED_BYTES)?, data);
+
+        // magic 까지 포함해서 계산한 lz4 헤더 체크섬
+        let mut broken = compress(CompressionType::Lz4, &data)?;
+        broken[6] = lz4_header_checksum(&broken[..6]);
+        assert_eq!(decompress(CompressionType::Lz4, &broken, MAX_DECOMPRESSED_BYTES)?, data);
+        assert_eq!(compress_legacy(CompressionType::Lz4, 0, &data)?, broken);
+
+        for codec in &CODECS[1..] {
+            assert!(decompress(*codec, &[0xde, 0xad, 0xbe, 0xef], MAX_DECOMPRESSED_BYTES).is_err(), "{:?}", codec);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_decompress_rejects_output_over_limit() -> Result<(), ApplicationError> {
+        // 잘 압축되는 입력이 한도보다 크게 풀리는 경우
+        let data = vec![0u8; 200_000];
+        let raw_snappy = snap::raw::Encoder::new().compress_vec(&data).unwrap();
+        for (codec, compressed) in CODECS.iter()
+            .map(|codec| Ok((*codec, compress(*codec, &data)?)))
+            .chain([Ok((CompressionType::Snappy, raw_snappy))])
+            .collect::<Result<Vec<_>, ApplicationError>>()?
+        {
+            assert!(matches!(decompress(codec, &compressed, 100_000), Err(ApplicationError::Protocol(_))), "{:?}", codec);
+            assert_eq!(decompress(codec, &compressed, data.len())?.len(), data.len(), "{:?}", codec);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_compression_config_from_str() {
+        assert_eq!("producer".parse::<CompressionConfig>().unwrap(), CompressionConfig::Producer);
+        assert_eq!("uncompressed".parse::<CompressionConfig>().unwrap(), CompressionConfig::Codec(CompressionType::None));
+        assert_eq!("zstd".parse::<CompressionConfig>().unwrap(), CompressionConfig::Codec(CompressionType::Zstd));
+        assert!("brotli".parse::<CompressionConfig>().is_err());
+    }
+}
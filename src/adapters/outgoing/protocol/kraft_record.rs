@@ -1,45 +1,66 @@
 use bytes::{Buf, Bytes};
+use crate::adapters::outgoing::protocol::record_batch::{Record, RecordBatch};
 use crate::application::error::ApplicationError;
 
 // === Type Definitions ===
 
-#[derive(Debug, Clone)]
-#[allow(dead_code)]
-pub struct RecordBatch {
-    pub base_offset: i64,
-    batch_length: i32,
-    partition_leader_epoch: i32,
-    magic: i8,
-    crc: u32,
-    attributes: i16,
-    last_offset_delta: i32,
-    base_timestamp: i64,
-    max_timestamp: i64,
-    producer_id: i64,
-    producer_epoch: i16,
-    base_sequence: i32,
-    pub records: Vec<Record>,
-}
-
-#[derive(Debug, Clone)]
-#[allow(dead_code)]
-pub struct Record {
-    length: i64,
-    attributes: i8,
-    timestamp_delta: i64,
-    offset_delta: i64,
-    key: Vec<u8>,
-    value_length: i64,
-    pub value: RecordValue,
-    headers: Vec<Header>,
-}
-
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub enum RecordValue {
+    RegisterBroker(RegisterBrokerValue),
+    UnregisterBroker(UnregisterBrokerValue),
     FeatureLevel(FeatureLevelValue),
     Topic(TopicValue),
     Partition(PartitionValue),
+    Config(ConfigValue),
+    PartitionChange(PartitionChangeValue),
+    RemoveTopic(RemoveTopicValue),
+    UserScramCredential(UserScramCredentialValue),
+    ClientQuota(ClientQuotaValue),
+    ProducerIds(ProducerIdsValue),
+    BrokerRegistrationChange(BrokerRegistrationChangeValue),
+    AccessControlEntry(AccessControlEntryValue),
+    RemoveAccessControlEntry(RemoveAccessControlEntryValue),
+    NoOp,
+    ZkMigrationState(ZkMigrationStateValue),
+    BeginTransaction(BeginTransactionValue),
+    EndTransaction,
+}
+
+/// 브로커 등록. 같은 broker_id 의 이전 등록을 대체함
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegisterBrokerValue {
+    pub broker_id: i32,
+    pub is_migrating_zk_broker: bool,
+    pub incarnation_id: String,
+    pub broker_epoch: i64,
+    pub end_points: Vec<BrokerEndpoint>,
+    pub features: Vec<BrokerFeature>,
+    pub rack: Option<String>,
+    pub fenced: bool,
+    pub in_controlled_shutdown: bool,
+    pub log_dirs: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokerEndpoint {
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+    pub security_protocol: i16,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokerFeature {
+    pub name: String,
+    pub min_supported_version: i16,
+    pub max_supported_version: i16,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnregisterBrokerValue {
+    pub broker_id: i32,
+    pub broker_epoch: i64,
 }
 
 #[derive(Debug, Clone)]
@@ -64,196 +85,227 @@ pub struct PartitionValue {
     pub directories: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
-pub struct FeatureLevelValue {
-    #[allow(dead_code)]
-    name: String,
-    #[allow(dead_code)]
-    level: u16,
+/// 브로커 / 토픽 설정 하나. value 가 None 이면 설정을 지움
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigValue {
+    pub resource_type: i8,
+    pub resource_name: String,
+    pub name: String,
+    pub value: Option<String>,
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Header;
-
-pub trait Deserialize<T> {
-    fn deserialize(src: &mut Bytes) -> Result<T, ApplicationError>;
+/// 파티션의 바뀐 부분. 바뀌지 않은 필드는 None (리더는 NO_LEADER_CHANGE)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionChangeValue {
+    pub partition_id: u32,
+    pub topic_id: String,
+    pub in_sync_replicas: Option<Vec<u32>>,
+    pub leader_id: i32,
+    pub replicas: Option<Vec<u32>>,
+    pub removing_replicas: Option<Vec<u32>>,
+    pub adding_replicas: Option<Vec<u32>>,
+    pub leader_recovery_state: i8,
+    pub eligible_leader_replicas: Option<Vec<u32>>,
+    pub last_known_elr: Option<Vec<u32>>,
+    pub directories: Option<Vec<String>>,
 }
 
-pub struct CompactString;
+/// PartitionChangeRecord 의 leader 가 이 값이면 리더는 그대로
+pub const NO_LEADER_CHANGE: i32 = -2;
 
-pub struct CompactArray;
+/// 토픽 삭제. 이 뒤로 topic_id 의 토픽 / 파티션 / 설정은 없는 것으로 취급
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoveTopicValue {
+    pub topic_id: String,
+}
 
-pub struct NullableBytes;
+/// ConfigRecord 의 resource_type (org.apache.kafka.common.config.ConfigResource.Type)
+pub const TOPIC_RESOURCE_TYPE: i8 = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserScramCredentialValue {
+    pub name: String,
+    pub mechanism: i8,
+    pub salt: Vec<u8>,
+    pub stored_key: Vec<u8>,
+    pub server_key: Vec<u8>,
+    pub iterations: i32,
+}
 
-pub struct CompactNullableBytes;
+#[derive(Debug, Clone)]
+pub struct FeatureLevelValue {
+    pub name: String,
+    pub level: u16,
+}
 
-pub struct VarInt;
+/// 엔티티 (user / client-id 조합) 의 쿼터 하나. remove 면 value 는 무시하고 쿼터를 지움
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientQuotaValue {
+    pub entity: Vec<ClientQuotaEntity>,
+    pub key: String,
+    pub value: f64,
+    pub remove: bool,
+}
 
-pub struct Uuid;
+/// entity_name 이 None 이면 그 타입의 기본 엔티티
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientQuotaEntity {
+    pub entity_type: String,
+    pub entity_name: Option<String>,
+}
 
-// === Implementations ===
+/// 브로커에게 할당한 producer id 블록. 다음 블록은 next_producer_id 부터
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProducerIdsValue {
+    pub broker_id: i32,
+    pub broker_epoch: i64,
+    pub next_producer_id: i64,
+}
 
-impl RecordBatch {
-    pub fn from_bytes(src: &mut Bytes) -> Result<Self, ApplicationError> {
-        if src.remaining() < 8 {
-            return Err(ApplicationError::Protocol("buffer too short for base_offset".to_string()));
-        }
-        let base_offset = src.get_i64();
+/// 등록된 브로커의 상태 변경. fenced 는 1 이면 fence, -1 이면 unfence, 0 이면 그대로
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokerRegistrationChangeValue {
+    pub broker_id: i32,
+    pub broker_epoch: i64,
+    pub fenced: i8,
+    pub in_controlled_shutdown: i8,
+    pub log_dirs: Option<Vec<String>>,
+}
 
-        if src.remaining() < 4 {
-            return Err(ApplicationError::Protocol("buffer too short for batch_length".to_string()));
-        }
-        let batch_length = src.get_i32();
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessControlEntryValue {
+    pub id: String,
+    pub resource_type: i8,
+    pub resource_name: String,
+    pub pattern_type: i8,
+    pub principal: String,
+    pub host: String,
+    pub operation: i8,
+    pub permission_type: i8,
+}
 
-        if src.remaining() < 4 {
-            return Err(ApplicationError::Protocol("buffer too short for partition_leader_epoch".to_string()));
-        }
-        let partition_leader_epoch = src.get_i32();
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoveAccessControlEntryValue {
+    pub id: String,
+}
 
-        if src.remaining() < 1 {
-            return Err(ApplicationError::Protocol("buffer too short for magic".to_string()));
-        }
-        let magic = src.get_i8();
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZkMigrationStateValue {
+    pub migration_state: i8,
+}
 
-        if src.remaining() < 4 {
-            return Err(ApplicationError::Protocol("buffer too short for crc".to_string()));
-        }
-        let crc = src.get_u32();
+/// 여러 배치에 걸친 메타데이터 트랜잭션의 시작. EndTransaction 까지가 한 트랜잭션
+#[derive(Debug, Clone, PartialEq)]
+pub struct BeginTransactionValue {
+    pub name: Option<String>,
+}
 
-        if src.remaining() < 2 {
-            return Err(ApplicationError::Protocol("buffer too short for attributes".to_string()));
-        }
-        let attributes = src.get_i16();
+pub trait Deserialize<T> {
+    fn deserialize(src: &mut Bytes) -> Result<T, ApplicationError>;
+}
 
-        if src.remaining() < 4 {
-            return Err(ApplicationError::Protocol("buffer too short for last_offset_delta".to_string()));
-        }
-        let last_offset_delta = src.get_i32();
+pub struct CompactString;
 
-        if src.remaining() < 8 {
-            return Err(ApplicationError::Protocol("buffer too short for base_timestamp".to_string()));
-        }
-        let base_timestamp = src.get_i64();
+pub struct CompactBytes;
 
-        if src.remaining() < 8 {
-            return Err(ApplicationError::Protocol("buffer too short for max_timestamp".to_string()));
-        }
-        let max_timestamp = src.get_i64();
+pub struct CompactArray;
 
-        if src.remaining() < 8 {
-            return Err(ApplicationError::Protocol("buffer too short for producer_id".to_string()));
-        }
-        let producer_id = src.get_i64();
+pub struct VarInt;
 
-        if src.remaining() < 2 {
-            return Err(ApplicationError::Protocol("buffer too short for producer_epoch".to_string()));
-        }
-        let producer_epoch = src.get_i16();
+pub struct Uuid;
 
-        if src.remaining() < 4 {
-            return Err(ApplicationError::Protocol("buffer too short for base_sequence".to_string()));
-        }
-        let base_sequence = src.get_i32();
+/// flexible 버전 구조체 끝의 tagged fields
+pub struct TaggedFields;
 
-        let records = NullableBytes::deserialize::<Record, RecordBatch>(src)?;
+// === Implementations ===
 
-        Ok(Self {
-            base_offset,
-            batch_length,
-            partition_leader_epoch,
-            magic,
-            crc,
-            attributes,
-            last_offset_delta,
-            base_timestamp,
-            max_timestamp,
-            producer_id,
-            producer_epoch,
-            base_sequence,
-            records,
-        })
+impl RecordBatch {
+    /// 메타데이터 로그에 쓸 배치를 만듦. offset delta 는 레코드 순서대로 부여됨
+    pub fn metadata(base_offset: i64, partition_leader_epoch: i32, timestamp: i64, values: Vec<RecordValue>) -> Self {
+        let records = values.iter()
+            .enumerate()
+            .map(|(offset_delta, value)| Record::new(offset_delta as i32, None, Some(value.to_bytes())))
+            .collect();
+        Self::new(base_offset, partition_leader_epoch, timestamp, records)
     }
 }
 
 impl Record {
-    pub fn from_bytes(src: &mut Bytes) -> Result<Self, ApplicationError> {
-        let length = VarInt::deserialize(src)?;
-        
-        if src.remaining() < 1 {
-            return Err(ApplicationError::Protocol("buffer too short for attributes".to_string()));
-        }
-        let attributes = src.get_i8();
-        
-        let timestamp_delta = VarInt::deserialize(src)?;
-        let offset_delta = VarInt::deserialize(src)?;
-        let key = CompactNullableBytes::deserialize(src)?;
-        let value_length = VarInt::deserialize(src)?;
-        let value = RecordValue::from_bytes(src)?;
-        let headers = CompactArray::deserialize::<Header, Record>(src)?;
-
-        Ok(Record {
-            length,
-            attributes,
-            timestamp_delta,
-            offset_delta,
-            key,
-            value_length,
-            value,
-            headers,
-        })
+    /// __cluster_metadata 레코드의 value 를 KRaft 메타데이터 레코드로 해석
+    pub fn metadata_value(&self) -> Result<RecordValue, ApplicationError> {
+        let value = self.value.as_ref()
+            .ok_or_else(|| ApplicationError::Protocol("metadata record without value".to_string()))?;
+        RecordValue::from_bytes(&mut Bytes::copy_from_slice(value))
     }
 }
 
 impl RecordValue {
+    /// 모든 메타데이터 레코드는 flexible 이라 끝에 tagged fields 가 붙음
+    /// 필드로 정의된 tag 만 해석하고 나머지 tag 는 건너뜀
     pub fn from_bytes(src: &mut Bytes) -> Result<Self, ApplicationError> {
-        if src.remaining() < 1 {
-            return Err(ApplicationError::Protocol("buffer too short for frame_version".to_string()));
-        }
-        let frame_version = src.get_u8();
+        let frame_version = read_u8(src, "frame_version")?;
         if frame_version != 1 {
             return Err(ApplicationError::Protocol(format!("invalid frame version: {}", frame_version)));
         }
 
-        if src.remaining() < 1 {
-            return Err(ApplicationError::Protocol("buffer too short for record_type".to_string()));
-        }
-        let record_type = src.get_u8();
-        
+        // record type 과 version 은 unsigned varint
+        let record_type = VarInt::deserialize(src)?;
+        let version = VarInt::deserialize(src)?;
+
         match record_type {
+            0 => {
+                check_version("register broker", version, 3)?;
+                let broker_id = read_i32(src, "broker_id")?;
+                let is_migrating_zk_broker = version >= 2 && read_bool(src, "is_migrating_zk_broker")?;
+                let incarnation_id = Uuid::deserialize(src)?;
+                let broker_epoch = read_i64(src, "broker_epoch")?;
+                let end_points = CompactArray::deserialize::<BrokerEndpoint, BrokerEndpoint>(src)?;
+                let features = CompactArray::deserialize::<BrokerFeature, BrokerFeature>(src)?;
+                let rack = CompactString::deserialize_nullable(src)?;
+                let fenced = read_bool(src, "fenced")?;
+                let in_controlled_shutdown = version >= 1 && read_bool(src, "in_controlled_shutdown")?;
+                let log_dirs = if version >= 3 {
+                    CompactArray::deserialize::<String, PartitionValue>(src)?
+                } else {
+                    Vec::new()
+                };
+                TaggedFields::deserialize(src)?;
+
+                Ok(RecordValue::RegisterBroker(RegisterBrokerValue {
+                    broker_id,
+                    is_migrating_zk_broker,
+                    incarnation_id,
+                    broker_epoch,
+                    end_points,
+                    features,
+                    rack,
+                    fenced,
+                    in_controlled_shutdown,
+                    log_dirs,
+                }))
+            }
+            1 => {
+                check_version("unregister broker", version, 0)?;
+                let broker_id = read_i32(src, "broker_id")?;
+                let broker_epoch = read_i64(src, "broker_epoch")?;
+                TaggedFields::deserialize(src)?;
+
+                Ok(RecordValue::UnregisterBroker(UnregisterBrokerValue { broker_id, broker_epoch }))
+            }
             2 => {
-                if src.remaining() < 1 {
-                    return Err(ApplicationError::Protocol("buffer too short for version".to_string()));
-                }
-                let version = src.get_u8();
-                if version != 0 {
-                    return Err(ApplicationError::Protocol(format!("invalid version for topic record: {}", version)));
-                }
-                
+                check_version("topic", version, 0)?;
                 let topic_name = CompactString::deserialize(src)?;
                 let topic_id = Uuid::deserialize(src)?;
-                let tagged_fields_count = VarInt::deserialize(src)?;
-                if tagged_fields_count != 0 {
-                    return Err(ApplicationError::Protocol(format!("unexpected tagged fields count: {}", tagged_fields_count)));
-                }
-                
+                TaggedFields::deserialize(src)?;
+
                 Ok(RecordValue::Topic(TopicValue {
                     topic_name,
                     topic_id,
                 }))
             }
             3 => {
-                if src.remaining() < 1 {
-                    return Err(ApplicationError::Protocol("buffer too short for version".to_string()));
-                }
-                let version = src.get_u8();
-                if version != 1 {
-                    return Err(ApplicationError::Protocol(format!("invalid version for partition record: {}", version)));
-                }
-
-                if src.remaining() < 4 {
-                    return Err(ApplicationError::Protocol("buffer too short for partition_id".to_string()));
-                }
-                let partition_id = src.get_u32();
+                check_version("partition", version, 2)?;
+                let partition_id = read_u32(src, "partition_id")?;
                 let topic_id = Uuid::deserialize(src)?;
 
                 let replicas = CompactArray::deserialize::<u32, PartitionValue>(src)?;
@@ -261,26 +313,17 @@ impl RecordValue {
                 let removing_replicas = CompactArray::deserialize::<u32, PartitionValue>(src)?;
                 let adding_replicas = CompactArray::deserialize::<u32, PartitionValue>(src)?;
 
-                if src.remaining() < 4 {
-                    return Err(ApplicationError::Protocol("buffer too short for leader_id".to_string()));
-                }
-                let leader_id = src.get_u32();
+                let leader_id = read_u32(src, "leader_id")?;
+                let leader_epoch = read_u32(src, "leader_epoch")?;
+                let partition_epoch = read_u32(src, "partition_epoch")?;
 
-                if src.remaining() < 4 {
-                    return Err(ApplicationError::Protocol("buffer too short for leader_epoch".to_string()));
-                }
-                let leader_epoch = src.get_u32();
-
-                if src.remaining() < 4 {
-                    return Err(ApplicationError::Protocol("buffer too short for partition_epoch".to_string()));
-                }
-                let partition_epoch = src.get_u32();
-
-                let directories = CompactArray::deserialize::<String, PartitionValue>(src)?;
-                let tagged_fields_count = VarInt::deserialize(src)?;
-                if tagged_fields_count != 0 {
-                    return Err(ApplicationError::Protocol(format!("unexpected tagged fields count: {}", tagged_fields_count)));
-                }
+                let directories = if version >= 1 {
+                    CompactArray::deserialize::<String, PartitionValue>(src)?
+                } else {
+                    Vec::new()
+                };
+                // leader recovery state, ELR 같은 tagged 필드는 아직 쓰지 않음
+                TaggedFields::deserialize(src)?;
 
                 Ok(RecordValue::Partition(PartitionValue {
                     partition_id,
@@ -295,43 +338,458 @@ impl RecordValue {
                     directories,
                 }))
             }
-            12 => {
-                if src.remaining() < 1 {
-                    return Err(ApplicationError::Protocol("buffer too short for version".to_string()));
-                }
-                let version = src.get_u8();
-                if version != 0 {
-                    return Err(ApplicationError::Protocol(format!("invalid version for feature level record: {}", version)));
+            4 => {
+                check_version("config", version, 0)?;
+                let resource_type = read_i8(src, "resource_type")?;
+                let resource_name = CompactString::deserialize(src)?;
+                let name = CompactString::deserialize(src)?;
+                let value = CompactString::deserialize_nullable(src)?;
+                TaggedFields::deserialize(src)?;
+
+                Ok(RecordValue::Config(ConfigValue {
+                    resource_type,
+                    resource_name,
+                    name,
+                    value,
+                }))
+            }
+            5 => {
+                check_version("partition change", version, 2)?;
+                let partition_id = read_u32(src, "partition_id")?;
+                let topic_id = Uuid::deserialize(src)?;
+
+                // 바뀐 필드만 tagged 로 들어옴
+                let mut change = PartitionChangeValue {
+                    partition_id,
+                    topic_id,
+                    in_sync_replicas: None,
+                    leader_id: NO_LEADER_CHANGE,
+                    replicas: None,
+                    removing_replicas: None,
+                    adding_replicas: None,
+                    leader_recovery_state: -1,
+                    eligible_leader_replicas: None,
+                    last_known_elr: None,
+                    directories: None,
+                };
+                for (tag, mut field) in TaggedFields::deserialize(src)? {
+                    match tag {
+                        0 => change.in_sync_replicas = CompactArray::deserialize_nullable::<u32, PartitionValue>(&mut field)?,
+                        1 => change.leader_id = read_i32(&mut field, "leader")?,
+                        2 => change.replicas = CompactArray::deserialize_nullable::<u32, PartitionValue>(&mut field)?,
+                        3 => change.removing_replicas = CompactArray::deserialize_nullable::<u32, PartitionValue>(&mut field)?,
+                        4 => change.adding_replicas = CompactArray::deserialize_nullable::<u32, PartitionValue>(&mut field)?,
+                        5 => change.leader_recovery_state = read_i8(&mut field, "leader_recovery_state")?,
+                        6 => change.eligible_leader_replicas = CompactArray::deserialize_nullable::<u32, PartitionValue>(&mut field)?,
+                        7 => change.last_known_elr = CompactArray::deserialize_nullable::<u32, PartitionValue>(&mut field)?,
+                        8 => change.directories = CompactArray::deserialize_nullable::<String, PartitionValue>(&mut field)?,
+                        _ => {}
+                    }
                 }
 
+                Ok(RecordValue::PartitionChange(change))
+            }
+            9 => {
+                check_version("remove topic", version, 0)?;
+                let topic_id = Uuid::deserialize(src)?;
+                TaggedFields::deserialize(src)?;
+
+                Ok(RecordValue::RemoveTopic(RemoveTopicValue { topic_id }))
+            }
+            11 => {
+                check_version("user scram credential", version, 0)?;
                 let name = CompactString::deserialize(src)?;
-                
-                if src.remaining() < 2 {
-                    return Err(ApplicationError::Protocol("buffer too short for level".to_string()));
+                let mechanism = read_i8(src, "mechanism")?;
+                let salt = CompactBytes::deserialize(src)?;
+                let stored_key = CompactBytes::deserialize(src)?;
+                let server_key = CompactBytes::deserialize(src)?;
+                let iterations = read_i32(src, "iterations")?;
+                TaggedFields::deserialize(src)?;
+
+                Ok(RecordValue::UserScramCredential(UserScramCredentialValue {
+                    name,
+                    mechanism,
+                    salt,
+                    stored_key,
+                    server_key,
+                    iterations,
+                }))
+            }
+            12 => {
+                check_version("feature level", version, 0)?;
+                let name = CompactString::deserialize(src)?;
+                let level = read_u16(src, "level")?;
+                TaggedFields::deserialize(src)?;
+
+                Ok(RecordValue::FeatureLevel(FeatureLevelValue { name, level }))
+            }
+            14 => {
+                check_version("client quota", version, 0)?;
+                let entity = CompactArray::deserialize::<ClientQuotaEntity, ClientQuotaEntity>(src)?;
+                let key = CompactString::deserialize(src)?;
+                let value = read_f64(src, "value")?;
+                let remove = read_bool(src, "remove")?;
+                TaggedFields::deserialize(src)?;
+
+                Ok(RecordValue::ClientQuota(ClientQuotaValue { entity, key, value, remove }))
+            }
+            15 => {
+                check_version("producer ids", version, 0)?;
+                let broker_id = read_i32(src, "broker_id")?;
+                let broker_epoch = read_i64(src, "broker_epoch")?;
+                let next_producer_id = read_i64(src, "next_producer_id")?;
+                TaggedFields::deserialize(src)?;
+
+                Ok(RecordValue::ProducerIds(ProducerIdsValue { broker_id, broker_epoch, next_producer_id }))
+            }
+            17 => {
+                check_version("broker registration change", version, 2)?;
+                let broker_id = read_i32(src, "broker_id")?;
+                let broker_epoch = read_i64(src, "broker_epoch")?;
+
+                let mut change = BrokerRegistrationChangeValue {
+                    broker_id,
+                    broker_epoch,
+                    fenced: 0,
+                    in_controlled_shutdown: 0,
+                    log_dirs: None,
+                };
+                for (tag, mut field) in TaggedFields::deserialize(src)? {
+                    match tag {
+                        0 => change.fenced = read_i8(&mut field, "fenced")?,
+                        1 => change.in_controlled_shutdown = read_i8(&mut field, "in_controlled_shutdown")?,
+                        2 => change.log_dirs = Some(CompactArray::deserialize::<String, PartitionValue>(&mut field)?),
+                        _ => {}
+                    }
                 }
-                let level = src.get_u16();
-                
-                let tagged_fields_count = VarInt::deserialize(src)?;
-                if tagged_fields_count != 0 {
-                    return Err(ApplicationError::Protocol(format!("unexpected tagged fields count: {}", tagged_fields_count)));
+
+                Ok(RecordValue::BrokerRegistrationChange(change))
+            }
+            18 => {
+                check_version("access control entry", version, 0)?;
+                let id = Uuid::deserialize(src)?;
+                let resource_type = read_i8(src, "resource_type")?;
+                let resource_name = CompactString::deserialize(src)?;
+                let pattern_type = read_i8(src, "pattern_type")?;
+                let principal = CompactString::deserialize(src)?;
+                let host = CompactString::deserialize(src)?;
+                let operation = read_i8(src, "operation")?;
+                let permission_type = read_i8(src, "permission_type")?;
+                TaggedFields::deserialize(src)?;
+
+                Ok(RecordValue::AccessControlEntry(AccessControlEntryValue {
+                    id,
+                    resource_type,
+                    resource_name,
+                    pattern_type,
+                    principal,
+                    host,
+                    operation,
+                    permission_type,
+                }))
+            }
+            19 => {
+                check_version("remove access control entry", version, 0)?;
+                let id = Uuid::deserialize(src)?;
+                TaggedFields::deserialize(src)?;
+
+                Ok(RecordValue::RemoveAccessControlEntry(RemoveAccessControlEntryValue { id }))
+            }
+            20 => {
+                check_version("no-op", version, 0)?;
+                TaggedFields::deserialize(src)?;
+
+                Ok(RecordValue::NoOp)
+            }
+            21 => {
+                check_version("zk migration state", version, 0)?;
+                let migration_state = read_i8(src, "migration_state")?;
+                TaggedFields::deserialize(src)?;
+
+                Ok(RecordValue::ZkMigrationState(ZkMigrationStateValue { migration_state }))
+            }
+            23 => {
+                check_version("begin transaction", version, 0)?;
+                let mut name = None;
+                for (tag, mut field) in TaggedFields::deserialize(src)? {
+                    if tag == 0 {
+                        name = CompactString::deserialize_nullable(&mut field)?;
+                    }
                 }
 
-                Ok(RecordValue::FeatureLevel(FeatureLevelValue { name, level }))
+                Ok(RecordValue::BeginTransaction(BeginTransactionValue { name }))
+            }
+            24 => {
+                check_version("end transaction", version, 0)?;
+                TaggedFields::deserialize(src)?;
+
+                Ok(RecordValue::EndTransaction)
             }
             _ => Err(ApplicationError::Protocol(format!("unknown record type: {}", record_type))),
         }
     }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = vec![1]; // frame version
+        // (tag, 값) 목록. 기본값인 필드는 넣지 않음
+        let mut tagged_fields: Vec<(u64, Vec<u8>)> = Vec::new();
+        match self {
+            RecordValue::RegisterBroker(broker) => {
+                buf.push(0); // record type
+                buf.push(3); // version
+                buf.extend_from_slice(&broker.broker_id.to_be_bytes());
+                buf.push(broker.is_migrating_zk_broker as u8);
+                Uuid::serialize(&broker.incarnation_id, &mut buf);
+                buf.extend_from_slice(&broker.broker_epoch.to_be_bytes());
+                VarInt::serialize(broker.end_points.len() as u64 + 1, &mut buf);
+                for end_point in &broker.end_points {
+                    CompactString::serialize(&end_point.name, &mut buf);
+                    CompactString::serialize(&end_point.host, &mut buf);
+                    buf.extend_from_slice(&end_point.port.to_be_bytes());
+                    buf.extend_from_slice(&end_point.security_protocol.to_be_bytes());
+                    TaggedFields::serialize(&[], &mut buf);
+                }
+                VarInt::serialize(broker.features.len() as u64 + 1, &mut buf);
+                for feature in &broker.features {
+                    CompactString::serialize(&feature.name, &mut buf);
+                    buf.extend_from_slice(&feature.min_supported_version.to_be_bytes());
+                    buf.extend_from_slice(&feature.max_supported_version.to_be_bytes());
+                    TaggedFields::serialize(&[], &mut buf);
+                }
+                CompactString::serialize_nullable(broker.rack.as_deref(), &mut buf);
+                buf.push(broker.fenced as u8);
+                buf.push(broker.in_controlled_shutdown as u8);
+                CompactArray::serialize_uuids(&broker.log_dirs, &mut buf);
+            }
+            RecordValue::UnregisterBroker(broker) => {
+                buf.push(1); // record type
+                buf.push(0); // version
+                buf.extend_from_slice(&broker.broker_id.to_be_bytes());
+                buf.extend_from_slice(&broker.broker_epoch.to_be_bytes());
+            }
+            RecordValue::Topic(topic) => {
+                buf.push(2); // record type
+                buf.push(0); // version
+                CompactString::serialize(&topic.topic_name, &mut buf);
+                Uuid::serialize(&topic.topic_id, &mut buf);
+            }
+            RecordValue::Partition(partition) => {
+                buf.push(3); // record type
+                buf.push(1); // version
+                buf.extend_from_slice(&partition.partition_id.to_be_bytes());
+                Uuid::serialize(&partition.topic_id, &mut buf);
+                CompactArray::serialize_u32(&partition.replicas, &mut buf);
+                CompactArray::serialize_u32(&partition.in_sync_replicas, &mut buf);
+                CompactArray::serialize_u32(&partition.removing_replicas, &mut buf);
+                CompactArray::serialize_u32(&partition.adding_replicas, &mut buf);
+                buf.extend_from_slice(&partition.leader_id.to_be_bytes());
+                buf.extend_from_slice(&partition.leader_epoch.to_be_bytes());
+                buf.extend_from_slice(&partition.partition_epoch.to_be_bytes());
+                CompactArray::serialize_uuids(&partition.directories, &mut buf);
+            }
+            RecordValue::Config(config) => {
+                buf.push(4); // record type
+                buf.push(0); // version
+                buf.push(config.resource_type as u8);
+                CompactString::serialize(&config.resource_name, &mut buf);
+                CompactString::serialize(&config.name, &mut buf);
+                CompactString::serialize_nullable(config.value.as_deref(), &mut buf);
+            }
+            RecordValue::PartitionChange(change) => {
+                // directories 는 v2 부터
+                let version = if change.directories.is_some() { 2 } else { 1 };
+                buf.push(5); // record type
+                buf.push(version);
+                buf.extend_from_slice(&change.partition_id.to_be_bytes());
+                Uuid::serialize(&change.topic_id, &mut buf);
+                let replica_lists = [
+                    (0, &change.in_sync_replicas),
+                    (2, &change.replicas),
+                    (3, &change.removing_replicas),
+                    (4, &change.adding_replicas),
+                    (6, &change.eligible_leader_replicas),
+                    (7, &change.last_known_elr),
+                ];
+                for (tag, replicas) in replica_lists {
+                    if let Some(replicas) = replicas {
+                        let mut field = Vec::new();
+                        CompactArray::serialize_u32(replicas, &mut field);
+                        tagged_fields.push((tag, field));
+                    }
+                }
+                if change.leader_id != NO_LEADER_CHANGE {
+                    tagged_fields.push((1, change.leader_id.to_be_bytes().to_vec()));
+                }
+                if change.leader_recovery_state != -1 {
+                    tagged_fields.push((5, vec![change.leader_recovery_state as u8]));
+                }
+                if let Some(directories) = &change.directories {
+                    let mut field = Vec::new();
+                    CompactArray::serialize_uuids(directories, &mut field);
+                    tagged_fields.push((8, field));
+                }
+            }
+            RecordValue::RemoveTopic(remove) => {
+                buf.push(9); // record type
+                buf.push(0); // version
+                Uuid::serialize(&remove.topic_id, &mut buf);
+            }
+            RecordValue::UserScramCredential(credential) => {
+                buf.push(11); // record type
+                buf.push(0); // version
+                CompactString::serialize(&credential.name, &mut buf);
+                buf.push(credential.mechanism as u8);
+                CompactBytes::serialize(&credential.salt, &mut buf);
+                CompactBytes::serialize(&credential.stored_key, &mut buf);
+                CompactBytes::serialize(&credential.server_key, &mut buf);
+                buf.extend_from_slice(&credential.iterations.to_be_bytes());
+            }
+            RecordValue::FeatureLevel(feature) => {
+                buf.push(12); // record type
+                buf.push(0); // version
+                CompactString::serialize(&feature.name, &mut buf);
+                buf.extend_from_slice(&feature.level.to_be_bytes());
+            }
+            RecordValue::ClientQuota(quota) => {
+                buf.push(14); // record type
+                buf.push(0); // version
+                VarInt::serialize(quota.entity.len() as u64 + 1, &mut buf);
+                for entity in &quota.entity {
+                    CompactString::serialize(&entity.entity_type, &mut buf);
+                    CompactString::serialize_nullable(entity.entity_name.as_deref(), &mut buf);
+                    TaggedFields::serialize(&[], &mut buf);
+                }
+                CompactString::serialize(&quota.key, &mut buf);
+                buf.extend_from_slice(&quota.value.to_be_bytes());
+                buf.push(quota.remove as u8);
+            }
+            RecordValue::ProducerIds(producer_ids) => {
+                buf.push(15); // record type
+                buf.push(0); // version
+                buf.extend_from_slice(&producer_ids.broker_id.to_be_bytes());
+                buf.extend_from_slice(&producer_ids.broker_epoch.to_be_bytes());
+                buf.extend_from_slice(&producer_ids.next_producer_id.to_be_bytes());
+            }
+            RecordValue::BrokerRegistrationChange(change) => {
+                buf.push(17); // record type
+                buf.push(2); // version
+                buf.extend_from_slice(&change.broker_id.to_be_bytes());
+                buf.extend_from_slice(&change.broker_epoch.to_be_bytes());
+                if change.fenced != 0 {
+                    tagged_fields.push((0, vec![change.fenced as u8]));
+                }
+                if change.in_controlled_shutdown != 0 {
+                    tagged_fields.push((1, vec![change.in_controlled_shutdown as u8]));
+                }
+                if let Some(log_dirs) = &change.log_dirs {
+                    let mut field = Vec::new();
+                    CompactArray::serialize_uuids(log_dirs, &mut field);
+                    tagged_fields.push((2, field));
+                }
+            }
+            RecordValue::AccessControlEntry(acl) => {
+                buf.push(18); // record type
+                buf.push(0); // version
+                Uuid::serialize(&acl.id, &mut buf);
+                buf.push(acl.resource_type as u8);
+                CompactString::serialize(&acl.resource_name, &mut buf);
+                buf.push(acl.pattern_type as u8);
+                CompactString::serialize(&acl.principal, &mut buf);
+                CompactString::serialize(&acl.host, &mut buf);
+                buf.push(acl.operation as u8);
+                buf.push(acl.permission_type as u8);
+            }
+            RecordValue::RemoveAccessControlEntry(remove) => {
+                buf.push(19); // record type
+                buf.push(0); // version
+                Uuid::serialize(&remove.id, &mut buf);
+            }
+            RecordValue::NoOp => {
+                buf.push(20); // record type
+                buf.push(0); // version
+            }
+            RecordValue::ZkMigrationState(state) => {
+                buf.push(21); // record type
+                buf.push(0); // version
+                buf.push(state.migration_state as u8);
+            }
+            RecordValue::BeginTransaction(begin) => {
+                buf.push(23); // record type
+                buf.push(0); // version
+                if let Some(name) = &begin.name {
+                    let mut field = Vec::new();
+                    CompactString::serialize(name, &mut field);
+                    tagged_fields.push((0, field));
+                }
+            }
+            RecordValue::EndTransaction => {
+                buf.push(24); // record type
+                buf.push(0); // version
+            }
+        }
+        TaggedFields::serialize(&tagged_fields, &mut buf);
+        buf
+    }
+}
+
+fn check_version(record: &str, version: i64, max_version: i64) -> Result<(), ApplicationError> {
+    if !(0..=max_version).contains(&version) {
+        return Err(ApplicationError::Protocol(format!("invalid version for {} record: {}", record, version)));
+    }
+    Ok(())
 }
 
-impl Deserialize<Record> for RecordBatch {
-    fn deserialize(src: &mut Bytes) -> Result<Record, ApplicationError> {
-        Record::from_bytes(src)
+macro_rules! read_primitive {
+    ($name:ident, $ty:ty, $get:ident) => {
+        fn $name(src: &mut Bytes, field: &str) -> Result<$ty, ApplicationError> {
+            if src.remaining() < std::mem::size_of::<$ty>() {
+                return Err(ApplicationError::Protocol(format!("buffer too short for {}", field)));
+            }
+            Ok(src.$get())
+        }
+    };
+}
+
+read_primitive!(read_u8, u8, get_u8);
+read_primitive!(read_i8, i8, get_i8);
+read_primitive!(read_u16, u16, get_u16);
+read_primitive!(read_i16, i16, get_i16);
+read_primitive!(read_i32, i32, get_i32);
+read_primitive!(read_u32, u32, get_u32);
+read_primitive!(read_i64, i64, get_i64);
+read_primitive!(read_f64, f64, get_f64);
+
+fn read_bool(src: &mut Bytes, field: &str) -> Result<bool, ApplicationError> {
+    Ok(read_u8(src, field)? != 0)
+}
+
+impl Deserialize<BrokerEndpoint> for BrokerEndpoint {
+    fn deserialize(src: &mut Bytes) -> Result<BrokerEndpoint, ApplicationError> {
+        let name = CompactString::deserialize(src)?;
+        let host = CompactString::deserialize(src)?;
+        let port = read_u16(src, "port")?;
+        let security_protocol = read_i16(src, "security_protocol")?;
+        TaggedFields::deserialize(src)?;
+        Ok(BrokerEndpoint { name, host, port, security_protocol })
+    }
+}
+
+impl Deserialize<BrokerFeature> for BrokerFeature {
+    fn deserialize(src: &mut Bytes) -> Result<BrokerFeature, ApplicationError> {
+        let name = CompactString::deserialize(src)?;
+        let min_supported_version = read_i16(src, "min_supported_version")?;
+        let max_supported_version = read_i16(src, "max_supported_version")?;
+        TaggedFields::deserialize(src)?;
+        Ok(BrokerFeature { name, min_supported_version, max_supported_version })
     }
 }
 
-impl Deserialize<Header> for Record {
-    fn deserialize(_src: &mut Bytes) -> Result<Header, ApplicationError> {
-        Ok(Header)
+impl Deserialize<ClientQuotaEntity> for ClientQuotaEntity {
+    fn deserialize(src: &mut Bytes) -> Result<ClientQuotaEntity, ApplicationError> {
+        let entity_type = CompactString::deserialize(src)?;
+        let entity_name = CompactString::deserialize_nullable(src)?;
+        TaggedFields::deserialize(src)?;
+        Ok(ClientQuotaEntity { entity_type, entity_name })
     }
 }
 
@@ -357,6 +815,11 @@ impl Deserialize<String> for PartitionValue {
 }
 
 impl CompactString {
+    pub fn serialize(value: &str, dst: &mut Vec<u8>) {
+        VarInt::serialize(value.len() as u64 + 1, dst);
+        dst.extend_from_slice(value.as_bytes());
+    }
+
     pub fn deserialize(src: &mut Bytes) -> Result<String, ApplicationError> {
         let len = VarInt::deserialize(src)?;
         let string_len = if len > 1 { len as usize - 1 } else { 0 };
@@ -371,9 +834,32 @@ impl CompactString {
         String::from_utf8(bytes.to_vec())
             .map_err(|e| ApplicationError::Protocol(format!("invalid UTF-8 sequence: {}", e)))
     }
+
+    /// 길이 0 은 null
+    pub fn serialize_nullable(value: Option<&str>, dst: &mut Vec<u8>) {
+        match value {
+            Some(value) => Self::serialize(value, dst),
+            None => VarInt::serialize(0, dst),
+        }
+    }
+
+    pub fn deserialize_nullable(src: &mut Bytes) -> Result<Option<String>, ApplicationError> {
+        if src.first() == Some(&0) {
+            src.advance(1);
+            return Ok(None);
+        }
+        Self::deserialize(src).map(Some)
+    }
 }
 
 impl CompactArray {
+    pub fn serialize_u32(items: &[u32], dst: &mut Vec<u8>) {
+        VarInt::serialize(items.len() as u64 + 1, dst);
+        for item in items {
+            dst.extend_from_slice(&item.to_be_bytes());
+        }
+    }
+
     pub fn deserialize<T, U: Deserialize<T>>(src: &mut Bytes) -> Result<Vec<T>, ApplicationError> {
         let len = VarInt::deserialize(src)?;
         let items_len = if len > 1 { len as usize - 1 } else { 0 };
@@ -385,40 +871,85 @@ impl CompactArray {
 
         Ok(items)
     }
-}
 
-impl NullableBytes {
-    pub fn deserialize<T, U: Deserialize<T>>(src: &mut Bytes) -> Result<Vec<T>, ApplicationError> {
-        if src.remaining() < 4 {
-            return Err(ApplicationError::Protocol("buffer too short for length".to_string()));
+    /// 길이 0 은 null
+    pub fn deserialize_nullable<T, U: Deserialize<T>>(src: &mut Bytes) -> Result<Option<Vec<T>>, ApplicationError> {
+        if src.first() == Some(&0) {
+            src.advance(1);
+            return Ok(None);
         }
-        let len = src.get_i32();
-        let items_len = if len == -1 { 0 } else { len as usize };
+        Self::deserialize::<T, U>(src).map(Some)
+    }
 
-        let mut items = Vec::with_capacity(items_len);
-        for _ in 0..items_len {
-            items.push(U::deserialize(src)?);
+    pub fn serialize_uuids(items: &[String], dst: &mut Vec<u8>) {
+        VarInt::serialize(items.len() as u64 + 1, dst);
+        for item in items {
+            Uuid::serialize(item, dst);
         }
-        Ok(items)
     }
 }
 
-impl CompactNullableBytes {
+impl CompactBytes {
+    pub fn serialize(value: &[u8], dst: &mut Vec<u8>) {
+        VarInt::serialize(value.len() as u64 + 1, dst);
+        dst.extend_from_slice(value);
+    }
+
     pub fn deserialize(src: &mut Bytes) -> Result<Vec<u8>, ApplicationError> {
         let len = VarInt::deserialize(src)?;
         let bytes_len = if len > 1 { len as usize - 1 } else { 0 };
-        
         if src.remaining() < bytes_len {
             return Err(ApplicationError::Protocol(format!("buffer too short for bytes of length {}", bytes_len)));
         }
-        
-        let bytes = src.slice(..bytes_len);
+        let bytes = src.slice(..bytes_len).to_vec();
         src.advance(bytes_len);
-        Ok(bytes.to_vec())
+        Ok(bytes)
+    }
+}
+
+impl TaggedFields {
+    /// 필드 개수, 그리고 필드마다 tag, 크기, 값 순서. tag 는 오름차순이어야 함
+    pub fn serialize(fields: &[(u64, Vec<u8>)], dst: &mut Vec<u8>) {
+        let mut fields = fields.iter().collect::<Vec<_>>();
+        fields.sort_by_key(|(tag, _)| *tag);
+        VarInt::serialize(fields.len() as u64, dst);
+        for (tag, value) in fields {
+            VarInt::serialize(*tag, dst);
+            VarInt::serialize(value.len() as u64, dst);
+            dst.extend_from_slice(value);
+        }
+    }
+
+    /// (tag, 값) 목록. 값의 해석은 tag 를 아는 쪽에서 함
+    pub fn deserialize(src: &mut Bytes) -> Result<Vec<(i64, Bytes)>, ApplicationError> {
+        let count = VarInt::deserialize(src)?;
+        let mut fields = Vec::new();
+        for _ in 0..count {
+            let tag = VarInt::deserialize(src)?;
+            let size = VarInt::deserialize(src)? as usize;
+            if src.remaining() < size {
+                return Err(ApplicationError::Protocol(format!("buffer too short for tagged field {} of size {}", tag, size)));
+            }
+            fields.push((tag, src.split_to(size)));
+        }
+        Ok(fields)
     }
 }
 
 impl VarInt {
+    pub fn serialize(mut value: u64, dst: &mut Vec<u8>) {
+        while value & !0x7F != 0 {
+            dst.push(((value & 0x7F) | 0x80) as u8);
+            value >>= 7;
+        }
+        dst.push(value as u8);
+    }
+
+    /// zigzag 인코딩: 0, -1, 1, -2 ... 를 0, 1, 2, 3 ... 으로 매핑
+    pub fn serialize_signed(value: i64, dst: &mut Vec<u8>) {
+        Self::serialize(((value << 1) ^ (value >> 63)) as u64, dst);
+    }
+
     pub(crate) fn deserialize<T>(buf: &mut T) -> Result<i64, ApplicationError>
     where
         T: Buf,
@@ -429,11 +960,8 @@ impl VarInt {
         }
 
         let buf_len = buf.remaining();
-        let mut b0 = buf.get_i8() as i64;
-        let mut res = b0 & 0b0111_1111;
-        let mut n_bytes = 1;
-
-        while b0 & 0b1000_0000 != 0 && n_bytes <= MAX_BYTES {
+        let mut res: i64 = 0;
+        for n_bytes in 0..MAX_BYTES {
             if buf.remaining() == 0 {
                 return Err(ApplicationError::Protocol(format!(
                     "buffer too short ({} bytes) for varint",
@@ -441,24 +969,40 @@ impl VarInt {
                 )));
             }
 
-            let b1 = buf.get_i8() as i64;
-            if buf.remaining() == 0 && b1 & 0b1000_0000 != 0 {
-                return Err(ApplicationError::Protocol(format!(
-                    "invalid varint encoding at byte {}",
-                    n_bytes
-                )));
+            let byte = buf.get_u8();
+            res |= ((byte & 0b0111_1111) as i64) << (7 * n_bytes);
+            if byte & 0b1000_0000 == 0 {
+                return Ok(res);
             }
-
-            res += (b1 & 0b0111_1111) << 7;
-            n_bytes += 1;
-            b0 = b1;
         }
 
-        Ok(res)
+        Err(ApplicationError::Protocol(format!(
+            "invalid varint encoding: longer than {} bytes",
+            MAX_BYTES
+        )))
+    }
+
+    /// zigzag 로 인코딩된 varint / varlong
+    pub(crate) fn deserialize_signed<T>(buf: &mut T) -> Result<i64, ApplicationError>
+    where
+        T: Buf,
+    {
+        let raw = Self::deserialize(buf)? as u64;
+        Ok((raw >> 1) as i64 ^ -((raw & 1) as i64))
     }
 }
 
 impl Uuid {
+    /// "xxxxxxxx-xxxx-..." 형식의 문자열을 16 바이트로 씀. 잘못된 값은 0 으로 채움
+    pub fn serialize(value: &str, dst: &mut Vec<u8>) {
+        let bytes = hex::decode(value.replace('-', "")).unwrap_or_default();
+        if bytes.len() == 16 {
+            dst.extend_from_slice(&bytes);
+        } else {
+            dst.extend_from_slice(&[0; 16]);
+        }
+    }
+
     pub fn deserialize(src: &mut Bytes) -> Result<String, ApplicationError> {
         if src.remaining() < 16 {
             return Err(ApplicationError::Protocol("buffer too short for UUID".to_string()));
@@ -594,63 +1138,273 @@ mod tests {
     }
 
     #[test]
-    fn test_record_batch_from_bytes() {
+    fn test_varint_serialize_roundtrip() {
+        for value in [0u64, 1, 127, 128, 300, 16384, 1 << 40] {
+            let mut buf = Vec::new();
+            VarInt::serialize(value, &mut buf);
+            let mut bytes = Bytes::from(buf);
+            assert_eq!(VarInt::deserialize(&mut bytes).unwrap(), value as i64);
+        }
+
+        // zigzag
+        let mut buf = Vec::new();
+        VarInt::serialize_signed(-1, &mut buf);
+        VarInt::serialize_signed(29, &mut buf);
+        assert_eq!(buf, vec![0x01, 0x3a]);
+        let mut bytes = Bytes::from(buf);
+        assert_eq!(VarInt::deserialize_signed(&mut bytes).unwrap(), -1);
+        assert_eq!(VarInt::deserialize_signed(&mut bytes).unwrap(), 29);
+
+        // 10 바이트를 넘는 varint 는 에러
+        let mut bytes = Bytes::from(vec![0xff; 11]);
+        assert!(VarInt::deserialize(&mut bytes).is_err());
+    }
+
+    #[test]
+    fn test_record_batch_to_bytes_roundtrip() {
+        let topic_id = "0102030405060708090a0b0c0d0e0f10".to_string();
+        let topic_id = format!("{}-{}-{}-{}-{}", &topic_id[0..8], &topic_id[8..12], &topic_id[12..16], &topic_id[16..20], &topic_id[20..]);
+        let batch = RecordBatch::metadata(5, 1, 1726045943832, vec![
+            RecordValue::Topic(TopicValue {
+                topic_name: "foo".to_string(),
+                topic_id: topic_id.clone(),
+            }),
+            RecordValue::Config(ConfigValue {
+                resource_type: TOPIC_RESOURCE_TYPE,
+                resource_name: "foo".to_string(),
+                name: "cleanup.policy".to_string(),
+                value: Some("compact".to_string()),
+            }),
+            RecordValue::Config(ConfigValue {
+                resource_type: TOPIC_RESOURCE_TYPE,
+                resource_name: "foo".to_string(),
+                name: "retention.ms".to_string(),
+                value: None,
+            }),
+            RecordValue::Partition(PartitionValue {
+                partition_id: 0,
+                topic_id: topic_id.clone(),
+                replicas: vec![1],
+                in_sync_replicas: vec![1],
+                removing_replicas: vec![],
+                adding_replicas: vec![],
+                leader_id: 1,
+                leader_epoch: 0,
+                partition_epoch: 0,
+                directories: vec![topic_id.clone()],
+            }),
+            RecordValue::RemoveTopic(RemoveTopicValue {
+                topic_id: topic_id.clone(),
+            }),
+        ]);
+
+        let encoded = batch.to_bytes().unwrap();
+        let decoded = RecordBatch::from_bytes(&mut Bytes::from(encoded)).unwrap();
+        assert_eq!(decoded.base_offset, 5);
+        assert_eq!(decoded.next_offset(), 10);
+        assert_eq!(decoded.records.len(), 5);
+        match decoded.records[0].metadata_value().unwrap() {
+            RecordValue::Topic(topic) => {
+                assert_eq!(topic.topic_name, "foo");
+                assert_eq!(topic.topic_id, topic_id);
+            }
+            _ => panic!("Expected Topic record"),
+        }
+        match (decoded.records[1].metadata_value().unwrap(), decoded.records[2].metadata_value().unwrap()) {
+            (RecordValue::Config(set), RecordValue::Config(deleted)) => {
+                assert_eq!(set.name, "cleanup.policy");
+                assert_eq!(set.value.as_deref(), Some("compact"));
+                assert_eq!(deleted.resource_name, "foo");
+                assert_eq!(deleted.value, None);
+            }
+            _ => panic!("Expected Config records"),
+        }
+        match decoded.records[3].metadata_value().unwrap() {
+            RecordValue::Partition(partition) => {
+                assert_eq!(partition.replicas, vec![1]);
+                assert_eq!(partition.leader_id, 1);
+                assert_eq!(partition.directories, vec![topic_id.clone()]);
+            }
+            _ => panic!("Expected Partition record"),
+        }
+        match decoded.records[4].metadata_value().unwrap() {
+            RecordValue::RemoveTopic(remove) => assert_eq!(remove.topic_id, topic_id),
+            _ => panic!("Expected RemoveTopic record"),
+        }
+    }
+
+    #[test]
+    fn test_record_value_skips_unknown_tagged_fields() {
+        let mut bytes = Bytes::from(vec![
+            1,   // frame version
+            2,   // record type (Topic)
+            0,   // version
+            5,   // topic name length (4 + 1)
+            b't', b'e', b's', b't',  // topic name
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,  // topic id (UUID)
+            1,   // tagged fields count
+            9, 2, 0xab, 0xcd,  // tag, size, value
+        ]);
+        match RecordValue::from_bytes(&mut bytes).unwrap() {
+            RecordValue::Topic(topic) => assert_eq!(topic.topic_name, "test"),
+            _ => panic!("Expected Topic record"),
+        }
+        assert_eq!(bytes.remaining(), 0);
+
+        // PartitionChange 는 바뀐 필드만 tagged 로 들어옴
         let mut bytes = Bytes::from(vec![
-            0, 0, 0, 0, 0, 0, 0, 1,  // base offset
-            0, 0, 0, 100,            // batch length
-            0, 0, 0, 0,              // partition leader epoch
-            2,                       // magic
-            0, 0, 0, 0,              // crc
-            0, 1,                    // attributes
-            0, 0, 0, 0,              // last offset delta
-            0, 0, 0, 0, 0, 0, 0, 0,  // base timestamp
-            0, 0, 0, 0, 0, 0, 0, 0,  // max timestamp
-            0, 0, 0, 0, 0, 0, 0, 0,  // producer id
-            0, 0,                    // producer epoch
-            0, 0, 0, 0,              // base sequence
-            0, 0, 0, 0,              // records length (empty)
+            1,   // frame version
+            5,   // record type (PartitionChange)
+            0,   // version
+            0, 0, 0, 2,  // partition id
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,  // topic id (UUID)
+            2,   // tagged fields count
+            0, 9, 3, 0, 0, 0, 1, 0, 0, 0, 2,  // isr [1, 2]
+            1, 4, 0, 0, 0, 2,  // leader 2
         ]);
+        match RecordValue::from_bytes(&mut bytes).unwrap() {
+            RecordValue::PartitionChange(change) => {
+                assert_eq!(change.partition_id, 2);
+                assert_eq!(change.in_sync_replicas, Some(vec![1, 2]));
+                assert_eq!(change.leader_id, 2);
+                assert_eq!(change.replicas, None);
+                assert_eq!(change.leader_recovery_state, -1);
+            }
+            _ => panic!("Expected PartitionChange record"),
+        }
 
-        let batch = RecordBatch::from_bytes(&mut bytes).unwrap();
-        assert_eq!(batch.base_offset, 1);
-        assert_eq!(batch.batch_length, 100);
-        assert_eq!(batch.magic, 2);
-        assert_eq!(batch.attributes, 1);
-        assert!(batch.records.is_empty());
+        // tagged field 크기가 버퍼보다 크면 에러
+        let mut bytes = Bytes::from(vec![1, 20, 0, 1, 0, 5, 0]);
+        assert!(RecordValue::from_bytes(&mut bytes).is_err());
+        let mut bytes = Bytes::from(vec![1, 99, 0, 0]);
+        assert!(RecordValue::from_bytes(&mut bytes).is_err());
     }
 
     #[test]
-    fn test_record_from_bytes() {
+    fn test_partition_change_v2_tagged_fields() {
+        // 컨트롤러가 쓰는 v2 레코드: tag 6 ELR, tag 7 LastKnownElr, tag 8 Directories
         let mut bytes = Bytes::from(vec![
-            2,                       // length
-            0,                       // attributes
-            2,                       // timestamp delta
-            2,                       // offset delta
-            1,                       // key length (empty)
-            2,                       // value length
-            1,                       // frame version
-            12,                      // record type (FeatureLevel)
-            0,                       // version
-            5,                       // feature name length (4 + 1)
-            b't', b'e', b's', b't',  // feature name
-            0, 1,                    // level
-            0,                       // tagged fields count
-            1,                       // headers length (empty)
+            1,   // frame version
+            5,   // record type (PartitionChange)
+            2,   // version
+            0, 0, 0, 0,  // partition id
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,  // topic id (UUID)
+            3,   // tagged fields count
+            6, 9, 3, 0, 0, 0, 1, 0, 0, 0, 2,  // eligible leader replicas [1, 2]
+            7, 5, 2, 0, 0, 0, 3,  // last known elr [3]
+            8, 17, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 7,  // directories
         ]);
+        let change = match RecordValue::from_bytes(&mut bytes).unwrap() {
+            RecordValue::PartitionChange(change) => change,
+            _ => panic!("Expected PartitionChange record"),
+        };
+        assert_eq!(bytes.remaining(), 0);
+        assert_eq!(change.eligible_leader_replicas, Some(vec![1, 2]));
+        assert_eq!(change.last_known_elr, Some(vec![3]));
+        assert_eq!(change.directories, Some(vec!["00000000-0000-0000-0000-000000000007".to_string()]));
+        assert_eq!(change.in_sync_replicas, None);
+        assert_eq!(change.leader_id, NO_LEADER_CHANGE);
+
+        // 다시 쓰면 같은 태그 번호로 나가야 함
+        let encoded = RecordValue::PartitionChange(change.clone()).to_bytes();
+        assert_eq!(encoded[2], 2);
+        match RecordValue::from_bytes(&mut Bytes::from(encoded)).unwrap() {
+            RecordValue::PartitionChange(decoded) => assert_eq!(decoded, change),
+            _ => panic!("Expected PartitionChange record"),
+        }
+    }
 
-        let record = Record::from_bytes(&mut bytes).unwrap();
-        assert_eq!(record.length, 2);
-        assert_eq!(record.attributes, 0);
-        assert_eq!(record.timestamp_delta, 2);
-        assert_eq!(record.offset_delta, 2);
-        assert!(record.key.is_empty());
-        
-        match record.value {
-            RecordValue::FeatureLevel(feature) => {
-                assert_eq!(feature.name, "test");
-                assert_eq!(feature.level, 1);
+    #[test]
+    fn test_cluster_record_values_roundtrip() {
+        let id = "00000000-0000-0000-0000-000000000001".to_string();
+        let values = vec![
+            RecordValue::RegisterBroker(RegisterBrokerValue {
+                broker_id: 1,
+                is_migrating_zk_broker: false,
+                incarnation_id: id.clone(),
+                broker_epoch: 7,
+                end_points: vec![BrokerEndpoint { name: "PLAINTEXT".to_string(), host: "localhost".to_string(), port: 9092, security_protocol: 0 }],
+                features: vec![BrokerFeature { name: "metadata.version".to_string(), min_supported_version: 1, max_supported_version: 20 }],
+                rack: Some("rack-a".to_string()),
+                fenced: true,
+                in_controlled_shutdown: false,
+                log_dirs: vec![id.clone()],
+            }),
+            RecordValue::UnregisterBroker(UnregisterBrokerValue { broker_id: 1, broker_epoch: 7 }),
+            RecordValue::PartitionChange(PartitionChangeValue {
+                partition_id: 0,
+                topic_id: id.clone(),
+                in_sync_replicas: Some(vec![1]),
+                leader_id: -1,
+                replicas: None,
+                removing_replicas: None,
+                adding_replicas: Some(vec![]),
+                leader_recovery_state: 1,
+                eligible_leader_replicas: Some(vec![2]),
+                last_known_elr: None,
+                directories: Some(vec![id.clone()]),
+            }),
+            RecordValue::UserScramCredential(UserScramCredentialValue {
+                name: "alice".to_string(),
+                mechanism: 1,
+                salt: vec![1, 2, 3],
+                stored_key: vec![4, 5],
+                server_key: vec![],
+                iterations: 4096,
+            }),
+            RecordValue::ClientQuota(ClientQuotaValue {
+                entity: vec![
+                    ClientQuotaEntity { entity_type: "user".to_string(), entity_name: Some("alice".to_string()) },
+                    ClientQuotaEntity { entity_type: "client-id".to_string(), entity_name: None },
+                ],
+                key: "producer_byte_rate".to_string(),
+                value: 1024.5,
+                remove: false,
+            }),
+            RecordValue::ProducerIds(ProducerIdsValue { broker_id: 1, broker_epoch: 7, next_producer_id: 2000 }),
+            RecordValue::BrokerRegistrationChange(BrokerRegistrationChangeValue {
+                broker_id: 1,
+                broker_epoch: 7,
+                fenced: -1,
+                in_controlled_shutdown: 0,
+                log_dirs: None,
+            }),
+            RecordValue::AccessControlEntry(AccessControlEntryValue {
+                id: id.clone(),
+                resource_type: 2,
+                resource_name: "foo".to_string(),
+                pattern_type: 3,
+                principal: "User:alice".to_string(),
+                host: "*".to_string(),
+                operation: 3,
+                permission_type: 3,
+            }),
+            RecordValue::RemoveAccessControlEntry(RemoveAccessControlEntryValue { id: id.clone() }),
+            RecordValue::NoOp,
+            RecordValue::ZkMigrationState(ZkMigrationStateValue { migration_state: 0 }),
+            RecordValue::BeginTransaction(BeginTransactionValue { name: Some("bulk".to_string()) }),
+            RecordValue::EndTransaction,
+        ];
+
+        for value in values {
+            let mut bytes = Bytes::from(value.to_bytes());
+            let decoded = RecordValue::from_bytes(&mut bytes).unwrap();
+            assert_eq!(bytes.remaining(), 0, "{:?}", value);
+            match (&value, &decoded) {
+                (RecordValue::RegisterBroker(a), RecordValue::RegisterBroker(b)) => assert_eq!(a, b),
+                (RecordValue::UnregisterBroker(a), RecordValue::UnregisterBroker(b)) => assert_eq!(a, b),
+                (RecordValue::PartitionChange(a), RecordValue::PartitionChange(b)) => assert_eq!(a, b),
+                (RecordValue::UserScramCredential(a), RecordValue::UserScramCredential(b)) => assert_eq!(a, b),
+                (RecordValue::ClientQuota(a), RecordValue::ClientQuota(b)) => assert_eq!(a, b),
+                (RecordValue::ProducerIds(a), RecordValue::ProducerIds(b)) => assert_eq!(a, b),
+                (RecordValue::BrokerRegistrationChange(a), RecordValue::BrokerRegistrationChange(b)) => assert_eq!(a, b),
+                (RecordValue::AccessControlEntry(a), RecordValue::AccessControlEntry(b)) => assert_eq!(a, b),
+                (RecordValue::RemoveAccessControlEntry(a), RecordValue::RemoveAccessControlEntry(b)) => assert_eq!(a, b),
+                (RecordValue::ZkMigrationState(a), RecordValue::ZkMigrationState(b)) => assert_eq!(a, b),
+                (RecordValue::BeginTransaction(a), RecordValue::BeginTransaction(b)) => assert_eq!(a, b),
+                (RecordValue::NoOp, RecordValue::NoOp) | (RecordValue::EndTransaction, RecordValue::EndTransaction) => {}
+                _ => panic!("Expected {:?}, got {:?}", value, decoded),
             }
-            _ => panic!("Expected FeatureLevel record"),
         }
     }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,408 @@
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use crate::adapters::outgoing::protocol::compression::{compress_legacy, decompress, MAX_DECOMPRESSED_BYTES};
+use crate::adapters::outgoing::protocol::record_batch::{CompressionType, Record, RecordBatch, TimestampType};
+use crate::application::error::ApplicationError;
+use crate::domain::error::DomainError;
+
+/// offset(8) + message_size(4)
+const LOG_OVERHEAD: usize = 12;
+/// 메시지 셋 안에서 첫 메시지의 magic 위치 (RecordBatch v2 의 magic 과 같은 자리)
+const MAGIC_OFFSET: usize = 16;
+/// crc(4) + magic(1) + attributes(1) + key 길이(4) + value 길이(4), v1 은 timestamp(8) 추가
+const MESSAGE_V0_HEADER_SIZE: usize = 14;
+const MESSAGE_V1_HEADER_SIZE: usize = 22;
+
+const COMPRESSION_CODEC_MASK: i8 = 0x07;
+const TIMESTAMP_TYPE_FLAG: i8 = 0x08;
+const NO_TIMESTAMP: i64 = -1;
+/// 프로듀서가 보내는 배치의 partition leader epoch (브로커가 채우지 않음)
+const NO_PARTITION_LEADER_EPOCH: i32 = -1;
+
+/// magic v0 / v1 메시지 셋의 메시지 하나
+/// 압축된 메시지는 value 에 압축된 내부 메시지 셋을 가진 wrapper 임
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegacyMessage {
+    pub offset: i64,
+    pub magic: i8,
+    pub attributes: i8,
+    /// v0 은 timestamp 가 없어서 -1
+    pub timestamp: i64,
+    pub key: Option<Vec<u8>>,
+    pub value: Option<Vec<u8>>,
+}
+
+impl LegacyMessage {
+    pub fn new(offset: i64, magic: i8, timestamp: i64, key: Option<Vec<u8>>, value: Option<Vec<u8>>) -> Self {
+        Self {
+            offset,
+            magic,
+            attributes: 0,
+            timestamp: if magic == 0 { NO_TIMESTAMP } else { timestamp },
+            key,
+            value,
+        }
+    }
+
+    /// offset, message_size 를 포함한 메시지 하나. crc 는 CRC-32 (IEEE) 로 magic 부터 끝까지를 덮음
+    pub fn from_bytes(src: &mut Bytes) -> Result<Self, ApplicationError> {
+        ensure_remaining(src, LOG_OVERHEAD, "message header")?;
+        let offset = src.get_i64();
+        let size = src.get_i32();
+        if size < MESSAGE_V0_HEADER_SIZE as i32 {
+            return Err(ApplicationError::Protocol(format!("invalid message size: {}", size)));
+        }
+        ensure_remaining(src, size as usize, "message")?;
+        let mut body = src.split_to(size as usize);
+
+        let crc = body.get_u32();
+        let computed_crc = crc32fast::hash(&body);
+        if crc != computed_crc {
+            return Err(ApplicationError::Protocol(format!(
+                "message is corrupt (stored crc = {}, computed crc = {})", crc, computed_crc
+            )));
+        }
+        let magic = body.get_i8();
+        let attributes = body.get_i8();
+        let timestamp = match magic {
+            0 => NO_TIMESTAMP,
+            1 => {
+                ensure_remaining(&body, 8, "timestamp")?;
+                body.get_i64()
+            },
+            _ => return Err(ApplicationError::Protocol(format!("unsupported message magic: {}", magic))),
+        };
+        let key = read_bytes(&mut body, "key")?;
+        let value = read_bytes(&mut body, "value")?;
+        if body.has_remaining() {
+            return Err(ApplicationError::Protocol(format!("{} unexpected bytes after message", body.remaining())));
+        }
+
+        Ok(Self {
+            offset,
+            magic,
+            attributes,
+            timestamp,
+            key,
+            value,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let header_size = if self.magic == 0 { MESSAGE_V0_HEADER_SIZE } else { MESSAGE_V1_HEADER_SIZE };
+        let size = header_size + self.key.as_ref().map_or(0, Vec::len) + self.value.as_ref().map_or(0, Vec::len);
+
+        let mut buf = BytesMut::with_capacity(LOG_OVERHEAD + size);
+        buf.put_i64(self.offset);
+        buf.put_i32(size as i32);
+        buf.put_u32(0); // crc (아래에서 채움)
+        buf.put_i8(self.magic);
+        buf.put_i8(self.attributes);
+        if self.magic > 0 {
+            buf.put_i64(self.timestamp);
+        }
+        write_bytes(self.key.as_deref(), &mut buf);
+        write_bytes(self.value.as_deref(), &mut buf);
+
+        let crc = crc32fast::hash(&buf[LOG_OVERHEAD + 4..]);
+        buf[LOG_OVERHEAD..LOG_OVERHEAD + 4].copy_from_slice(&crc.to_be_bytes());
+        buf.to_vec()
+    }
+
+    /// 레거시 포맷에는 zstd 가 없음
+    pub fn compression_type(&self) -> Result<CompressionType, ApplicationError> {
+        match CompressionType::from_attributes((self.attributes & COMPRESSION_CODEC_MASK) as i16)? {
+            CompressionType::Zstd => Err(ApplicationError::Protocol("zstd is not supported by message format v0/v1".to_string())),
+            compression => Ok(compression),
+        }
+    }
+
+    pub fn timestamp_type(&self) -> TimestampType {
+        if self.magic > 0 && self.attributes & TIMESTAMP_TYPE_FLAG != 0 {
+            TimestampType::LogAppendTime
+        } else {
+            TimestampType::CreateTime
+        }
+    }
+
+    /// 압축된 wrapper 면 내부 메시지들을 풀어서 절대 offset 과 timestamp 를 채우고, 아니면 자기 자신
+    /// v1 의 내부 offset 은 상대값이고 wrapper 의 offset 이 마지막 내부 메시지의 절대 offset 임
+    fn into_messages(self) -> Result<Vec<LegacyMessage>, ApplicationError> {
+        let compression = self.compression_type()?;
+        if compression == CompressionType::None {
+            return Ok(vec![self]);
+        }
+
+        let value = self.value.as_deref()
+            .ok_or_else(|| ApplicationError::Protocol("compressed message without value".to_string()))?;
+        let mut inner = Bytes::from(decompress(compression, value, MAX_DECOMPRESSED_BYTES)?);
+        let mut messages = Vec::new();
+        while inner.has_remaining() {
+            let message = LegacyMessage::from_bytes(&mut inner)?;
+            if message.magic != self.magic || message.attributes & COMPRESSION_CODEC_MASK != 0 {
+                return Err(ApplicationError::Protocol("invalid message inside compressed message".to_string()));
+            }
+            messages.push(message);
+        }
+
+        if self.magic > 0 {
+            let last_relative_offset = messages.last().map_or(0, |message| message.offset);
+            for message in &mut messages {
+                message.offset = self.offset.saturating_sub(last_relative_offset).saturating_add(message.offset);
+                if self.timestamp_type() == TimestampType::LogAppendTime {
+                    message.timestamp = self.timestamp;
+                }
+            }
+        }
+        Ok(messages)
+    }
+}
+
+/// 버퍼의 첫 메시지가 magic v0 / v1 인지 (magic 은 RecordBatch v2 와 같은 자리에 있음)
+pub fn is_legacy_message_set(buf: &[u8]) -> bool {
+    matches!(buf.get(MAGIC_OFFSET), Some(0 | 1))
+}
+
+/// 메시지 셋을 읽어서 압축된 wrapper 는 내부 메시지로 풀어낸 목록을 돌려줌
+pub fn read_message_set(buf: &[u8]) -> Result<Vec<LegacyMessage>, ApplicationError> {
+    let mut messages = Vec::new();
+    for wrapper in read_wrappers(buf)? {
+        messages.extend(wrapper.into_messages()?);
+    }
+    Ok(messages)
+}
+
+/// 프로듀서가 보낸 메시지 셋을 RecordBatch v2 하나로 바꿈 (offset 은 로그에 쓸 때 다시 할당됨)
+/// 압축된 메시지 셋이면 같은 코덱으로 다시 압축
+pub fn up_convert(buf: &[u8]) -> Result<Vec<u8>, ApplicationError> {
+    let wrappers = read_wrappers(buf)?;
+    let mut compression = CompressionType::None;
+    for wrapper in &wrappers {
+        let codec = wrapper.compression_type()?;
+        if codec != CompressionType::None {
+            compression = codec;
+        }
+    }
+
+    let mut messages = Vec::new();
+    for wrapper in wrappers {
+        messages.extend(wrapper.into_messages()?);
+    }
+    if messages.is_empty() {
+        return Err(ApplicationError::Protocol("empty message set".to_string()));
+    }
+
+    let base_timestamp = messages[0].timestamp;
+    let records = messages.into_iter()
+        .enumerate()
+        .map(|(offset_delta, message)| Record {
+            timestamp_delta: message.timestamp.saturating_sub(base_timestamp),
+            ..Record::new(offset_delta as i32, message.key, message.value)
+        })
+        .collect();
+    let mut batch = RecordBatch::new(0, NO_PARTITION_LEADER_EPOCH, base_timestamp, records);
+    batch.set_compression_type(compression);
+    batch.to_bytes()
+}
+
+/// RecordBatch v2 들을 magic v0 / v1 메시지 셋으로 바꿈 (v4 미만 Fetch 응답용)
+/// 배치 단위로 변환하고 결과가 max_bytes 를 넘으면 멈춤. 단 첫 배치는 항상 포함
+/// 헤더와 컨트롤 배치는 레거시 포맷으로 표현할 수 없어서 버림
+pub fn down_convert(records: &[u8], magic: i8, max_bytes: usize) -> Result<Vec<u8>, ApplicationError> {
+    let mut src = Bytes::copy_from_slice(records);
+    let mut converted = Vec::new();
+    while src.has_remaining() {
+        let batch = RecordBatch::from_bytes(&mut src)?;
+        if batch.is_control() {
+            continue;
+        }
+        let message_set = down_convert_batch(&batch, magic)?;
+        if !converted.is_empty() && converted.len() + message_set.len() > max_bytes {
+            break;
+        }
+        converted.extend(message_set);
+    }
+    Ok(converted)
+}
+
+fn down_convert_batch(batch: &RecordBatch, magic: i8) -> Result<Vec<u8>, ApplicationError> {
+    let compression = batch.compression_type()?;
+    if compression == CompressionType::Zstd {
+        return Err(ApplicationError::Domain(DomainError::UnsupportedCompressionType));
+    }
+    let log_append_time = batch.timestamp_type() == TimestampType::LogAppendTime;
+    let messages: Vec<LegacyMessage> = batch.records.iter()
+        .map(|record| {
+            let timestamp = if log_append_time {
+                batch.max_timestamp
+            } else {
+                batch.base_timestamp.saturating_add(record.timestamp_delta)
+            };
+            let offset = batch.base_offset.saturating_add(record.offset_delta as i64);
+            LegacyMessage::new(offset, magic, timestamp, record.key.clone(), record.value.clone())
+        })
+        .collect();
+
+    if compression == CompressionType::None {
+        return Ok(messages.iter().flat_map(|message| message.to_bytes()).collect());
+    }
+    let Some(last_offset) = messages.last().map(|message| message.offset) else {
+        return Ok(Vec::new());
+    };
+
+    // v1 의 내부 offset 은 첫 메시지 기준 상대값
+    let first_offset = messages[0].offset;
+    let inner: Vec<u8> = messages.into_iter()
+        .flat_map(|mut message| {
+            if magic > 0 {
+                message.offset -= first_offset;
+            }
+            message.to_bytes()
+        })
+        .collect();
+    let mut wrapper = LegacyMessage::new(last_offset, magic, batch.max_timestamp, None, Some(compress_legacy(compression, magic, &inner)?));
+    wrapper.attributes = compression as i8;
+    if log_append_time && magic > 0 {
+        wrapper.attributes |= TIMESTAMP_TYPE_FLAG;
+    }
+    Ok(wrapper.to_bytes())
+}
+
+fn read_wrappers(buf: &[u8]) -> Result<Vec<LegacyMessage>, ApplicationError> {
+    let mut src = Bytes::copy_from_slice(buf);
+    let mut wrappers = Vec::new();
+    while src.has_remaining() {
+        wrappers.push(LegacyMessage::from_bytes(&mut src)?);
+    }
+    Ok(wrappers)
+}
+
+fn ensure_remaining(src: &Bytes, len: usize, what: &str) -> Result<(), ApplicationError> {
+    if src.remaining() < len {
+        return Err(ApplicationError::Protocol(format!("buffer too short for {}", what)));
+    }
+    Ok(())
+}
+
+/// i32 길이 + 바이트. 길이 -1 은 null
+fn read_bytes(src: &mut Bytes, what: &str) -> Result<Option<Vec<u8>>, ApplicationError> {
+    ensure_remaining(src, 4, what)?;
+    let len = src.get_i32();
+    if len == -1 {
+        return Ok(None);
+    }
+    if len < 0 {
+        return Err(ApplicationError::Protocol(format!("invalid {} length: {}", what, len)));
+    }
+    ensure_remaining(src, len as usize, what)?;
+    Ok(Some(src.split_to(len as usize).to_vec()))
+}
+
+fn write_bytes(value: Option<&[u8]>, dst: &mut BytesMut) {
+    match value {
+        Some(value) => {
+            dst.put_i32(value.len() as i32);
+            dst.put_slice(value);
+        }
+        None => dst.put_i32(-1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message_set(magic: i8, values: &[&[u8]]) -> Vec<u8> {
+        values.iter().enumerate()
+            .flat_map(|(i, value)| LegacyMessage::new(i as i64, magic, 1000 + i as i64, Some(b"key".to_vec()), Some(value.to_vec())).to_bytes())
+            .collect()
+    }
+
+    fn compressed_message_set(magic: i8, compression: CompressionType, values: &[&[u8]]) -> Vec<u8> {
+        let inner = message_set(magic, values);
+        let mut wrapper = LegacyMessage::new(values.len() as i64 - 1, magic, 2000, None, Some(compress_legacy(compression, magic, &inner).unwrap()));
+        wrapper.attributes = compression as i8;
+        wrapper.to_bytes()
+    }
+
+    #[test]
+    fn test_legacy_message_round_trip() {
+        for magic in [0, 1] {
+            let message = LegacyMessage::new(7, magic, 1234, None, Some(b"value".to_vec()));
+            let encoded = message.to_bytes();
+            assert!(is_legacy_message_set(&encoded));
+            assert_eq!(LegacyMessage::from_bytes(&mut Bytes::from(encoded.clone())).unwrap(), message);
+
+            let mut corrupted = encoded;
+            *corrupted.last_mut().unwrap() ^= 0x01;
+            assert!(LegacyMessage::from_bytes(&mut Bytes::from(corrupted)).is_err());
+        }
+        assert_eq!(LegacyMessage::new(0, 0, 1234, None, None).timestamp, NO_TIMESTAMP);
+    }
+
+    #[test]
+    fn test_read_compressed_message_set() {
+        for compression in [CompressionType::Gzip, CompressionType::Snappy, CompressionType::Lz4] {
+            // v1 wrapper offset 5 = 마지막 내부 메시지의 절대 offset
+            let mut wrapper = LegacyMessage::from_bytes(&mut Bytes::from(compressed_message_set(1, compression, &[b"a", b"b", b"c"]))).unwrap();
+            wrapper.offset = 5;
+            let messages = read_message_set(&wrapper.to_bytes()).unwrap();
+            let offsets: Vec<i64> = messages.iter().map(|m| m.offset).collect();
+            assert_eq!(offsets, vec![3, 4, 5], "{:?}", compression);
+            assert_eq!(messages[1].value, Some(b"b".to_vec()));
+            assert_eq!(messages[1].timestamp, 1001);
+
+            let messages = read_message_set(&compressed_message_set(0, compression, &[b"a", b"b"])).unwrap();
+            assert_eq!(messages.len(), 2, "{:?}", compression);
+            assert_eq!(messages[0].timestamp, NO_TIMESTAMP);
+        }
+    }
+
+    #[test]
+    fn test_up_convert() {
+        let batch = RecordBatch::from_bytes(&mut Bytes::from(up_convert(&message_set(1, &[b"a", b"b"])).unwrap())).unwrap();
+        assert_eq!(batch.compression_type().unwrap(), CompressionType::None);
+        assert_eq!(batch.base_timestamp, 1000);
+        assert_eq!(batch.max_timestamp, 1001);
+        assert_eq!(batch.records.len(), 2);
+        assert_eq!(batch.records[1].offset_delta, 1);
+        assert_eq!(batch.records[1].value, Some(b"b".to_vec()));
+
+        let batch = RecordBatch::from_bytes(&mut Bytes::from(up_convert(&compressed_message_set(0, CompressionType::Lz4, &[b"a", b"b", b"c"])).unwrap())).unwrap();
+        assert_eq!(batch.compression_type().unwrap(), CompressionType::Lz4);
+        assert_eq!(batch.next_offset(), 3);
+        assert_eq!(batch.base_timestamp, NO_TIMESTAMP);
+
+        assert!(up_convert(&[]).is_err());
+    }
+
+    #[test]
+    fn test_down_convert() {
+        let records: Vec<Record> = (0..3)
+            .map(|i| Record { timestamp_delta: i as i64, ..Record::new(i, None, Some(vec![b'a' + i as u8])) })
+            .collect();
+        let mut batch = RecordBatch::new(10, 0, 1000, records);
+        let mut batches = batch.to_bytes().unwrap();
+        batch.base_offset = 13;
+        batch.set_compression_type(CompressionType::Gzip);
+        batches.extend(batch.to_bytes().unwrap());
+
+        for magic in [0, 1] {
+            let messages = read_message_set(&down_convert(&batches, magic, usize::MAX).unwrap()).unwrap();
+            let offsets: Vec<i64> = messages.iter().map(|m| m.offset).collect();
+            assert_eq!(offsets, vec![10, 11, 12, 13, 14, 15]);
+            assert!(messages.iter().all(|m| m.magic == magic));
+            assert_eq!(messages[4].value, Some(b"b".to_vec()));
+            assert_eq!(messages[4].timestamp, if magic == 0 { NO_TIMESTAMP } else { 1001 });
+        }
+
+        // 한도를 넘으면 첫 배치만
+        let limited = read_message_set(&down_convert(&batches, 1, 1).unwrap()).unwrap();
+        assert_eq!(limited.len(), 3);
+
+        batch.set_compression_type(CompressionType::Zstd);
+        assert!(matches!(
+            down_convert(&batch.to_bytes().unwrap(), 1, usize::MAX),
+            Err(ApplicationError::Domain(DomainError::UnsupportedCompressionType))
+        ));
+    }
+}
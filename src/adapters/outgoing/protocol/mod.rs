@@ -1 +1,4 @@
-pub mod kraft_record; 
\ No newline at end of file
+pub mod compression;
+pub mod kraft_record;
+pub mod legacy_message;
+pub mod record_batch;
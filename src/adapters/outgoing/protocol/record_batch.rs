@@ -0,0 +1,488 @@
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use crate::adapters::outgoing::log::batch::{write_crc, BATCH_HEADER_SIZE, BATCH_OVERHEAD};
+use crate::adapters::outgoing::protocol::compression::{compress, decompress, MAX_DECOMPRESSED_BYTES};
+use crate::adapters::outgoing::protocol::kraft_record::VarInt;
+use crate::application::error::ApplicationError;
+
+const COMPRESSION_CODEC_MASK: i16 = 0x07;
+const TIMESTAMP_TYPE_FLAG: i16 = 0x08;
+const TRANSACTIONAL_FLAG: i16 = 0x10;
+const CONTROL_FLAG: i16 = 0x20;
+
+// === Type Definitions ===
+
+/// magic v2 RecordBatch. 키 / 값은 해석하지 않은 바이트 그대로 가짐
+/// records 는 항상 압축을 푼 상태이고, 인코딩할 때 attributes 의 코덱으로 다시 압축함
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordBatch {
+    pub base_offset: i64,
+    pub batch_length: i32,
+    pub partition_leader_epoch: i32,
+    pub magic: i8,
+    pub crc: u32,
+    pub attributes: i16,
+    pub last_offset_delta: i32,
+    pub base_timestamp: i64,
+    pub max_timestamp: i64,
+    pub producer_id: i64,
+    pub producer_epoch: i16,
+    pub base_sequence: i32,
+    pub records: Vec<Record>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub attributes: i8,
+    pub timestamp_delta: i64,
+    pub offset_delta: i32,
+    pub key: Option<Vec<u8>>,
+    pub value: Option<Vec<u8>>,
+    pub headers: Vec<Header>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Header {
+    pub key: String,
+    pub value: Option<Vec<u8>>,
+}
+
+/// attributes 의 bit 0~2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i16)]
+pub enum CompressionType {
+    None = 0,
+    Gzip = 1,
+    Snappy = 2,
+    Lz4 = 3,
+    Zstd = 4,
+}
+
+/// attributes 의 bit 3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampType {
+    CreateTime,
+    LogAppendTime,
+}
+
+// === Implementations ===
+
+impl CompressionType {
+    pub fn from_attributes(attributes: i16) -> Result<Self, ApplicationError> {
+        match attributes & COMPRESSION_CODEC_MASK {
+            0 => Ok(CompressionType::None),
+            1 => Ok(CompressionType::Gzip),
+            2 => Ok(CompressionType::Snappy),
+            3 => Ok(CompressionType::Lz4),
+            4 => Ok(CompressionType::Zstd),
+            codec => Err(ApplicationError::Protocol(format!("unknown compression codec: {}", codec))),
+        }
+    }
+}
+
+/// message.timestamp.type 설정 값
+impl std::str::FromStr for TimestampType {
+    type Err = ApplicationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "CreateTime" => Ok(TimestampType::CreateTime),
+            "LogAppendTime" => Ok(TimestampType::LogAppendTime),
+            _ => Err(ApplicationError::Protocol(format!("invalid message.timestamp.type: {}", value))),
+        }
+    }
+}
+
+impl RecordBatch {
+    /// 레코드들로 압축 없는 배치를 만듦. batch_length 와 crc 는 to_bytes 에서 채워짐
+    pub fn new(base_offset: i64, partition_leader_epoch: i32, timestamp: i64, records: Vec<Record>) -> Self {
+        let last_offset_delta = records.last().map_or(0, |r| r.offset_delta);
+        let max_timestamp_delta = records.iter().map(|r| r.timestamp_delta).max().unwrap_or(0);
+
+        Self {
+            base_offset,
+            batch_length: 0,
+            partition_leader_epoch,
+            magic: 2,
+            crc: 0,
+            attributes: 0,
+            last_offset_delta,
+            base_timestamp: timestamp,
+            max_timestamp: timestamp.saturating_add(max_timestamp_delta),
+            producer_id: -1,
+            producer_epoch: -1,
+            base_sequence: -1,
+            records,
+        }
+    }
+
+    pub fn from_bytes(src: &mut Bytes) -> Result<Self, ApplicationError> {
+        ensure_remaining(src, BATCH_HEADER_SIZE, "batch header")?;
+        let base_offset = src.get_i64();
+        let batch_length = src.get_i32();
+        if batch_length < (BATCH_OVERHEAD - BATCH_HEADER_SIZE) as i32 {
+            return Err(ApplicationError::Protocol(format!("invalid batch length: {}", batch_length)));
+        }
+        ensure_remaining(src, batch_length as usize, "record batch")?;
+        // 배치 밖의 바이트는 읽지 않도록 batch_length 만큼만 잘라서 파싱
+        let mut body = src.split_to(batch_length as usize);
+
+        let partition_leader_epoch = body.get_i32();
+        let magic = body.get_i8();
+        if magic != 2 {
+            return Err(ApplicationError::Protocol(format!("unsupported record batch magic: {}", magic)));
+        }
+        let crc = body.get_u32();
+        let computed_crc = crc32c::crc32c(&body);
+        if crc != computed_crc {
+            return Err(ApplicationError::Protocol(format!(
+                "record batch is corrupt (stored crc = {}, computed crc = {})", crc, computed_crc
+            )));
+        }
+        let attributes = body.get_i16();
+        let last_offset_delta = body.get_i32();
+        let base_timestamp = body.get_i64();
+        let max_timestamp = body.get_i64();
+        let producer_id = body.get_i64();
+        let producer_epoch = body.get_i16();
+        let base_sequence = body.get_i32();
+        let records_count = body.get_i32();
+
+        let compression = CompressionType::from_attributes(attributes)?;
+        if compression != CompressionType::None {
+            body = Bytes::from(decompress(compression, &body, MAX_DECOMPRESSED_BYTES)?);
+        }
+        // 레코드는 최소 1 바이트이므로 남은 바이트보다 많을 수 없음
+        if records_count < 0 || records_count as usize > body.remaining() {
+            return Err(ApplicationError::Protocol(format!("invalid records count: {}", records_count)));
+        }
+
+        let mut records = Vec::with_capacity(records_count as usize);
+        for _ in 0..records_count {
+            records.push(Record::from_bytes(&mut body)?);
+        }
+        if body.has_remaining() {
+            return Err(ApplicationError::Protocol(format!("{} unexpected bytes after records", body.remaining())));
+        }
+
+        Ok(Self {
+            base_offset,
+            batch_length,
+            partition_leader_epoch,
+            magic,
+            crc,
+            attributes,
+            last_offset_delta,
+            base_timestamp,
+            max_timestamp,
+            producer_id,
+            producer_epoch,
+            base_sequence,
+            records,
+        })
+    }
+
+    /// 이 배치 다음에 올 배치의 base offset
+    pub fn next_offset(&self) -> i64 {
+        self.base_offset.saturating_add(self.last_offset_delta as i64).saturating_add(1)
+    }
+
+    pub fn compression_type(&self) -> Result<CompressionType, ApplicationError> {
+        CompressionType::from_attributes(self.attributes)
+    }
+
+    /// 인코딩할 때 쓸 코덱을 바꿈
+    pub fn set_compression_type(&mut self, compression: CompressionType) {
+        self.attributes = (self.attributes & !COMPRESSION_CODEC_MASK) | compression as i16;
+    }
+
+    pub fn timestamp_type(&self) -> TimestampType {
+        if self.attributes & TIMESTAMP_TYPE_FLAG != 0 {
+            TimestampType::LogAppendTime
+        } else {
+            TimestampType::CreateTime
+        }
+    }
+
+    pub fn is_transactional(&self) -> bool {
+        self.attributes & TRANSACTIONAL_FLAG != 0
+    }
+
+    /// 트랜잭션 마커, 리더 변경 같은 컨트롤 레코드만 들어있는 배치
+    pub fn is_control(&self) -> bool {
+        self.attributes & CONTROL_FLAG != 0
+    }
+
+    /// records 를 압축하고 batch_length 와 crc 를 계산해서 로그에 그대로 쓸 수 있는 바이트로 인코딩
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ApplicationError> {
+        let mut buf = BytesMut::new();
+        buf.put_i64(self.base_offset);
+        buf.put_i32(0); // batch_length (아래에서 채움)
+        buf.put_i32(self.partition_leader_epoch);
+        buf.put_i8(self.magic);
+        buf.put_u32(0); // crc (아래에서 채움)
+        buf.put_i16(self.attributes);
+        buf.put_i32(self.last_offset_delta);
+        buf.put_i64(self.base_timestamp);
+        buf.put_i64(self.max_timestamp);
+        buf.put_i64(self.producer_id);
+        buf.put_i16(self.producer_epoch);
+        buf.put_i32(self.base_sequence);
+        buf.put_i32(self.records.len() as i32);
+        let records: Vec<u8> = self.records.iter().flat_map(Record::to_bytes).collect();
+        buf.put_slice(&compress(self.compression_type()?, &records)?);
+
+        let batch_length = (buf.len() - BATCH_HEADER_SIZE) as i32;
+        buf[8..12].copy_from_slice(&batch_length.to_be_bytes());
+        write_crc(&mut buf);
+        Ok(buf.to_vec())
+    }
+}
+
+impl Record {
+    pub fn new(offset_delta: i32, key: Option<Vec<u8>>, value: Option<Vec<u8>>) -> Self {
+        Self {
+            attributes: 0,
+            timestamp_delta: 0,
+            offset_delta,
+            key,
+            value,
+            headers: Vec::new(),
+        }
+    }
+
+    /// length, key/value 길이, delta 들은 모두 zigzag varint 로 인코딩됨 (null 은 길이 -1)
+    pub fn from_bytes(src: &mut Bytes) -> Result<Self, ApplicationError> {
+        let length = VarInt::deserialize_signed(src)?;
+        if length < 0 || length as usize > src.remaining() {
+            return Err(ApplicationError::Protocol(format!("invalid record length: {}", length)));
+        }
+        let mut body = src.split_to(length as usize);
+
+        ensure_remaining(&body, 1, "attributes")?;
+        let attributes = body.get_i8();
+        let timestamp_delta = VarInt::deserialize_signed(&mut body)?;
+        let offset_delta = VarInt::deserialize_signed(&mut body)? as i32;
+        let key = read_varint_bytes(&mut body, "key")?;
+        let value = read_varint_bytes(&mut body, "value")?;
+
+        let headers_count = VarInt::deserialize_signed(&mut body)?;
+        if headers_count < 0 || headers_count as usize > body.remaining() {
+            return Err(ApplicationError::Protocol(format!("invalid headers count: {}", headers_count)));
+        }
+        let mut headers = Vec::with_capacity(headers_count as usize);
+        for _ in 0..headers_count {
+            let key = read_varint_bytes(&mut body, "header key")?
+                .ok_or_else(|| ApplicationError::Protocol("null header key".to_string()))?;
+            let key = String::from_utf8(key)
+                .map_err(|e| ApplicationError::Protocol(format!("invalid UTF-8 sequence: {}", e)))?;
+            let value = read_varint_bytes(&mut body, "header value")?;
+            headers.push(Header { key, value });
+        }
+        if body.has_remaining() {
+            return Err(ApplicationError::Protocol(format!("{} unexpected bytes after record", body.remaining())));
+        }
+
+        Ok(Record {
+            attributes,
+            timestamp_delta,
+            offset_delta,
+            key,
+            value,
+            headers,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.push(self.attributes as u8);
+        VarInt::serialize_signed(self.timestamp_delta, &mut body);
+        VarInt::serialize_signed(self.offset_delta as i64, &mut body);
+        write_varint_bytes(self.key.as_deref(), &mut body);
+        write_varint_bytes(self.value.as_deref(), &mut body);
+        VarInt::serialize_signed(self.headers.len() as i64, &mut body);
+        for header in &self.headers {
+            write_varint_bytes(Some(header.key.as_bytes()), &mut body);
+            write_varint_bytes(header.value.as_deref(), &mut body);
+        }
+
+        let mut buf = Vec::with_capacity(body.len() + 2);
+        VarInt::serialize_signed(body.len() as i64, &mut buf);
+        buf.extend_from_slice(&body);
+        buf
+    }
+}
+
+fn ensure_remaining(src: &Bytes, len: usize, what: &str) -> Result<(), ApplicationError> {
+    if src.remaining() < len {
+        return Err(ApplicationError::Protocol(format!("buffer too short for {}", what)));
+    }
+    Ok(())
+}
+
+/// zigzag varint 길이 + 바이트. 길이 -1 은 null
+fn read_varint_bytes(src: &mut Bytes, what: &str) -> Result<Option<Vec<u8>>, ApplicationError> {
+    let len = VarInt::deserialize_signed(src)?;
+    if len == -1 {
+        return Ok(None);
+    }
+    if len < 0 {
+        return Err(ApplicationError::Protocol(format!("invalid {} length: {}", what, len)));
+    }
+    ensure_remaining(src, len as usize, what)?;
+    Ok(Some(src.split_to(len as usize).to_vec()))
+}
+
+fn write_varint_bytes(value: Option<&[u8]>, dst: &mut Vec<u8>) {
+    match value {
+        Some(value) => {
+            VarInt::serialize_signed(value.len() as i64, dst);
+            dst.extend_from_slice(value);
+        }
+        None => VarInt::serialize_signed(-1, dst),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::outgoing::log::batch::CRC_COVERED_START;
+
+    fn user_record(offset_delta: i32, key: Option<&[u8]>, value: Option<&[u8]>) -> Record {
+        Record {
+            timestamp_delta: offset_delta as i64 * 10,
+            headers: vec![
+                Header { key: "trace-id".to_string(), value: Some(b"abc".to_vec()) },
+                Header { key: "empty".to_string(), value: None },
+            ],
+            ..Record::new(offset_delta, key.map(<[u8]>::to_vec), value.map(<[u8]>::to_vec))
+        }
+    }
+
+    #[test]
+    fn test_record_batch_from_bytes() {
+        let mut bytes = vec![
+            0, 0, 0, 0, 0, 0, 0, 1,  // base offset
+            0, 0, 0, 49,             // batch length
+            0, 0, 0, 0,              // partition leader epoch
+            2,                       // magic
+            0, 0, 0, 0,              // crc
+            0, 0x18,                 // attributes (log append time, transactional)
+            0, 0, 0, 0,              // last offset delta
+            0, 0, 0, 0, 0, 0, 0, 0,  // base timestamp
+            0, 0, 0, 0, 0, 0, 0, 0,  // max timestamp
+            0, 0, 0, 0, 0, 0, 0, 0,  // producer id
+            0, 0,                    // producer epoch
+            0, 0, 0, 0,              // base sequence
+            0, 0, 0, 0,              // records length (empty)
+        ];
+        write_crc(&mut bytes);
+
+        let batch = RecordBatch::from_bytes(&mut Bytes::from(bytes)).unwrap();
+        assert_eq!(batch.base_offset, 1);
+        assert_eq!(batch.batch_length, 49);
+        assert_eq!(batch.magic, 2);
+        assert_eq!(batch.compression_type().unwrap(), CompressionType::None);
+        assert_eq!(batch.timestamp_type(), TimestampType::LogAppendTime);
+        assert!(batch.is_transactional());
+        assert!(!batch.is_control());
+        assert!(batch.records.is_empty());
+    }
+
+    #[test]
+    fn test_record_from_bytes() {
+        let mut bytes = Bytes::from(vec![
+            28,                      // length (14)
+            0,                       // attributes
+            2,                       // timestamp delta (1)
+            4,                       // offset delta (2)
+            1,                       // key length (null)
+            6,                       // value length (3)
+            b'f', b'o', b'o',        // value
+            2,                       // headers count (1)
+            4,                       // header key length (2)
+            b'h', b'1',              // header key
+            2,                       // header value length (1)
+            b'v',                    // header value
+        ]);
+
+        let record = Record::from_bytes(&mut bytes).unwrap();
+        assert_eq!(record.attributes, 0);
+        assert_eq!(record.timestamp_delta, 1);
+        assert_eq!(record.offset_delta, 2);
+        assert_eq!(record.key, None);
+        assert_eq!(record.value, Some(b"foo".to_vec()));
+        assert_eq!(record.headers, vec![Header { key: "h1".to_string(), value: Some(b"v".to_vec()) }]);
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn test_record_batch_to_bytes_roundtrip() {
+        let batch = RecordBatch::new(10, 3, 1726045943832, vec![
+            user_record(0, Some(b"key"), Some(b"value")),
+            user_record(1, None, Some(&[0xff; 300])),
+            user_record(2, Some(b""), None),
+        ]);
+
+        let encoded = batch.to_bytes().unwrap();
+        let batch_length = i32::from_be_bytes([encoded[8], encoded[9], encoded[10], encoded[11]]);
+        assert_eq!(batch_length as usize, encoded.len() - BATCH_HEADER_SIZE);
+        let crc = u32::from_be_bytes([encoded[17], encoded[18], encoded[19], encoded[20]]);
+        assert_eq!(crc, crc32c::crc32c(&encoded[CRC_COVERED_START..]));
+
+        let decoded = RecordBatch::from_bytes(&mut Bytes::from(encoded)).unwrap();
+        assert_eq!(decoded.next_offset(), 13);
+        assert_eq!(decoded.max_timestamp, 1726045943832 + 20);
+        assert_eq!(decoded.records, batch.records);
+        assert_eq!(decoded.records[2].key, Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_compressed_record_batch_round_trip() {
+        let records: Vec<Record> = (0..100).map(|i| user_record(i, Some(b"key"), Some(&[i as u8; 100]))).collect();
+        let uncompressed_size = RecordBatch::new(0, 0, 0, records.clone()).to_bytes().unwrap().len();
+
+        for codec in [CompressionType::Gzip, CompressionType::Snappy, CompressionType::Lz4, CompressionType::Zstd] {
+            let mut batch = RecordBatch::new(0, 0, 0, records.clone());
+            batch.set_compression_type(codec);
+            let encoded = batch.to_bytes().unwrap();
+            assert!(encoded.len() < uncompressed_size, "{:?}", codec);
+
+            let decoded = RecordBatch::from_bytes(&mut Bytes::from(encoded)).unwrap();
+            assert_eq!(decoded.compression_type().unwrap(), codec);
+            assert_eq!(decoded.records, records);
+        }
+    }
+
+    #[test]
+    fn test_record_batch_from_bytes_rejects_invalid_batches() {
+        let encoded = RecordBatch::new(0, 0, 0, vec![user_record(0, Some(b"k"), Some(b"v"))]).to_bytes().unwrap();
+
+        // 잘린 배치
+        assert!(RecordBatch::from_bytes(&mut Bytes::from(encoded[..encoded.len() - 1].to_vec())).is_err());
+
+        // 레코드 수가 실제보다 많음
+        let mut corrupted = encoded.clone();
+        corrupted[BATCH_OVERHEAD - 1] = 2;
+        write_crc(&mut corrupted);
+        assert!(RecordBatch::from_bytes(&mut Bytes::from(corrupted)).is_err());
+
+        // 압축 코덱과 맞지 않는 내용
+        let mut compressed = encoded.clone();
+        compressed[CRC_COVERED_START + 1] = 1;
+        write_crc(&mut compressed);
+        assert!(RecordBatch::from_bytes(&mut Bytes::from(compressed)).is_err());
+
+        // crc 불일치
+        let mut corrupted = encoded.clone();
+        *corrupted.last_mut().unwrap() ^= 0x01;
+        let error = RecordBatch::from_bytes(&mut Bytes::from(corrupted)).unwrap_err();
+        assert!(error.to_string().contains("corrupt"));
+
+        // 다음 배치는 건드리지 않음
+        let mut two = encoded.clone();
+        two.extend_from_slice(&encoded);
+        let mut two = Bytes::from(two);
+        RecordBatch::from_bytes(&mut two).unwrap();
+        assert_eq!(two.len(), encoded.len());
+    }
+}
@@ -1,35 +1,85 @@
 use crate::adapters::incoming::protocol::constants::{
-    API_VERSIONS_KEY, DESCRIBE_TOPIC_PARTITIONS_KEY,
-    FETCH_KEY, UNKNOWN_TOPIC_OR_PARTITION,
-    UNSUPPORTED_VERSION,
+    API_VERSIONS_KEY, CREATE_PARTITIONS_KEY, CREATE_TOPICS_KEY, DEFAULT_NUM_PARTITIONS, DEFAULT_REPLICATION_FACTOR, DELETE_TOPICS_KEY, MAX_PARTITIONS_PER_TOPIC, DESCRIBE_TOPIC_PARTITIONS_KEY,
+    DYNAMIC_TOPIC_CONFIG_SOURCE,
+    DOWN_CONVERSION_MAX_BYTES, EARLIEST_LOCAL_TIMESTAMP, EARLIEST_TIMESTAMP, FETCH_KEY, FETCH_RECORD_BATCH_VERSION, FETCH_TOPIC_ID_VERSION, LATEST_TIMESTAMP, LIST_OFFSETS_KEY, MAX_TIMESTAMP, METADATA_KEY, PRODUCE_KEY, UNKNOWN_TOPIC_OR_PARTITION,
 };
 use crate::adapters::incoming::protocol::messages::{
-    ApiVersionsResponse, DescribeTopicPartitionsResponse, ErrorCode, FetchResponse,
-    KafkaMessage, KafkaRequest, KafkaResponse, PartitionInfo,
-    RequestHeader, RequestPayload, ResponsePayload, TopicRequest, TopicResponse,
+    ApiVersionsResponse, CreatableTopic, CreatableTopicConfigs, CreatableTopicResult, CreatePartitionsResponse,
+    CreatePartitionsTopic, CreatePartitionsTopicResult, CreateTopicsResponse,
+    DeletableTopicResult, DeleteTopicState, DeleteTopicsResponse,
+    DescribeTopicPartitionsResponse, EpochEndOffset, ErrorCode, FetchPartition, FetchRequest,
+    FetchResponse, FetchTopic, LeaderIdAndEpoch,
+    KafkaRequest, KafkaResponse, ListOffsetsPartition, ListOffsetsPartitionResponse, ListOffsetsResponse,
+    ListOffsetsTopicResponse, MetadataRequest, MetadataRequestTopic, MetadataResponse, MetadataResponseBroker,
+    MetadataResponsePartition, MetadataResponseTopic, PartitionInfo, ProducePartition,
+    ProducePartitionResponse, ProduceResponse, ProduceTopicResponse,
+    RequestPayload, ResponsePayload, TopicResponse,
     FetchableTopicResponse, FetchablePartitionResponse,
 };
-use crate::domain::message::TopicMetadata;
-use crate::ports::incoming::message_handler::MessageHandler;
+use crate::adapters::outgoing::log::partition_log::LogConfig;
+use crate::adapters::outgoing::protocol::legacy_message::down_convert;
+use crate::domain::message::{BrokerMetadata, Partition, TimestampOffset, TopicMetadata};
+use crate::ports::incoming::message_handler::{MessageHandler, ParkSignal};
 use crate::ports::outgoing::message_store::MessageStore;
 use crate::ports::outgoing::metadata_store::MetadataStore;
 use crate::application::error::ApplicationError;
+use crate::application::fetch_session::FetchSessionCache;
+use crate::application::purgatory::{FetchPurgatory, TopicPartition};
+use crate::domain::error::DomainError;
 use crate::Result;
 use async_trait::async_trait;
-use hex;
 
 #[allow(dead_code)]
 pub struct KafkaBroker {
     message_store: Box<dyn MessageStore>,
     metadata_store: Box<dyn MetadataStore>,
+    node: BrokerMetadata,
+    purgatory: FetchPurgatory,
+    fetch_sessions: FetchSessionCache,
+    /// 자동 생성되는 토픽의 파티션 수 (num.partitions)
+    num_partitions: u32,
+}
+
+/// CreateTopics 검증을 통과한 토픽: 파티션 i 의 replica 목록이 assignments[i]
+struct NewTopic {
+    assignments: Vec<Vec<u32>>,
+    configs: Vec<(String, String)>,
 }
 
 impl KafkaBroker {
-    pub fn new(message_store: Box<dyn MessageStore>, metadata_store: Box<dyn MetadataStore>) -> Self {
+    pub fn new(message_store: Box<dyn MessageStore>, metadata_store: Box<dyn MetadataStore>, node: BrokerMetadata) -> Self {
         Self { 
             message_store,
             metadata_store,
+            node,
+            purgatory: FetchPurgatory::new(),
+            fetch_sessions: FetchSessionCache::default(),
+            num_partitions: DEFAULT_NUM_PARTITIONS,
+        }
+    }
+
+    pub fn with_num_partitions(mut self, num_partitions: u32) -> Self {
+        self.num_partitions = num_partitions;
+        self
+    }
+
+    fn topic_id_to_bytes(topic_id: &str) -> [u8; 16] {
+        let topic_id_bytes = hex::decode(topic_id.replace("-", ""))
+            .unwrap_or(vec![0; 16]);
+        let mut bytes = [0u8; 16];
+        if topic_id_bytes.len() == 16 {
+            bytes.copy_from_slice(&topic_id_bytes);
         }
+        bytes
+    }
+
+    /// 토픽 이름 규칙: 1~249 자의 [a-zA-Z0-9._-], "." 과 ".." 은 불가
+    fn is_valid_topic_name(name: &str) -> bool {
+        !name.is_empty()
+            && name.len() <= 249
+            && name != "."
+            && name != ".."
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-')
     }
 
     fn convert_topic_id_to_uuid(topic_id: &[u8]) -> String {
@@ -44,66 +94,388 @@ impl KafkaBroker {
         )
     }
 
-    async fn handle_fetch_request(&self, request: &KafkaRequest, fetch_request: &RequestPayload) -> Result<KafkaResponse> {
-        if let RequestPayload::Fetch(fetch_request) = fetch_request {
-            match fetch_request.topics.first() {
-                Some(first_topic) => {
-                    let topic_id = Self::convert_topic_id_to_uuid(&first_topic.topic_id);
-                    println!("[DEBUG] Looking for topic_id: {}", topic_id);
-                    let topic_metadata = self.metadata_store.get_topic_metadata_by_ids(vec![topic_id.clone()]).await?;
-                    
-                    let response = match topic_metadata {
-                        Some(metadata_list) => {
-                            let metadata = metadata_list.first().unwrap();
-                            if metadata.error_code == i16::from(ErrorCode::UnknownTopicOrPartition) {
-                                FetchResponse::unknown_topic(first_topic.topic_id)
-                            } else {
-                                if let Some(partition) = first_topic.partitions.first() {
-                                    let records = self.message_store.read_messages(&metadata.name, partition.partition, partition.fetch_offset).await?;
-                                    println!("[DEBUG] Read records: {:?}", records.is_some());
-                                    
-                                    FetchResponse {
-                                        throttle_time_ms: 0,
-                                        session_id: 0,
-                                        responses: vec![
-                                            FetchableTopicResponse {
-                                                topic_id: first_topic.topic_id,
-                                                partitions: vec![
-                                                    FetchablePartitionResponse {
-                                                        partition_index: partition.partition,
-                                                        error_code: 0,
-                                                        high_watermark: 1,
-                                                        records,
-                                                    }
-                                                ],
-                                            }
-                                        ],
-                                    }
-                                } else {
-                                    FetchResponse::empty_topic(first_topic.topic_id)
-                                }
-                            }
-                        },
-                        None => FetchResponse::unknown_topic(first_topic.topic_id),
-                    };
+    /// 한 파티션을 partition_max_bytes 와 남은 응답 한도 (remaining_bytes) 안에서 읽음
+    /// 응답에 아직 데이터가 없으면 (has_data == false) 한도를 넘더라도 첫 배치 하나는 포함
+    /// magic 이 2 보다 작으면 (v4 미만 Fetch) 읽은 배치를 그 magic 의 메시지 셋으로 바꿈
+    #[allow(clippy::too_many_arguments)]
+    async fn read_partition(&self, topic_name: &str, metadata: &Partition, partition: &FetchPartition, isolation_level: i8, magic: i8, remaining_bytes: &mut i32, has_data: bool) -> Result<FetchablePartitionResponse> {
+        let leader_epoch = metadata.leader_epoch as i32;
+        if partition.current_leader_epoch >= 0 && partition.current_leader_epoch != leader_epoch {
+            println!("[DEBUG] Fetch leader epoch {} does not match {} for {}-{}", partition.current_leader_epoch, leader_epoch, topic_name, partition.partition);
+            return Ok(if partition.current_leader_epoch < leader_epoch {
+                FetchablePartitionResponse {
+                    current_leader: Some(LeaderIdAndEpoch { leader_id: metadata.leader_id as i32, leader_epoch }),
+                    ..FetchablePartitionResponse::error(partition.partition, i16::from(ErrorCode::FencedLeaderEpoch))
+                }
+            } else {
+                FetchablePartitionResponse::error(partition.partition, i16::from(ErrorCode::UnknownLeaderEpoch))
+            });
+        }
+
+        let offsets = self.message_store.get_partition_offsets(topic_name, partition.partition).await?;
+        let mut response = FetchablePartitionResponse {
+            partition_index: partition.partition,
+            error_code: i16::from(ErrorCode::None),
+            high_watermark: offsets.high_watermark,
+            last_stable_offset: offsets.last_stable_offset,
+            log_start_offset: offsets.log_start_offset,
+            // 트랜잭션은 아직 지원하지 않으므로 READ_COMMITTED 에도 abort 된 것은 없음
+            aborted_transactions: (isolation_level == 1).then(Vec::new),
+            preferred_read_replica: -1,
+            diverging_epoch: None,
+            current_leader: None,
+            records: None,
+        };
+
+        // 팔로워가 마지막으로 받은 epoch 가 리더와 다르면 어디까지 잘라야 하는지 알려줌
+        if let Some(diverging_epoch) = Self::diverging_epoch(partition, leader_epoch, offsets.log_start_offset, offsets.log_end_offset) {
+            println!("[DEBUG] Fetch diverging at {:?} for {}-{}", diverging_epoch, topic_name, partition.partition);
+            response.diverging_epoch = Some(diverging_epoch);
+            return Ok(response);
+        }
+        if has_data && *remaining_bytes <= 0 {
+            return Ok(response);
+        }
+
+        let mut max_bytes = partition.partition_max_bytes.min(*remaining_bytes).max(0);
+        if magic < 2 {
+            max_bytes = max_bytes.min(DOWN_CONVERSION_MAX_BYTES);
+        }
+        let records = match self.message_store.read_messages(topic_name, partition.partition, partition.fetch_offset, max_bytes).await {
+            Ok(Some(records)) if magic < 2 => {
+                let converted = down_convert(&records, magic, max_bytes as usize)?;
+                println!("[DEBUG] Down-converted {} bytes to {} bytes of magic v{} for {}-{}", records.len(), converted.len(), magic, topic_name, partition.partition);
+                Ok((!converted.is_empty()).then_some(converted))
+            },
+            records => records,
+        };
+        match records {
+            Ok(Some(records)) => {
+                // 최소 한 배치 규칙은 응답 전체에서 첫 데이터에만 적용
+                if has_data && records.len() > max_bytes as usize {
+                    return Ok(response);
+                }
+                println!("[DEBUG] Read {} bytes from {}-{} at offset {}", records.len(), topic_name, partition.partition, partition.fetch_offset);
+                *remaining_bytes = remaining_bytes.saturating_sub(records.len() as i32);
+                response.records = Some(records);
+            },
+            Ok(None) => {},
+            Err(ApplicationError::Domain(DomainError::OffsetOutOfRange(offset))) => {
+                println!("[DEBUG] Fetch offset {} out of range for {}-{}", offset, topic_name, partition.partition);
+                response = FetchablePartitionResponse::error(partition.partition, i16::from(ErrorCode::OffsetOutOfRange));
+            },
+            Err(e) => return Err(e),
+        }
+        Ok(response)
+    }
+
+    /// 리더 epoch 기록이 현재 epoch 하나 (log start 부터 시작) 뿐이라고 보고 Kafka 의 endOffsetFor 규칙을 적용
+    fn diverging_epoch(partition: &FetchPartition, leader_epoch: i32, log_start_offset: i64, log_end_offset: i64) -> Option<EpochEndOffset> {
+        let last_fetched_epoch = partition.last_fetched_epoch;
+        if last_fetched_epoch < 0 {
+            return None;
+        }
+        let epoch_end = match last_fetched_epoch.cmp(&leader_epoch) {
+            std::cmp::Ordering::Equal => EpochEndOffset { epoch: leader_epoch, end_offset: log_end_offset },
+            std::cmp::Ordering::Less => EpochEndOffset { epoch: last_fetched_epoch, end_offset: log_start_offset },
+            std::cmp::Ordering::Greater => EpochEndOffset { epoch: -1, end_offset: -1 },
+        };
+        (epoch_end.epoch < last_fetched_epoch || epoch_end.end_offset < partition.fetch_offset).then_some(epoch_end)
+    }
+
+    /// Fetch 버전의 클라이언트가 읽을 수 있는 레코드 포맷 (v0~v1: magic 0, v2~v3: magic 1, v4+: magic 2)
+    fn fetch_magic(api_version: i16) -> i8 {
+        match api_version {
+            FETCH_RECORD_BATCH_VERSION.. => 2,
+            2.. => 1,
+            _ => 0,
+        }
+    }
+
+    /// 요청한 모든 파티션을 한 번 읽어서 토픽별 응답을 만듦
+    async fn read_fetch_topics(&self, topics: &[FetchTopic], fetch_request: &FetchRequest, magic: i8, topic_metadata: &[Option<TopicMetadata>], unknown_topic_error: ErrorCode) -> Result<Vec<FetchableTopicResponse>> {
+        let mut remaining_bytes = fetch_request.max_bytes;
+        let mut has_data = false;
+        let mut responses = Vec::with_capacity(topics.len());
+        for (topic, metadata) in topics.iter().zip(topic_metadata) {
+            let mut partitions = Vec::with_capacity(topic.partitions.len());
+            for partition in &topic.partitions {
+                let partition_metadata = metadata.as_ref()
+                    .map(|metadata| (metadata, metadata.partitions.iter().find(|p| p.partition_index as i32 == partition.partition)));
+                let partition_response = match partition_metadata {
+                    None => FetchablePartitionResponse::error(partition.partition, i16::from(unknown_topic_error)),
+                    Some((_, None)) => {
+                        FetchablePartitionResponse::error(partition.partition, i16::from(ErrorCode::UnknownTopicOrPartition))
+                    },
+                    Some((metadata, Some(partition_metadata))) => {
+                        match self.read_partition(&metadata.name, partition_metadata, partition, fetch_request.isolation_level, magic, &mut remaining_bytes, has_data).await {
+                            Ok(response) => {
+                                has_data |= response.records.is_some();
+                                response
+                            },
+                            Err(e) => {
+                                println!("[DEBUG] Fetch from {}-{} failed: {}", metadata.name, partition.partition, e);
+                                FetchablePartitionResponse::error(partition.partition, i16::from(ErrorCode::from(&e)))
+                            },
+                        }
+                    },
+                };
+                partitions.push(partition_response);
+            }
+
+            responses.push(FetchableTopicResponse {
+                topic: topic.topic.clone(),
+                topic_id: topic.topic_id,
+                partitions,
+            });
+        }
+        Ok(responses)
+    }
+
+    /// 요청 토픽마다 메타데이터를 찾음. v13 부터는 topic_id, 그 전에는 이름으로 찾고 없으면 None
+    async fn resolve_fetch_topics(&self, topics: &[FetchTopic], api_version: i16) -> Result<Vec<Option<TopicMetadata>>> {
+        if topics.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let uses_topic_ids = api_version >= FETCH_TOPIC_ID_VERSION;
+        let keys: Vec<String> = topics.iter()
+            .map(|topic| if uses_topic_ids { Self::convert_topic_id_to_uuid(&topic.topic_id) } else { topic.topic.clone() })
+            .collect();
+        println!("[DEBUG] Looking for fetch topics: {:?}", keys);
+        let topic_metadata = if uses_topic_ids {
+            self.metadata_store.get_topic_metadata_by_ids(keys.clone()).await?
+        } else {
+            self.metadata_store.get_topic_metadata_by_names(keys.clone()).await?
+        }.unwrap_or_default();
+
+        Ok(keys.iter()
+            .map(|key| topic_metadata.iter()
+                .find(|metadata| {
+                    let matches = if uses_topic_ids { &metadata.topic_id == key } else { &metadata.name == key };
+                    matches && metadata.error_code == i16::from(ErrorCode::None)
+                })
+                .cloned())
+            .collect())
+    }
 
-                    Ok(KafkaResponse::new(
+    /// min_bytes 만큼 모였거나 에러가 난 파티션이 있으면 바로 응답할 수 있음
+    fn is_fetch_satisfied(responses: &[FetchableTopicResponse], min_bytes: i32) -> bool {
+        let partitions = responses.iter().flat_map(|topic| &topic.partitions);
+        let accumulated: usize = partitions.clone()
+            .filter_map(|partition| partition.records.as_ref())
+            .map(|records| records.len())
+            .sum();
+        accumulated >= min_bytes.max(1) as usize
+            || partitions.clone().any(|partition| partition.error_code != i16::from(ErrorCode::None) || partition.diverging_epoch.is_some())
+    }
+
+    /// min_bytes 가 모자라면 purgatory 에 걸기 전에 parked 로 커넥션을 풀어줌
+    async fn handle_fetch_request(&self, request: &KafkaRequest, fetch_request: &RequestPayload, parked: &mut ParkSignal) -> Result<KafkaResponse> {
+        if let RequestPayload::Fetch(fetch_request) = fetch_request {
+            // 세션 (KIP-227) 에 따라 실제로 읽을 파티션 목록이 정해짐
+            let context = match self.fetch_sessions.resolve(fetch_request) {
+                Ok(context) => context,
+                Err(error_code) => {
+                    println!("[DEBUG] Fetch session error: {:?}", error_code);
+                    return Ok(KafkaResponse::new(
                         request.header.correlation_id,
-                        0,
-                        ResponsePayload::Fetch(response),
-                    ))
+                        request.header.api_version,
+                        i16::from(error_code),
+                        ResponsePayload::Fetch(FetchResponse {
+                            throttle_time_ms: 0,
+                            session_id: 0,
+                            responses: vec![],
+                            node_endpoints: vec![],
+                        }),
+                    ));
+                },
+            };
+            let topics = &context.topics;
+            let api_version = request.header.api_version;
+            if fetch_request.replica_id >= 0 {
+                println!("[DEBUG] Fetch from follower replica {}", fetch_request.replica_id);
+            }
+
+            // 요청에 있는 모든 토픽을 한 번에 조회
+            let topic_metadata = self.resolve_fetch_topics(topics, api_version).await?;
+            let unknown_topic_error = if api_version >= FETCH_TOPIC_ID_VERSION { ErrorCode::UnknownTopicId } else { ErrorCode::UnknownTopicOrPartition };
+            let magic = Self::fetch_magic(api_version);
+
+            // 데이터를 읽기 전에 watch 를 걸어야 그 사이에 들어온 append 를 놓치지 않음
+            let watched: Vec<TopicPartition> = topics.iter().zip(&topic_metadata)
+                .filter_map(|(topic, metadata)| metadata.as_ref().map(|metadata| (topic, metadata)))
+                .flat_map(|(topic, metadata)| topic.partitions.iter().map(|p| (metadata.name.clone(), p.partition)))
+                .collect();
+            let delayed = (fetch_request.max_wait_ms > 0 && !watched.is_empty())
+                .then(|| self.purgatory.watch(&watched, fetch_request.max_wait_ms));
+
+            let mut responses = self.read_fetch_topics(topics, fetch_request, magic, &topic_metadata, unknown_topic_error).await?;
+            if let Some(delayed) = delayed {
+                while !Self::is_fetch_satisfied(&responses, fetch_request.min_bytes) && !delayed.is_expired() {
+                    println!("[DEBUG] Parking fetch (correlation_id: {}) until min_bytes {} or {} ms", request.header.correlation_id, fetch_request.min_bytes, fetch_request.max_wait_ms);
+                    parked.park();
+                    // 깨어났든 시간이 다 됐든 한 번 더 읽어서 최신 상태로 응답
+                    let woken = delayed.wait().await;
+                    responses = self.read_fetch_topics(topics, fetch_request, magic, &topic_metadata, unknown_topic_error).await?;
+                    if !woken {
+                        break;
+                    }
                 }
-                None => Ok(KafkaResponse::new(
-                    request.header.correlation_id,
-                    0,
-                    ResponsePayload::Fetch(FetchResponse::empty()),
-                ))
             }
+
+            let responses = self.fetch_sessions.complete(&context, responses);
+            // current_leader 를 알려준 리더의 접속 정보. 리더는 항상 이 브로커
+            let node_endpoints = if responses.iter().flat_map(|topic| &topic.partitions).any(|p| p.current_leader.is_some()) {
+                vec![MetadataResponseBroker {
+                    node_id: self.node.node_id,
+                    host: self.node.host.clone(),
+                    port: self.node.port,
+                    rack: self.node.rack.clone(),
+                }]
+            } else {
+                vec![]
+            };
+
+            Ok(KafkaResponse::new(
+                request.header.correlation_id,
+                api_version,
+                0,
+                ResponsePayload::Fetch(FetchResponse {
+                    throttle_time_ms: 0,
+                    session_id: context.session_id,
+                    responses,
+                    node_endpoints,
+                }),
+            ))
         } else {
             unreachable!()
         }
     }
 
+    /// 요청한 API 의 응답 스키마로 에러 코드를 돌려줌 (body 에 있던 항목마다 에러를 채움)
+    /// UNSUPPORTED_VERSION 인 ApiVersions 는 클라이언트가 어떤 버전이든 읽을 수 있도록 v0 으로 응답
+    fn error_response(request: &KafkaRequest, error: ErrorCode) -> Result<KafkaResponse> {
+        let error_code = i16::from(error);
+        let (api_version, payload) = match request.header.api_key {
+            API_VERSIONS_KEY => (
+                if error == ErrorCode::UnsupportedVersion { 0 } else { request.header.api_version },
+                ResponsePayload::ApiVersions(ApiVersionsResponse::default()),
+            ),
+            FETCH_KEY => (request.header.api_version, ResponsePayload::Fetch(FetchResponse {
+                throttle_time_ms: 0,
+                session_id: 0,
+                responses: match &request.payload {
+                    RequestPayload::Fetch(fetch) => fetch.topics.iter()
+                        .map(|topic| FetchableTopicResponse {
+                            topic: topic.topic.clone(),
+                            topic_id: topic.topic_id,
+                            partitions: topic.partitions.iter()
+                                .map(|p| FetchablePartitionResponse::error(p.partition, error_code))
+                                .collect(),
+                        })
+                        .collect(),
+                    _ => vec![],
+                },
+                node_endpoints: vec![],
+            })),
+            DESCRIBE_TOPIC_PARTITIONS_KEY => (request.header.api_version, ResponsePayload::DescribeTopicPartitions(DescribeTopicPartitionsResponse {
+                topics: match &request.payload {
+                    RequestPayload::DescribeTopicPartitions(describe) => describe.topics.iter()
+                        .map(|topic| TopicResponse {
+                            topic_name: topic.topic_name.clone(),
+                            topic_id: [0; 16],
+                            error_code,
+                            is_internal: false,
+                            partitions: vec![],
+                        })
+                        .collect(),
+                    _ => vec![],
+                },
+            })),
+            PRODUCE_KEY => (request.header.api_version, ResponsePayload::Produce(ProduceResponse {
+                responses: match &request.payload {
+                    RequestPayload::Produce(produce) => produce.topics.iter()
+                        .map(|topic| ProduceTopicResponse {
+                            name: topic.name.clone(),
+                            partitions: topic.partitions.iter()
+                                .map(|p| ProducePartitionResponse::error(p.index, error_code))
+                                .collect(),
+                        })
+                        .collect(),
+                    _ => vec![],
+                },
+                throttle_time_ms: 0,
+            })),
+            METADATA_KEY => (request.header.api_version, ResponsePayload::Metadata(MetadataResponse {
+                throttle_time_ms: 0,
+                brokers: vec![],
+                cluster_id: None,
+                controller_id: -1,
+                topics: match &request.payload {
+                    RequestPayload::Metadata(metadata) => metadata.topics.iter().flatten()
+                        .map(|topic| Self::metadata_topic_error(topic.name.clone(), topic.topic_id, error))
+                        .collect(),
+                    _ => vec![],
+                },
+                cluster_authorized_operations: i32::MIN,
+            })),
+            LIST_OFFSETS_KEY => (request.header.api_version, ResponsePayload::ListOffsets(ListOffsetsResponse {
+                throttle_time_ms: 0,
+                topics: match &request.payload {
+                    RequestPayload::ListOffsets(list_offsets) => list_offsets.topics.iter()
+                        .map(|topic| ListOffsetsTopicResponse {
+                            name: topic.name.clone(),
+                            partitions: topic.partitions.iter()
+                                .map(|p| ListOffsetsPartitionResponse::error(p.partition_index, error_code))
+                                .collect(),
+                        })
+                        .collect(),
+                    _ => vec![],
+                },
+            })),
+            CREATE_TOPICS_KEY => (request.header.api_version, ResponsePayload::CreateTopics(CreateTopicsResponse {
+                throttle_time_ms: 0,
+                topics: match &request.payload {
+                    RequestPayload::CreateTopics(create_topics) => create_topics.topics.iter()
+                        .map(|topic| CreatableTopicResult::error(topic.name.clone(), error_code, None))
+                        .collect(),
+                    _ => vec![],
+                },
+            })),
+            DELETE_TOPICS_KEY => (request.header.api_version, ResponsePayload::DeleteTopics(DeleteTopicsResponse {
+                throttle_time_ms: 0,
+                responses: match &request.payload {
+                    RequestPayload::DeleteTopics(delete_topics) => delete_topics.topics.iter()
+                        .map(|topic| DeletableTopicResult {
+                            name: topic.name.clone(),
+                            topic_id: topic.topic_id,
+                            error_code,
+                            error_message: None,
+                        })
+                        .collect(),
+                    _ => vec![],
+                },
+            })),
+            CREATE_PARTITIONS_KEY => (request.header.api_version, ResponsePayload::CreatePartitions(CreatePartitionsResponse {
+                throttle_time_ms: 0,
+                results: match &request.payload {
+                    RequestPayload::CreatePartitions(create_partitions) => create_partitions.topics.iter()
+                        .map(|topic| CreatePartitionsTopicResult {
+                            name: topic.name.clone(),
+                            error_code,
+                            error_message: None,
+                        })
+                        .collect(),
+                    _ => vec![],
+                },
+            })),
+            api_key => return Err(ApplicationError::Protocol(format!("Unknown API key: {}", api_key))),
+        };
+
+        Ok(KafkaResponse::new(request.header.correlation_id, api_version, error_code, payload))
+    }
+
     async fn handle_describe_topic_partitions(&self, request: &KafkaRequest, describe_request: &RequestPayload) -> Result<KafkaResponse> {
         if let RequestPayload::DescribeTopicPartitions(req) = describe_request {
             let topic_names: Vec<String> = req.topics.iter()
@@ -129,6 +501,7 @@ impl KafkaBroker {
 
             Ok(KafkaResponse::new(
                 request.header.correlation_id,
+                request.header.api_version,
                 0,
                 ResponsePayload::DescribeTopicPartitions(
                     DescribeTopicPartitionsResponse { topics }
@@ -139,106 +512,945 @@ impl KafkaBroker {
         }
     }
 
-    fn create_topic_response(&self, metadata: TopicMetadata) -> TopicResponse {
-        let topic_id_bytes = hex::decode(metadata.topic_id.replace("-", ""))
-            .unwrap_or(vec![0; 16]);
-        let mut topic_id = [0u8; 16];
-        topic_id.copy_from_slice(&topic_id_bytes);
+    async fn handle_produce_request(&self, request: &KafkaRequest, produce_request: &RequestPayload) -> Result<Option<KafkaResponse>> {
+        if let RequestPayload::Produce(produce_request) = produce_request {
+            let acks = produce_request.acks;
+            let valid_acks = (-1..=1).contains(&acks);
+            let topic_names: Vec<String> = produce_request.topics.iter()
+                .map(|t| t.name.clone())
+                .collect();
+            let topic_metadata = if valid_acks {
+                self.metadata_store.get_topic_metadata_by_names(topic_names).await?
+                    .unwrap_or_default()
+            } else {
+                Vec::new()
+            };
 
-        TopicResponse {
-            topic_name: metadata.name,
+            let mut responses = Vec::with_capacity(produce_request.topics.len());
+            for topic in &produce_request.topics {
+                let metadata = topic_metadata.iter()
+                    .find(|m| m.name == topic.name && m.error_code == i16::from(ErrorCode::None));
+
+                let mut partitions = Vec::with_capacity(topic.partitions.len());
+                for partition in &topic.partitions {
+                    let partition_response = if !valid_acks {
+                        ProducePartitionResponse::error(partition.index, i16::from(ErrorCode::InvalidRequiredAcks))
+                    } else {
+                        match metadata {
+                            Some(metadata) if metadata.partitions.iter()
+                                .any(|p| p.partition_index as i32 == partition.index) => {
+                                match self.append_partition(&topic.name, partition).await {
+                                    Ok(response) => response,
+                                    Err(e) => {
+                                        println!("[DEBUG] Produce to {}-{} failed: {}", topic.name, partition.index, e);
+                                        ProducePartitionResponse::error(partition.index, i16::from(ErrorCode::from(&e)))
+                                    },
+                                }
+                            }
+                            _ => ProducePartitionResponse::error(partition.index, UNKNOWN_TOPIC_OR_PARTITION),
+                        }
+                    };
+                    partitions.push(partition_response);
+                }
+
+                responses.push(ProduceTopicResponse {
+                    name: topic.name.clone(),
+                    partitions,
+                });
+            }
+
+            // acks=0 이면 프로듀서는 응답을 기다리지 않음
+            if acks == 0 {
+                return Ok(None);
+            }
+
+            Ok(Some(KafkaResponse::new(
+                request.header.correlation_id,
+                request.header.api_version,
+                0,
+                ResponsePayload::Produce(ProduceResponse {
+                    responses,
+                    throttle_time_ms: 0,
+                }),
+            )))
+        } else {
+            unreachable!()
+        }
+    }
+
+    async fn append_partition(&self, topic_name: &str, partition: &ProducePartition) -> Result<ProducePartitionResponse> {
+        let records = match &partition.records {
+            Some(records) => records,
+            None => return Ok(ProducePartitionResponse::error(partition.index, i16::from(ErrorCode::CorruptMessage))),
+        };
+
+        match self.message_store.store_message(topic_name, partition.index, records).await {
+            Ok(append_info) => {
+                self.purgatory.notify(topic_name, partition.index);
+                let offsets = self.message_store.get_partition_offsets(topic_name, partition.index).await?;
+                Ok(ProducePartitionResponse {
+                    index: partition.index,
+                    error_code: i16::from(ErrorCode::None),
+                    base_offset: append_info.base_offset,
+                    log_append_time_ms: append_info.log_append_time,
+                    log_start_offset: offsets.log_start_offset,
+                })
+            },
+            Err(ApplicationError::Protocol(e)) => {
+                println!("[DEBUG] Rejecting produce to {}-{}: {}", topic_name, partition.index, e);
+                Ok(ProducePartitionResponse::error(partition.index, i16::from(ErrorCode::CorruptMessage)))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn handle_list_offsets_request(&self, request: &KafkaRequest, list_offsets_request: &RequestPayload) -> Result<KafkaResponse> {
+        if let RequestPayload::ListOffsets(list_offsets_request) = list_offsets_request {
+            let topic_names: Vec<String> = list_offsets_request.topics.iter()
+                .map(|t| t.name.clone())
+                .collect();
+            let topic_metadata = if topic_names.is_empty() {
+                Vec::new()
+            } else {
+                self.metadata_store.get_topic_metadata_by_names(topic_names).await?.unwrap_or_default()
+            };
+
+            let mut topics = Vec::with_capacity(list_offsets_request.topics.len());
+            for topic in &list_offsets_request.topics {
+                let metadata = topic_metadata.iter()
+                    .find(|m| m.name == topic.name && m.error_code == i16::from(ErrorCode::None));
+
+                let mut partitions = Vec::with_capacity(topic.partitions.len());
+                for partition in &topic.partitions {
+                    let leader_epoch = metadata
+                        .and_then(|m| m.partitions.iter().find(|p| p.partition_index as i32 == partition.partition_index))
+                        .map(|p| p.leader_epoch as i32);
+                    let partition_response = match leader_epoch {
+                        Some(leader_epoch) => self.list_partition_offset(
+                            &topic.name,
+                            partition,
+                            request.header.api_version,
+                            list_offsets_request.isolation_level,
+                            leader_epoch,
+                        ).await.unwrap_or_else(|e| {
+                            println!("[DEBUG] ListOffsets for {}-{} failed: {}", topic.name, partition.partition_index, e);
+                            ListOffsetsPartitionResponse::error(partition.partition_index, i16::from(ErrorCode::from(&e)))
+                        }),
+                        None => ListOffsetsPartitionResponse::error(partition.partition_index, i16::from(ErrorCode::UnknownTopicOrPartition)),
+                    };
+                    partitions.push(partition_response);
+                }
+
+                topics.push(ListOffsetsTopicResponse {
+                    name: topic.name.clone(),
+                    partitions,
+                });
+            }
+
+            Ok(KafkaResponse::new(
+                request.header.correlation_id,
+                request.header.api_version,
+                0,
+                ResponsePayload::ListOffsets(ListOffsetsResponse {
+                    throttle_time_ms: 0,
+                    topics,
+                }),
+            ))
+        } else {
+            unreachable!()
+        }
+    }
+
+    /// 특수 timestamp (latest / earliest / max timestamp) 와 일반 timestamp 를 offset 으로 바꿈
+    /// 해당하는 레코드가 없으면 에러 없이 timestamp / offset 모두 -1
+    async fn list_partition_offset(&self, topic_name: &str, partition: &ListOffsetsPartition, api_version: i16, isolation_level: i8, leader_epoch: i32) -> Result<ListOffsetsPartitionResponse> {
+        let index = partition.partition_index;
+        let found = match partition.timestamp {
+            LATEST_TIMESTAMP => {
+                let offsets = self.message_store.get_partition_offsets(topic_name, index).await?;
+                // READ_COMMITTED 컨슈머는 LSO 까지만 볼 수 있음
+                let offset = if isolation_level == 1 { offsets.last_stable_offset } else { offsets.high_watermark };
+                Some(TimestampOffset { timestamp: -1, offset })
+            },
+            EARLIEST_TIMESTAMP | EARLIEST_LOCAL_TIMESTAMP if partition.timestamp == EARLIEST_TIMESTAMP || api_version >= 8 => {
+                let offsets = self.message_store.get_partition_offsets(topic_name, index).await?;
+                Some(TimestampOffset { timestamp: -1, offset: offsets.log_start_offset })
+            },
+            MAX_TIMESTAMP if api_version >= 7 => self.message_store.find_max_timestamp_offset(topic_name, index).await?,
+            timestamp if timestamp >= 0 => self.message_store.find_offset_by_timestamp(topic_name, index, timestamp).await?,
+            timestamp => {
+                println!("[DEBUG] Unsupported ListOffsets timestamp {} for v{}", timestamp, api_version);
+                return Ok(ListOffsetsPartitionResponse::error(index, i16::from(ErrorCode::UnsupportedVersion)));
+            },
+        };
+
+        Ok(match found {
+            Some(found) => ListOffsetsPartitionResponse {
+                partition_index: index,
+                error_code: i16::from(ErrorCode::None),
+                timestamp: found.timestamp,
+                offset: found.offset,
+                leader_epoch,
+            },
+            None => ListOffsetsPartitionResponse {
+                error_code: i16::from(ErrorCode::None),
+                ..ListOffsetsPartitionResponse::error(index, 0)
+            },
+        })
+    }
+
+    async fn handle_metadata_request(&self, request: &KafkaRequest, metadata_request: &RequestPayload) -> Result<KafkaResponse> {
+        if let RequestPayload::Metadata(metadata_request) = metadata_request {
+            let api_version = request.header.api_version;
+            let topics = match &metadata_request.topics {
+                None => self.metadata_store.get_all_topic_metadata().await?
+                    .into_iter()
+                    .map(|metadata| self.create_metadata_topic_response(metadata, metadata_request))
+                    .collect(),
+                Some(requested) => self.resolve_metadata_topics(requested, metadata_request, api_version).await?,
+            };
+
+            Ok(KafkaResponse::new(
+                request.header.correlation_id,
+                api_version,
+                0,
+                ResponsePayload::Metadata(MetadataResponse {
+                    throttle_time_ms: 0,
+                    brokers: vec![MetadataResponseBroker {
+                        node_id: self.node.node_id,
+                        host: self.node.host.clone(),
+                        port: self.node.port,
+                        rack: self.node.rack.clone(),
+                    }],
+                    cluster_id: self.metadata_store.get_cluster_id().await?,
+                    // combined 모드의 단일 노드이므로 컨트롤러도 자기 자신
+                    controller_id: self.node.node_id,
+                    topics,
+                    cluster_authorized_operations: i32::MIN,
+                }),
+            ))
+        } else {
+            unreachable!()
+        }
+    }
+
+    /// 요청 순서대로 이름 또는 ID 로 토픽을 찾고, 없는 토픽은 허용된 경우 자동 생성
+    async fn resolve_metadata_topics(
+        &self,
+        requested: &[MetadataRequestTopic],
+        metadata_request: &MetadataRequest,
+        api_version: i16,
+    ) -> Result<Vec<MetadataResponseTopic>> {
+        let names: Vec<String> = requested.iter().filter_map(|t| t.name.clone()).collect();
+        let ids: Vec<String> = requested.iter()
+            .filter(|t| t.name.is_none())
+            .map(|t| Self::convert_topic_id_to_uuid(&t.topic_id))
+            .collect();
+
+        let by_name = if names.is_empty() {
+            Vec::new()
+        } else {
+            self.metadata_store.get_topic_metadata_by_names(names).await?.unwrap_or_default()
+        };
+        let by_id = if ids.is_empty() {
+            Vec::new()
+        } else {
+            self.metadata_store.get_topic_metadata_by_ids(ids).await?.unwrap_or_default()
+        };
+
+        let mut topics = Vec::with_capacity(requested.len());
+        for topic in requested {
+            let response = match &topic.name {
+                Some(name) => {
+                    let found = by_name.iter()
+                        .find(|m| &m.name == name && m.error_code == i16::from(ErrorCode::None));
+                    match found {
+                        Some(metadata) => self.create_metadata_topic_response(metadata.clone(), metadata_request),
+                        None if !Self::is_valid_topic_name(name) => {
+                            Self::metadata_topic_error(Some(name.clone()), [0; 16], ErrorCode::InvalidTopicException)
+                        }
+                        None if metadata_request.allow_auto_topic_creation => {
+                            let created = self.metadata_store.create_topic(
+                                name,
+                                self.num_partitions,
+                                vec![self.node.node_id as u32],
+                            ).await?;
+                            // 지워졌던 이름이면 저장소의 삭제 표시도 여기서 풀림
+                            let configs = self.metadata_store.get_topic_configs(name).await?;
+                            self.create_partition_logs(&created, &configs).await?;
+                            self.create_metadata_topic_response(created, metadata_request)
+                        }
+                        None => Self::metadata_topic_error(Some(name.clone()), [0; 16], ErrorCode::UnknownTopicOrPartition),
+                    }
+                }
+                None => {
+                    let topic_id = Self::convert_topic_id_to_uuid(&topic.topic_id);
+                    let found = by_id.iter()
+                        .find(|m| m.topic_id == topic_id && m.error_code == i16::from(ErrorCode::None));
+                    match found {
+                        Some(metadata) => self.create_metadata_topic_response(metadata.clone(), metadata_request),
+                        // v12 부터 응답의 topic name 이 nullable
+                        None => Self::metadata_topic_error(
+                            if api_version >= 12 { None } else { Some(String::new()) },
+                            topic.topic_id,
+                            ErrorCode::UnknownTopicId,
+                        ),
+                    }
+                }
+            };
+            topics.push(response);
+        }
+
+        Ok(topics)
+    }
+
+    fn metadata_topic_error(name: Option<String>, topic_id: [u8; 16], error_code: ErrorCode) -> MetadataResponseTopic {
+        MetadataResponseTopic {
+            error_code: i16::from(error_code),
+            name,
             topic_id,
-            error_code: metadata.error_code,
             is_internal: false,
+            partitions: vec![],
+            topic_authorized_operations: i32::MIN,
+        }
+    }
+
+    fn create_metadata_topic_response(&self, metadata: TopicMetadata, metadata_request: &MetadataRequest) -> MetadataResponseTopic {
+        MetadataResponseTopic {
+            error_code: metadata.error_code,
+            topic_id: Self::topic_id_to_bytes(&metadata.topic_id),
+            name: Some(metadata.name),
+            is_internal: metadata.is_internal,
             partitions: metadata.partitions.iter()
-                .map(|p| PartitionInfo {
-                    partition_id: p.partition_index as i32,
+                .map(|p| MetadataResponsePartition {
                     error_code: p.error_code,
+                    partition_index: p.partition_index as i32,
+                    leader_id: p.leader_id as i32,
+                    leader_epoch: p.leader_epoch as i32,
+                    replica_nodes: p.replicas.iter().map(|r| *r as i32).collect(),
+                    isr_nodes: p.in_sync_replicas.iter().map(|r| *r as i32).collect(),
+                    offline_replicas: p.off_line_replicas.iter().map(|r| *r as i32).collect(),
                 })
                 .collect(),
+            topic_authorized_operations: if metadata_request.include_topic_authorized_operations {
+                metadata.topic_authorized_operations
+            } else {
+                i32::MIN
+            },
         }
     }
-}
 
-#[async_trait]
-impl MessageHandler for KafkaBroker {
-    async fn handle_request(&self, request: KafkaRequest) -> Result<KafkaResponse> {
-        if !request.header.is_supported_version() {
-            return Ok(KafkaResponse::new(
+    async fn handle_create_topics_request(&self, request: &KafkaRequest, create_topics_request: &RequestPayload) -> Result<KafkaResponse> {
+        if let RequestPayload::CreateTopics(create_topics_request) = create_topics_request {
+            let names: Vec<String> = create_topics_request.topics.iter().map(|topic| topic.name.clone()).collect();
+            let existing = self.metadata_store.get_topic_metadata_by_names(names.clone()).await?.unwrap_or_default();
+
+            let mut topics = Vec::with_capacity(create_topics_request.topics.len());
+            for topic in &create_topics_request.topics {
+                let validated = if names.iter().filter(|name| **name == topic.name).count() > 1 {
+                    Err((ErrorCode::InvalidRequest, format!("Create topics request contains multiple entries for the topic {}", topic.name)))
+                } else if existing.iter().any(|m| m.name == topic.name && m.error_code == i16::from(ErrorCode::None)) {
+                    Err((ErrorCode::TopicAlreadyExists, format!("Topic '{}' already exists.", topic.name)))
+                } else {
+                    self.validate_new_topic(topic)
+                };
+
+                let result = match validated {
+                    Err((error, message)) => {
+                        println!("[DEBUG] CreateTopics {} rejected: {}", topic.name, message);
+                        CreatableTopicResult::error(topic.name.clone(), i16::from(error), Some(message))
+                    },
+                    Ok(new_topic) if create_topics_request.validate_only => Self::created_topic_result(topic, [0; 16], &new_topic),
+                    Ok(new_topic) => match self.create_topic(topic, &new_topic).await {
+                        Ok(metadata) => Self::created_topic_result(topic, Self::topic_id_to_bytes(&metadata.topic_id), &new_topic),
+                        Err(e) => {
+                            println!("[DEBUG] CreateTopics {} failed: {}", topic.name, e);
+                            CreatableTopicResult::error(topic.name.clone(), i16::from(ErrorCode::from(&e)), Some(e.to_string()))
+                        },
+                    },
+                };
+                topics.push(result);
+            }
+
+            Ok(KafkaResponse::new(
                 request.header.correlation_id,
-                UNSUPPORTED_VERSION,
-                ResponsePayload::ApiVersions(ApiVersionsResponse::default()),
-            ));
+                request.header.api_version,
+                0,
+                ResponsePayload::CreateTopics(CreateTopicsResponse {
+                    throttle_time_ms: 0,
+                    topics,
+                }),
+            ))
+        } else {
+            unreachable!()
         }
+    }
+
+    async fn handle_delete_topics_request(&self, request: &KafkaRequest, delete_topics_request: &RequestPayload) -> Result<KafkaResponse> {
+        if let RequestPayload::DeleteTopics(delete_topics_request) = delete_topics_request {
+            let existing = self.metadata_store.get_all_topic_metadata().await?;
+            let topics = &delete_topics_request.topics;
 
-        match request.header.api_key {
-            API_VERSIONS_KEY => Ok(KafkaResponse::new(
+            let mut responses = Vec::with_capacity(topics.len());
+            for topic in topics {
+                let resolved = if topics.iter().filter(|other| other.name.is_some() && other.name == topic.name).count() > 1 {
+                    Err((ErrorCode::InvalidRequest, "Duplicate topic name.".to_string()))
+                } else if topics.iter().filter(|other| other.topic_id != [0; 16] && other.topic_id == topic.topic_id).count() > 1 {
+                    Err((ErrorCode::InvalidRequest, "Duplicate topic id.".to_string()))
+                } else {
+                    Self::resolve_deletable_topic(topic, &existing)
+                };
+
+                let result = match resolved {
+                    Err((error, message)) => {
+                        println!("[DEBUG] DeleteTopics {:?} rejected: {}", topic, message);
+                        DeletableTopicResult {
+                            name: topic.name.clone(),
+                            topic_id: topic.topic_id,
+                            error_code: i16::from(error),
+                            error_message: Some(message),
+                        }
+                    },
+                    Ok(metadata) => {
+                        let (error_code, error_message) = match self.delete_topic(metadata).await {
+                            Ok(()) => (i16::from(ErrorCode::None), None),
+                            Err(e) => {
+                                println!("[DEBUG] DeleteTopics {} failed: {}", metadata.name, e);
+                                (i16::from(ErrorCode::from(&e)), Some(e.to_string()))
+                            },
+                        };
+                        DeletableTopicResult {
+                            name: Some(metadata.name.clone()),
+                            topic_id: Self::topic_id_to_bytes(&metadata.topic_id),
+                            error_code,
+                            error_message,
+                        }
+                    },
+                };
+                responses.push(result);
+            }
+
+            Ok(KafkaResponse::new(
                 request.header.correlation_id,
+                request.header.api_version,
                 0,
-                ResponsePayload::ApiVersions(ApiVersionsResponse::default()),
-            )),
-            FETCH_KEY => self.handle_fetch_request(&request, &request.payload).await,
-            DESCRIBE_TOPIC_PARTITIONS_KEY => self.handle_describe_topic_partitions(&request, &request.payload).await,
-            _ => Ok(KafkaResponse::new(
+                ResponsePayload::DeleteTopics(DeleteTopicsResponse {
+                    throttle_time_ms: 0,
+                    responses,
+                }),
+            ))
+        } else {
+            unreachable!()
+        }
+    }
+
+    async fn handle_create_partitions_request(&self, request: &KafkaRequest, create_partitions_request: &RequestPayload) -> Result<KafkaResponse> {
+        if let RequestPayload::CreatePartitions(create_partitions_request) = create_partitions_request {
+            let names: Vec<String> = create_partitions_request.topics.iter().map(|topic| topic.name.clone()).collect();
+            let existing = self.metadata_store.get_topic_metadata_by_names(names.clone()).await?.unwrap_or_default();
+
+            let mut results = Vec::with_capacity(create_partitions_request.topics.len());
+            for topic in &create_partitions_request.topics {
+                let validated = if names.iter().filter(|name| **name == topic.name).count() > 1 {
+                    Err((ErrorCode::InvalidRequest, "Duplicate topic name.".to_string()))
+                } else {
+                    match existing.iter().find(|m| m.name == topic.name && m.error_code == i16::from(ErrorCode::None)) {
+                        Some(metadata) => self.validate_new_partitions(topic, metadata),
+                        None => Err((ErrorCode::UnknownTopicOrPartition, format!("Unable to locate the topic {}", topic.name))),
+                    }
+                };
+
+                let (error, error_message) = match validated {
+                    Err((error, message)) => {
+                        println!("[DEBUG] CreatePartitions {} rejected: {}", topic.name, message);
+                        (error, Some(message))
+                    },
+                    Ok(_) if create_partitions_request.validate_only => (ErrorCode::None, None),
+                    Ok(assignments) => match self.create_partitions(topic, assignments).await {
+                        Ok(()) => (ErrorCode::None, None),
+                        Err(e) => {
+                            println!("[DEBUG] CreatePartitions {} failed: {}", topic.name, e);
+                            (ErrorCode::from(&e), Some(e.to_string()))
+                        },
+                    },
+                };
+                results.push(CreatePartitionsTopicResult {
+                    name: topic.name.clone(),
+                    error_code: i16::from(error),
+                    error_message,
+                });
+            }
+
+            Ok(KafkaResponse::new(
                 request.header.correlation_id,
+                request.header.api_version,
                 0,
-                ResponsePayload::ApiVersions(ApiVersionsResponse::new(vec![])),
+                ResponsePayload::CreatePartitions(CreatePartitionsResponse {
+                    throttle_time_ms: 0,
+                    results,
+                }),
             ))
+        } else {
+            unreachable!()
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::adapters::incoming::protocol::messages::{DescribeTopicPartitionsRequest, KafkaMessage, RequestHeader};
-    use crate::domain::message::TopicMetadata;
-    use async_trait::async_trait;
-
-    struct MockMessageStore;
-    #[async_trait]
-    impl MessageStore for MockMessageStore {
-        async fn store_message(&self, message: KafkaMessage) -> Result<()> {
-            Ok(())
+    /// 늘릴 파티션 수와 수동 배치를 검사하고 새 파티션마다 replica 목록을 돌려줌
+    /// 새 파티션의 replica 수는 기존 파티션과 같아야 함
+    fn validate_new_partitions(&self, topic: &CreatePartitionsTopic, metadata: &TopicMetadata) -> std::result::Result<Vec<Vec<u32>>, (ErrorCode, String)> {
+        let current = metadata.partitions.len() as i32;
+        if topic.count < current {
+            return Err((ErrorCode::InvalidPartitions, format!(
+                "The topic {} currently has {} partition(s); {} would not be an increase.",
+                topic.name, current, topic.count
+            )));
+        }
+        if topic.count == current {
+            return Err((ErrorCode::InvalidPartitions, format!("Topic already has {} partition(s).", current)));
+        }
+        if topic.count > MAX_PARTITIONS_PER_TOPIC {
+            return Err(Self::too_many_partitions(topic.count));
         }
 
-        async fn read_messages(&self, _topic_id: &str, _partition: i32, _offset: i64) -> Result<Option<Vec<u8>>> {
-            Ok(None)
+        let added = (topic.count - current) as usize;
+        let replication_factor = metadata.partitions.first()
+            .map(|partition| partition.replicas.len())
+            .unwrap_or(DEFAULT_REPLICATION_FACTOR as usize);
+        match &topic.assignments {
+            None if replication_factor > 1 => Err((ErrorCode::InvalidReplicationFactor, format!(
+                "Unable to replicate the partition {} time(s): The target replication factor of {} cannot be reached because only 1 broker(s) are registered.",
+                replication_factor, replication_factor
+            ))),
+            None => Ok(vec![vec![self.node.node_id as u32]; added]),
+            Some(assignments) => {
+                if assignments.len() != added {
+                    return Err((ErrorCode::InvalidReplicaAssignment, format!(
+                        "Attempted to add {} additional partition(s), but only {} assignment(s) were specified.",
+                        added, assignments.len()
+                    )));
+                }
+                assignments.iter()
+                    .map(|broker_ids| {
+                        let replicas = self.validate_replicas(broker_ids)?;
+                        if replicas.len() != replication_factor {
+                            return Err((ErrorCode::InvalidReplicaAssignment, format!(
+                                "The manual partition assignment includes a partition with {} replica(s), but this is not consistent with previous partitions, which have {} replica(s).",
+                                replicas.len(), replication_factor
+                            )));
+                        }
+                        Ok(replicas)
+                    })
+                    .collect()
+            },
         }
     }
 
-    struct MockMetadataStore {
-        topics: Vec<TopicMetadata>,
+    /// 메타데이터 로그에 새 파티션들을 기록한 뒤 파티션 디렉토리를 만듦
+    async fn create_partitions(&self, topic: &CreatePartitionsTopic, assignments: Vec<Vec<u32>>) -> Result<()> {
+        let added = assignments.len() as i32;
+        let metadata = self.metadata_store.create_partitions(&topic.name, assignments).await?;
+        let count = metadata.partitions.len() as i32;
+        self.message_store.create_partitions(&topic.name, count - added..count).await
     }
 
-    impl MockMetadataStore {
-        fn new(topics: Vec<TopicMetadata>) -> Self {
-            Self { topics }
+    /// 이름 또는 토픽 ID 로 지울 토픽을 찾음. 둘 다 주거나 둘 다 없으면 INVALID_REQUEST
+    fn resolve_deletable_topic<'a>(topic: &DeleteTopicState, existing: &'a [TopicMetadata]) -> std::result::Result<&'a TopicMetadata, (ErrorCode, String)> {
+        match (&topic.name, topic.topic_id != [0; 16]) {
+            (Some(_), true) => Err((ErrorCode::InvalidRequest, "You may not specify both topic name and topic id.".to_string())),
+            (None, false) => Err((ErrorCode::InvalidRequest, "Neither topic name nor topic id was specified.".to_string())),
+            (Some(name), false) => existing.iter()
+                .find(|metadata| metadata.name == *name)
+                .ok_or((ErrorCode::UnknownTopicOrPartition, ErrorCode::UnknownTopicOrPartition.message().to_string())),
+            (None, true) => {
+                let topic_id = Self::convert_topic_id_to_uuid(&topic.topic_id);
+                existing.iter()
+                    .find(|metadata| metadata.topic_id == topic_id)
+                    .ok_or((ErrorCode::UnknownTopicId, ErrorCode::UnknownTopicId.message().to_string()))
+            },
         }
     }
 
-    #[async_trait]
-    impl MetadataStore for MockMetadataStore {
-        async fn get_topic_metadata_by_names(&self, topic_names: Vec<String>) -> Result<Option<Vec<TopicMetadata>>> {
-            let mut result = Vec::new();
-            for topic_name in topic_names {
-                if let Some(topic) = self.topics.iter().find(|t| t.name == topic_name) {
-                    result.push(topic.clone());
+    /// 메타데이터 로그에 삭제를 기록한 뒤 파티션 로그를 지움
+    async fn delete_topic(&self, metadata: &TopicMetadata) -> Result<()> {
+        self.metadata_store.delete_topic(&metadata.topic_id).await?;
+        self.message_store.delete_topic(&metadata.name).await
+    }
+
+    /// 파티션 수 / 복제 계수 / 수동 배치 / 토픽 설정을 검사하고 파티션별 replica 목록과 설정을 돌려줌
+    /// 브로커는 이 노드 하나뿐이므로 replica 는 이 노드만 될 수 있음
+    fn validate_new_topic(&self, topic: &CreatableTopic) -> std::result::Result<NewTopic, (ErrorCode, String)> {
+        if !Self::is_valid_topic_name(&topic.name) {
+            return Err((ErrorCode::InvalidTopicException, format!("Topic name '{}' is illegal", topic.name)));
+        }
+
+        let node_id = self.node.node_id;
+        let assignments = if topic.assignments.is_empty() {
+            let num_partitions = match topic.num_partitions {
+                -1 => self.num_partitions as i32,
+                n if n > MAX_PARTITIONS_PER_TOPIC => return Err(Self::too_many_partitions(n)),
+                n if n > 0 => n,
+                _ => return Err((ErrorCode::InvalidPartitions, "Number of partitions was set to an invalid non-positive value.".to_string())),
+            };
+            let replication_factor = match topic.replication_factor {
+                -1 => DEFAULT_REPLICATION_FACTOR,
+                n if n > 0 => n,
+                _ => return Err((ErrorCode::InvalidReplicationFactor, "Replication factor must be larger than 0, or -1 to use the default value.".to_string())),
+            };
+            if replication_factor > 1 {
+                return Err((ErrorCode::InvalidReplicationFactor, format!(
+                    "Unable to replicate the partition {} time(s): The target replication factor of {} cannot be reached because only 1 broker(s) are registered.",
+                    replication_factor, replication_factor
+                )));
+            }
+            vec![vec![node_id as u32]; num_partitions as usize]
+        } else {
+            if topic.num_partitions != -1 {
+                return Err((ErrorCode::InvalidRequest, "A manual partition assignment was specified, but numPartitions was not set to -1.".to_string()));
+            }
+            if topic.replication_factor != -1 {
+                return Err((ErrorCode::InvalidRequest, "A manual partition assignment was specified, but replicationFactor was not set to -1.".to_string()));
+            }
+            if topic.assignments.len() > MAX_PARTITIONS_PER_TOPIC as usize {
+                return Err(Self::too_many_partitions(topic.assignments.len() as i32));
+            }
+            let mut assignments = topic.assignments.clone();
+            assignments.sort_by_key(|assignment| assignment.partition_index);
+            let mut replicas = Vec::with_capacity(assignments.len());
+            for (partition_index, assignment) in assignments.iter().enumerate() {
+                if assignment.partition_index != partition_index as i32 {
+                    return Err((ErrorCode::InvalidReplicaAssignment, format!("Partitions should be numbered 0..{}", assignments.len() - 1)));
                 }
+                let partition_replicas = self.validate_replicas(&assignment.broker_ids)?;
+                if assignment.broker_ids.len() != assignments[0].broker_ids.len() {
+                    return Err((ErrorCode::InvalidReplicaAssignment, "All partitions should have the same number of replicas.".to_string()));
+                }
+                replicas.push(partition_replicas);
             }
-            if result.is_empty() {
-                Ok(None)
-            } else {
-                Ok(Some(result))
+            replicas
+        };
+
+        let mut configs = Vec::with_capacity(topic.configs.len());
+        for config in &topic.configs {
+            match &config.value {
+                Some(value) => configs.push((config.name.clone(), value.clone())),
+                None => return Err((ErrorCode::InvalidConfig, format!("Null value not supported for topic configs: {}", config.name))),
             }
         }
+        LogConfig::default().with_topic_configs(&configs)
+            .map_err(|e| (ErrorCode::InvalidConfig, e.to_string()))?;
 
-        async fn get_topic_metadata_by_ids(&self, topic_ids: Vec<String>) -> Result<Option<Vec<TopicMetadata>>> {
-            let mut result = Vec::new();
-            for topic_id in topic_ids {
-                if let Some(topic) = self.topics.iter().find(|t| t.topic_id == topic_id) {
-                    result.push(topic.clone());
-                }
+        Ok(NewTopic { assignments, configs })
+    }
+
+    /// 파티션 수가 MAX_PARTITIONS_PER_TOPIC 을 넘는 CreateTopics / CreatePartitions 에 대한 INVALID_PARTITIONS
+    fn too_many_partitions(num_partitions: i32) -> (ErrorCode, String) {
+        (ErrorCode::InvalidPartitions, format!(
+            "Number of partitions {} exceeds the maximum of {} per topic.",
+            num_partitions, MAX_PARTITIONS_PER_TOPIC
+        ))
+    }
+
+    /// 수동으로 지정한 replica 목록을 검사. 비었거나, 같은 브로커가 두 번 나오거나, 없는 브로커면 INVALID_REPLICA_ASSIGNMENT
+    fn validate_replicas(&self, broker_ids: &[i32]) -> std::result::Result<Vec<u32>, (ErrorCode, String)> {
+        if broker_ids.is_empty() {
+            return Err((ErrorCode::InvalidReplicaAssignment, "The manual partition assignment includes an empty replica list.".to_string()));
+        }
+        for (i, broker_id) in broker_ids.iter().enumerate() {
+            if broker_ids[..i].contains(broker_id) {
+                return Err((ErrorCode::InvalidReplicaAssignment, format!("The manual partition assignment includes the broker {} more than once.", broker_id)));
+            }
+            if *broker_id != self.node.node_id {
+                return Err((ErrorCode::InvalidReplicaAssignment, format!("The manual partition assignment includes broker {}, but no such broker is registered.", broker_id)));
+            }
+        }
+        Ok(broker_ids.iter().map(|broker_id| *broker_id as u32).collect())
+    }
+
+    /// 메타데이터 로그에 토픽을 기록한 뒤 파티션 디렉토리를 만듦
+    async fn create_topic(&self, topic: &CreatableTopic, new_topic: &NewTopic) -> Result<TopicMetadata> {
+        let metadata = self.metadata_store.create_topic_with_assignments(&topic.name, new_topic.assignments.clone(), &new_topic.configs).await?;
+        self.create_partition_logs(&metadata, &new_topic.configs).await?;
+        Ok(metadata)
+    }
+
+    /// 메타데이터에 기록된 토픽의 파티션 디렉토리를 만듦
+    /// 못 만들면 RemoveTopicRecord 로 되돌려서, 다시 시도할 때 TOPIC_ALREADY_EXISTS 가 나지 않게 함
+    async fn create_partition_logs(&self, metadata: &TopicMetadata, configs: &[(String, String)]) -> Result<()> {
+        let Err(e) = self.message_store.create_topic(&metadata.name, metadata.partitions.len() as i32, configs).await else {
+            return Ok(());
+        };
+        println!("[DEBUG] Failed to create partitions of topic {}, rolling back: {:?}", metadata.name, e);
+        if let Err(rollback) = self.metadata_store.delete_topic(&metadata.topic_id).await {
+            println!("[DEBUG] Failed to roll back topic {}: {:?}", metadata.name, rollback);
+        }
+        Err(e)
+    }
+
+    fn created_topic_result(topic: &CreatableTopic, topic_id: [u8; 16], new_topic: &NewTopic) -> CreatableTopicResult {
+        CreatableTopicResult {
+            name: topic.name.clone(),
+            topic_id,
+            error_code: i16::from(ErrorCode::None),
+            error_message: None,
+            num_partitions: new_topic.assignments.len() as i32,
+            replication_factor: new_topic.assignments[0].len() as i16,
+            configs: Some(new_topic.configs.iter()
+                .map(|(name, value)| CreatableTopicConfigs {
+                    name: name.clone(),
+                    value: Some(value.clone()),
+                    read_only: false,
+                    config_source: DYNAMIC_TOPIC_CONFIG_SOURCE,
+                    is_sensitive: false,
+                })
+                .collect()),
+        }
+    }
+
+    fn create_topic_response(&self, metadata: TopicMetadata) -> TopicResponse {
+        let topic_id = Self::topic_id_to_bytes(&metadata.topic_id);
+
+        TopicResponse {
+            topic_name: metadata.name,
+            topic_id,
+            error_code: metadata.error_code,
+            is_internal: false,
+            partitions: metadata.partitions.iter()
+                .map(|p| PartitionInfo {
+                    partition_id: p.partition_index as i32,
+                    error_code: p.error_code,
+                    leader_id: p.leader_id as i32,
+                    leader_epoch: p.leader_epoch as i32,
+                    replica_nodes: p.replicas.iter().map(|r| *r as i32).collect(),
+                    isr_nodes: p.in_sync_replicas.iter().map(|r| *r as i32).collect(),
+                    eligible_leader_replicas: p.eligible_leader_replicas.iter().map(|r| *r as i32).collect(),
+                    last_known_elr: p.last_known_eligible_leader_replicas.iter().map(|r| *r as i32).collect(),
+                    offline_replicas: p.off_line_replicas.iter().map(|r| *r as i32).collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl MessageHandler for KafkaBroker {
+    async fn handle_request(&self, request: KafkaRequest) -> Result<Option<KafkaResponse>> {
+        self.handle_parkable_request(request, ParkSignal::none()).await
+    }
+
+    async fn handle_parkable_request(&self, request: KafkaRequest, mut parked: ParkSignal) -> Result<Option<KafkaResponse>> {
+        if !request.header.is_supported_version() {
+            println!("[DEBUG] Unsupported version {} for API Key {}", request.header.api_version, request.header.api_key);
+            return Self::error_response(&request, ErrorCode::UnsupportedVersion).map(Some);
+        }
+
+        let result = match request.header.api_key {
+            API_VERSIONS_KEY => Ok(Some(KafkaResponse::new(
+                request.header.correlation_id,
+                request.header.api_version,
+                0,
+                ResponsePayload::ApiVersions(ApiVersionsResponse::default()),
+            ))),
+            FETCH_KEY => self.handle_fetch_request(&request, &request.payload, &mut parked).await.map(Some),
+            DESCRIBE_TOPIC_PARTITIONS_KEY => self.handle_describe_topic_partitions(&request, &request.payload).await.map(Some),
+            PRODUCE_KEY => self.handle_produce_request(&request, &request.payload).await,
+            METADATA_KEY => self.handle_metadata_request(&request, &request.payload).await.map(Some),
+            LIST_OFFSETS_KEY => self.handle_list_offsets_request(&request, &request.payload).await.map(Some),
+            CREATE_TOPICS_KEY => self.handle_create_topics_request(&request, &request.payload).await.map(Some),
+            DELETE_TOPICS_KEY => self.handle_delete_topics_request(&request, &request.payload).await.map(Some),
+            CREATE_PARTITIONS_KEY => self.handle_create_partitions_request(&request, &request.payload).await.map(Some),
+            // 응답 스키마를 모르는 요청에는 답할 수 없으므로 커넥션을 닫게 함
+            api_key => return Err(ApplicationError::Protocol(format!("Unknown API key: {}", api_key))),
+        };
+
+        // 처리 중 실패해도 커넥션을 끊지 않고 에러 코드로 응답
+        // acks=0 프로듀서는 응답을 읽지 않으므로 Kafka 처럼 커넥션을 닫아 실패를 알림
+        match result {
+            Err(e) if !matches!(&request.payload, RequestPayload::Produce(produce) if produce.acks == 0) => {
+                println!("[DEBUG] Request for API Key {} failed: {}", request.header.api_key, e);
+                Self::error_response(&request, ErrorCode::from(&e)).map(Some)
+            },
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::incoming::protocol::constants::UNSUPPORTED_VERSION;
+    use crate::adapters::incoming::protocol::messages::{
+        CreatableReplicaAssignment, CreatableTopicConfig, CreatePartitionsRequest, CreateTopicsRequest, DeleteTopicsRequest,
+        DescribeTopicPartitionsRequest, FetchRequest, ListOffsetsRequest, ListOffsetsTopic, MetadataRequestTopic,
+        ProduceRequest, ProduceTopic,
+        RequestHeader, TopicRequest,
+    };
+    use crate::domain::message::{LogAppendInfo, Partition, PartitionOffsets};
+    use async_trait::async_trait;
+
+    struct MockMessageStore;
+    #[async_trait]
+    impl MessageStore for MockMessageStore {
+        async fn create_topic(&self, _topic_name: &str, _num_partitions: i32, _configs: &[(String, String)]) -> Result<()> {
+            Ok(())
+        }
+
+        async fn create_partitions(&self, _topic_name: &str, _partitions: std::ops::Range<i32>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn delete_topic(&self, _topic_name: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn store_message(&self, _topic_name: &str, _partition: i32, _records: &[u8]) -> Result<LogAppendInfo> {
+            Ok(LogAppendInfo { base_offset: 42, log_append_time: -1 })
+        }
+
+        async fn read_messages(&self, _topic_id: &str, _partition: i32, _offset: i64, _max_bytes: i32) -> Result<Option<Vec<u8>>> {
+            Ok(None)
+        }
+
+        async fn get_partition_offsets(&self, _topic_name: &str, _partition: i32) -> Result<PartitionOffsets> {
+            Ok(PartitionOffsets::new(0, 42))
+        }
+
+        async fn find_offset_by_timestamp(&self, _topic_name: &str, _partition: i32, _timestamp: i64) -> Result<Option<TimestampOffset>> {
+            Ok(None)
+        }
+
+        async fn find_max_timestamp_offset(&self, _topic_name: &str, _partition: i32) -> Result<Option<TimestampOffset>> {
+            Ok(None)
+        }
+    }
+
+    /// 디스크가 망가진 저장소 흉내. 모든 호출이 IO 에러
+    struct FailingMessageStore;
+    #[async_trait]
+    impl MessageStore for FailingMessageStore {
+        async fn create_topic(&self, _topic_name: &str, _num_partitions: i32, _configs: &[(String, String)]) -> Result<()> {
+            Err(std::io::Error::other("disk failure").into())
+        }
+
+        async fn create_partitions(&self, _topic_name: &str, _partitions: std::ops::Range<i32>) -> Result<()> {
+            Err(std::io::Error::other("disk failure").into())
+        }
+
+        async fn delete_topic(&self, _topic_name: &str) -> Result<()> {
+            Err(std::io::Error::other("disk failure").into())
+        }
+
+        async fn store_message(&self, _topic_name: &str, _partition: i32, _records: &[u8]) -> Result<LogAppendInfo> {
+            Err(std::io::Error::other("disk failure").into())
+        }
+
+        async fn read_messages(&self, _topic_id: &str, _partition: i32, _offset: i64, _max_bytes: i32) -> Result<Option<Vec<u8>>> {
+            Err(std::io::Error::other("disk failure").into())
+        }
+
+        async fn get_partition_offsets(&self, _topic_name: &str, _partition: i32) -> Result<PartitionOffsets> {
+            Err(std::io::Error::other("disk failure").into())
+        }
+
+        async fn find_offset_by_timestamp(&self, _topic_name: &str, _partition: i32, _timestamp: i64) -> Result<Option<TimestampOffset>> {
+            Err(std::io::Error::other("disk failure").into())
+        }
+
+        async fn find_max_timestamp_offset(&self, _topic_name: &str, _partition: i32) -> Result<Option<TimestampOffset>> {
+            Err(std::io::Error::other("disk failure").into())
+        }
+    }
+
+    /// 100 바이트짜리 배치들이 있는 로그 흉내. 처음엔 offset 0..3, append 마다 한 배치씩 늘어남
+    struct MockLogStore {
+        end_offset: std::sync::atomic::AtomicI64,
+    }
+
+    impl MockLogStore {
+        fn new() -> Self {
+            Self { end_offset: std::sync::atomic::AtomicI64::new(3) }
+        }
+    }
+
+    #[async_trait]
+    impl MessageStore for MockLogStore {
+        async fn create_topic(&self, _topic_name: &str, _num_partitions: i32, _configs: &[(String, String)]) -> Result<()> {
+            Ok(())
+        }
+
+        async fn create_partitions(&self, _topic_name: &str, _partitions: std::ops::Range<i32>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn delete_topic(&self, _topic_name: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn store_message(&self, _topic_name: &str, _partition: i32, _records: &[u8]) -> Result<LogAppendInfo> {
+            let base_offset = self.end_offset.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(LogAppendInfo { base_offset, log_append_time: -1 })
+        }
+
+        async fn read_messages(&self, _topic_id: &str, _partition: i32, offset: i64, max_bytes: i32) -> Result<Option<Vec<u8>>> {
+            let end_offset = self.end_offset.load(std::sync::atomic::Ordering::SeqCst);
+            if offset < 0 || offset > end_offset {
+                return Err(ApplicationError::Domain(DomainError::OffsetOutOfRange(offset)));
+            }
+            if offset == end_offset {
+                return Ok(None);
+            }
+            let batches = (end_offset - offset).min(max_bytes as i64 / 100).max(1);
+            Ok(Some(vec![0; batches as usize * 100]))
+        }
+
+        async fn get_partition_offsets(&self, _topic_name: &str, _partition: i32) -> Result<PartitionOffsets> {
+            Ok(PartitionOffsets::new(0, self.end_offset.load(std::sync::atomic::Ordering::SeqCst)))
+        }
+
+        /// offset n 레코드의 timestamp 는 n * 1000
+        async fn find_offset_by_timestamp(&self, _topic_name: &str, _partition: i32, timestamp: i64) -> Result<Option<TimestampOffset>> {
+            let end_offset = self.end_offset.load(std::sync::atomic::Ordering::SeqCst);
+            let offset = (timestamp + 999) / 1000;
+            Ok((offset < end_offset).then_some(TimestampOffset { timestamp: offset * 1000, offset }))
+        }
+
+        async fn find_max_timestamp_offset(&self, _topic_name: &str, _partition: i32) -> Result<Option<TimestampOffset>> {
+            let offset = self.end_offset.load(std::sync::atomic::Ordering::SeqCst) - 1;
+            Ok(Some(TimestampOffset { timestamp: offset * 1000, offset }))
+        }
+    }
+
+    struct MockMetadataStore {
+        topics: std::sync::Mutex<Vec<TopicMetadata>>,
+    }
+
+    impl MockMetadataStore {
+        fn new(topics: Vec<TopicMetadata>) -> Self {
+            Self { topics: std::sync::Mutex::new(topics) }
+        }
+    }
+
+    #[async_trait]
+    impl MetadataStore for MockMetadataStore {
+        async fn get_topic_metadata_by_names(&self, topic_names: Vec<String>) -> Result<Option<Vec<TopicMetadata>>> {
+            let mut result = Vec::new();
+            for topic_name in topic_names {
+                if let Some(topic) = self.topics.lock().unwrap().iter().find(|t| t.name == topic_name) {
+                    result.push(topic.clone());
+                }
+            }
+            if result.is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some(result))
+            }
+        }
+
+        async fn get_topic_metadata_by_ids(&self, topic_ids: Vec<String>) -> Result<Option<Vec<TopicMetadata>>> {
+            let mut result = Vec::new();
+            for topic_id in topic_ids {
+                if let Some(topic) = self.topics.lock().unwrap().iter().find(|t| t.topic_id == topic_id) {
+                    result.push(topic.clone());
+                }
             }
             if result.is_empty() {
                 Ok(None)
@@ -246,6 +1458,82 @@ mod tests {
                 Ok(Some(result))
             }
         }
+
+        async fn get_all_topic_metadata(&self) -> Result<Vec<TopicMetadata>> {
+            Ok(self.topics.lock().unwrap().clone())
+        }
+
+        async fn get_topic_configs(&self, _topic_name: &str) -> Result<Vec<(String, String)>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_cluster_id(&self) -> Result<Option<String>> {
+            Ok(Some("test-cluster".to_string()))
+        }
+
+        async fn create_topic(&self, topic_name: &str, num_partitions: u32, replicas: Vec<u32>) -> Result<TopicMetadata> {
+            let metadata = TopicMetadata {
+                error_code: i16::from(ErrorCode::None),
+                name: topic_name.to_string(),
+                topic_id: "00000000-0000-0000-0000-0000000000ff".to_string(),
+                is_internal: false,
+                partitions: (0..num_partitions)
+                    .map(|i| Partition::new(0, i, replicas[0], 0, replicas.clone(), replicas.clone(), vec![], vec![], vec![]))
+                    .collect(),
+                topic_authorized_operations: 0x0DF,
+            };
+            self.topics.lock().unwrap().push(metadata.clone());
+            Ok(metadata)
+        }
+
+        async fn create_topic_with_assignments(&self, topic_name: &str, assignments: Vec<Vec<u32>>, _configs: &[(String, String)]) -> Result<TopicMetadata> {
+            if self.topics.lock().unwrap().iter().any(|t| t.name == topic_name) {
+                return Err(ApplicationError::Domain(DomainError::TopicAlreadyExists(topic_name.to_string())));
+            }
+            let metadata = TopicMetadata {
+                error_code: i16::from(ErrorCode::None),
+                name: topic_name.to_string(),
+                topic_id: "00000000-0000-0000-0000-0000000000fe".to_string(),
+                is_internal: false,
+                partitions: assignments.iter().enumerate()
+                    .map(|(i, replicas)| Partition::new(0, i as u32, replicas[0], 0, replicas.clone(), replicas.clone(), vec![], vec![], vec![]))
+                    .collect(),
+                topic_authorized_operations: 0x0DF,
+            };
+            self.topics.lock().unwrap().push(metadata.clone());
+            Ok(metadata)
+        }
+
+        async fn create_partitions(&self, topic_name: &str, assignments: Vec<Vec<u32>>) -> Result<TopicMetadata> {
+            let mut topics = self.topics.lock().unwrap();
+            let Some(topic) = topics.iter_mut().find(|t| t.name == topic_name) else {
+                return Err(ApplicationError::Domain(DomainError::UnknownTopicOrPartition(topic_name.to_string())));
+            };
+            for replicas in assignments {
+                let i = topic.partitions.len() as u32;
+                topic.partitions.push(Partition::new(0, i, replicas[0], 0, replicas.clone(), replicas, vec![], vec![], vec![]));
+            }
+            Ok(topic.clone())
+        }
+
+        async fn delete_topic(&self, topic_id: &str) -> Result<()> {
+            let mut topics = self.topics.lock().unwrap();
+            let before = topics.len();
+            topics.retain(|t| t.topic_id != topic_id);
+            if topics.len() == before {
+                return Err(ApplicationError::Domain(DomainError::UnknownTopicId(topic_id.to_string())));
+            }
+            Ok(())
+        }
+    }
+
+    fn test_node() -> BrokerMetadata {
+        BrokerMetadata {
+            node_id: 1,
+            host: "localhost".to_string(),
+            port: 9092,
+            rack: None,
+        }
     }
 
     #[tokio::test]
@@ -263,6 +1551,7 @@ mod tests {
         let broker = KafkaBroker::new(
             Box::new(MockMessageStore),
             Box::new(MockMetadataStore::new(vec![topic_metadata])),
+            test_node(),
         );
 
         let request = KafkaRequest::new(
@@ -282,7 +1571,7 @@ mod tests {
             ),
         );
 
-        let response = broker.handle_request(request).await?;
+        let response = broker.handle_request(request).await?.unwrap();
         assert_eq!(response.correlation_id, 123);
         assert_eq!(response.error_code, 0);
 
@@ -306,6 +1595,7 @@ mod tests {
         let broker = KafkaBroker::new(
             Box::new(MockMessageStore),
             Box::new(MockMetadataStore::new(vec![])),
+            test_node(),
         );
 
         let request = KafkaRequest::new(
@@ -325,7 +1615,7 @@ mod tests {
             ),
         );
 
-        let response = broker.handle_request(request).await?;
+        let response = broker.handle_request(request).await?.unwrap();
         assert_eq!(response.correlation_id, 123);
         assert_eq!(response.error_code, 0);
 
@@ -342,4 +1632,1286 @@ mod tests {
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_unsupported_version_responses() -> Result<()> {
+        let broker = produce_broker();
+
+        // ApiVersions 는 v0 으로 지원 버전 목록과 함께 응답
+        let request = KafkaRequest::new(
+            RequestHeader { api_key: API_VERSIONS_KEY, api_version: 5, correlation_id: 1, client_id: None },
+            RequestPayload::ApiVersions,
+        );
+        let response = broker.handle_request(request).await?.unwrap();
+        assert_eq!(response.api_version, 0);
+        assert_eq!(response.error_code, UNSUPPORTED_VERSION);
+        assert!(matches!(response.payload, ResponsePayload::ApiVersions(ref r) if !r.api_versions.is_empty()));
+
+        // 나머지는 해당 API 의 응답으로, 요청한 파티션마다 에러
+        let mut request = produce_request(1);
+        request.header.api_version = 12;
+        let response = broker.handle_request(request).await?.unwrap();
+        assert_eq!(response.api_version, 12);
+        match response.payload {
+            ResponsePayload::Produce(resp) => {
+                assert_eq!(resp.responses[0].name, "test-topic");
+                assert_eq!(resp.responses[0].partitions.len(), 2);
+                assert!(resp.responses[0].partitions.iter().all(|p| p.error_code == UNSUPPORTED_VERSION));
+            }
+            _ => panic!("Expected Produce response"),
+        }
+
+        let request = KafkaRequest::new(
+            RequestHeader { api_key: FETCH_KEY, api_version: 17, correlation_id: 2, client_id: None },
+            RequestPayload::Unsupported,
+        );
+        let response = broker.handle_request(request).await?.unwrap();
+        assert_eq!(response.error_code, UNSUPPORTED_VERSION);
+        assert!(matches!(response.payload, ResponsePayload::Fetch(ref r) if r.responses.is_empty()));
+
+        // 모르는 API 에는 응답하지 않고 에러
+        let request = KafkaRequest::new(
+            RequestHeader { api_key: 999, api_version: 0, correlation_id: 3, client_id: None },
+            RequestPayload::Unsupported,
+        );
+        assert!(broker.handle_request(request).await.is_err());
+        Ok(())
+    }
+
+    fn produce_request(acks: i16) -> KafkaRequest {
+        KafkaRequest::new(
+            RequestHeader {
+                api_key: PRODUCE_KEY,
+                api_version: 9,
+                correlation_id: 7,
+                client_id: None,
+            },
+            RequestPayload::Produce(ProduceRequest {
+                transactional_id: None,
+                acks,
+                timeout_ms: 1000,
+                topics: vec![
+                    ProduceTopic {
+                        name: "test-topic".to_string(),
+                        partitions: vec![
+                            ProducePartition { index: 0, records: Some(vec![0; 61]) },
+                            ProducePartition { index: 1, records: Some(vec![0; 61]) },
+                        ],
+                    },
+                    ProduceTopic {
+                        name: "missing-topic".to_string(),
+                        partitions: vec![ProducePartition { index: 0, records: Some(vec![0; 61]) }],
+                    },
+                ],
+            }),
+        )
+    }
+
+    fn test_topic() -> TopicMetadata {
+        TopicMetadata {
+            error_code: i16::from(ErrorCode::None),
+            name: "test-topic".to_string(),
+            topic_id: "00000000-0000-0000-0000-000000000001".to_string(),
+            is_internal: false,
+            partitions: vec![Partition::new(0, 0, 1, 0, vec![1], vec![1], vec![], vec![], vec![])],
+            topic_authorized_operations: 0x0DF,
+        }
+    }
+
+    fn produce_broker() -> KafkaBroker {
+        KafkaBroker::new(
+            Box::new(MockMessageStore),
+            Box::new(MockMetadataStore::new(vec![test_topic()])),
+            test_node(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_handle_produce_request() -> Result<()> {
+        let response = produce_broker().handle_request(produce_request(-1)).await?.unwrap();
+        assert_eq!(response.correlation_id, 7);
+        assert_eq!(response.api_version, 9);
+
+        match response.payload {
+            ResponsePayload::Produce(resp) => {
+                assert_eq!(resp.responses.len(), 2);
+
+                let partitions = &resp.responses[0].partitions;
+                assert_eq!(partitions[0].error_code, 0);
+                assert_eq!(partitions[0].base_offset, 42);
+                assert_eq!(partitions[1].error_code, UNKNOWN_TOPIC_OR_PARTITION);
+                assert_eq!(partitions[1].base_offset, -1);
+
+                assert_eq!(resp.responses[1].name, "missing-topic");
+                assert_eq!(resp.responses[1].partitions[0].error_code, UNKNOWN_TOPIC_OR_PARTITION);
+            }
+            _ => panic!("Expected Produce response"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_produce_returns_log_append_time() -> Result<()> {
+        use crate::adapters::outgoing::disk_store::DiskMessageStore;
+        use crate::adapters::outgoing::kraft_metadata_store::KraftMetadataStore;
+        use crate::adapters::outgoing::protocol::record_batch::{Record, RecordBatch};
+
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let broker = KafkaBroker::new(
+            Box::new(DiskMessageStore::new(dir.path().to_path_buf())),
+            Box::new(KraftMetadataStore::new(dir.path().to_path_buf())),
+            test_node(),
+        );
+        let append_time_topic = CreatableTopic {
+            configs: vec![CreatableTopicConfig { name: "message.timestamp.type".to_string(), value: Some("LogAppendTime".to_string()) }],
+            ..creatable_topic("bar", 1, 1)
+        };
+        broker.handle_request(create_topics_request(7, vec![creatable_topic("foo", 1, 1), append_time_topic], false)).await?;
+
+        let records = RecordBatch::new(0, 0, 1000, vec![Record::new(0, None, Some(b"value".to_vec()))]).to_bytes()?;
+        let mut request = produce_request(-1);
+        let RequestPayload::Produce(produce) = &mut request.payload else { unreachable!() };
+        produce.topics = ["foo", "bar"].iter()
+            .map(|name| ProduceTopic {
+                name: name.to_string(),
+                partitions: vec![ProducePartition { index: 0, records: Some(records.clone()) }],
+            })
+            .collect();
+
+        let before = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis() as i64;
+        let ResponsePayload::Produce(resp) = broker.handle_request(request).await?.unwrap().payload else {
+            panic!("Expected Produce response");
+        };
+        // CreateTime 토픽은 -1, LogAppendTime 토픽은 브로커가 찍은 시각
+        assert_eq!(resp.responses[0].partitions[0].log_append_time_ms, -1);
+        let log_append_time = resp.responses[1].partitions[0].log_append_time_ms;
+        assert!(log_append_time >= before, "{} < {}", log_append_time, before);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_produce_request_without_acks_sends_no_response() -> Result<()> {
+        let response = produce_broker().handle_request(produce_request(0)).await?;
+        assert!(response.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_produce_request_invalid_acks() -> Result<()> {
+        let response = produce_broker().handle_request(produce_request(2)).await?.unwrap();
+
+        match response.payload {
+            ResponsePayload::Produce(resp) => {
+                assert!(resp.responses.iter()
+                    .flat_map(|t| &t.partitions)
+                    .all(|p| p.error_code == i16::from(ErrorCode::InvalidRequiredAcks)));
+            }
+            _ => panic!("Expected Produce response"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_storage_errors_become_partition_errors() -> Result<()> {
+        let broker = KafkaBroker::new(
+            Box::new(FailingMessageStore),
+            Box::new(MockMetadataStore::new(vec![test_topic()])),
+            test_node(),
+        );
+        let storage_error = i16::from(ErrorCode::KafkaStorageError);
+
+        let response = broker.handle_request(produce_request(-1)).await?.unwrap();
+        match response.payload {
+            ResponsePayload::Produce(resp) => {
+                let partitions = &resp.responses[0].partitions;
+                assert_eq!(partitions[0].error_code, storage_error);
+                assert_eq!(partitions[1].error_code, UNKNOWN_TOPIC_OR_PARTITION);
+            }
+            _ => panic!("Expected Produce response"),
+        }
+
+        let response = broker.handle_request(fetch_request(0, 1024, 1024)).await?.unwrap();
+        match response.payload {
+            ResponsePayload::Fetch(resp) => assert_eq!(resp.responses[0].partitions[0].error_code, storage_error),
+            _ => panic!("Expected Fetch response"),
+        }
+
+        let partitions = list_offsets(&broker, list_offsets_request(8, "test-topic", &[LATEST_TIMESTAMP])).await?;
+        assert_eq!(partitions[0].error_code, storage_error);
+
+        // acks=0 는 파티션 에러도 응답하지 않음
+        assert!(broker.handle_request(produce_request(0)).await?.is_none());
+        Ok(())
+    }
+
+    fn metadata_request(api_version: i16, topics: Option<Vec<MetadataRequestTopic>>, allow_auto_topic_creation: bool) -> KafkaRequest {
+        KafkaRequest::new(
+            RequestHeader {
+                api_key: METADATA_KEY,
+                api_version,
+                correlation_id: 11,
+                client_id: None,
+            },
+            RequestPayload::Metadata(MetadataRequest {
+                topics,
+                allow_auto_topic_creation,
+                include_cluster_authorized_operations: false,
+                include_topic_authorized_operations: false,
+            }),
+        )
+    }
+
+    fn named(name: &str) -> MetadataRequestTopic {
+        MetadataRequestTopic {
+            topic_id: [0; 16],
+            name: Some(name.to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_metadata_request_all_topics() -> Result<()> {
+        let response = produce_broker().handle_request(metadata_request(12, None, false)).await?.unwrap();
+
+        match response.payload {
+            ResponsePayload::Metadata(resp) => {
+                assert_eq!(resp.brokers.len(), 1);
+                assert_eq!(resp.brokers[0].node_id, 1);
+                assert_eq!(resp.brokers[0].port, 9092);
+                assert_eq!(resp.cluster_id, Some("test-cluster".to_string()));
+                assert_eq!(resp.controller_id, 1);
+
+                assert_eq!(resp.topics.len(), 1);
+                let topic = &resp.topics[0];
+                assert_eq!(topic.name, Some("test-topic".to_string()));
+                assert_eq!(topic.topic_id[15], 1);
+                assert_eq!(topic.topic_authorized_operations, i32::MIN);
+                assert_eq!(topic.partitions.len(), 1);
+                assert_eq!(topic.partitions[0].leader_id, 1);
+                assert_eq!(topic.partitions[0].replica_nodes, vec![1]);
+                assert_eq!(topic.partitions[0].isr_nodes, vec![1]);
+                assert!(topic.partitions[0].offline_replicas.is_empty());
+            }
+            _ => panic!("Expected Metadata response"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_metadata_request_unknown_topics() -> Result<()> {
+        let unknown_id = MetadataRequestTopic {
+            topic_id: [9; 16],
+            name: None,
+        };
+        let request = metadata_request(12, Some(vec![named("missing"), unknown_id, named("bad/name")]), false);
+        let response = produce_broker().handle_request(request).await?.unwrap();
+
+        match response.payload {
+            ResponsePayload::Metadata(resp) => {
+                assert_eq!(resp.topics.len(), 3);
+                assert_eq!(resp.topics[0].error_code, UNKNOWN_TOPIC_OR_PARTITION);
+                assert_eq!(resp.topics[0].name, Some("missing".to_string()));
+                assert_eq!(resp.topics[1].error_code, i16::from(ErrorCode::UnknownTopicId));
+                assert_eq!(resp.topics[1].name, None);
+                assert_eq!(resp.topics[1].topic_id, [9; 16]);
+                assert_eq!(resp.topics[2].error_code, i16::from(ErrorCode::InvalidTopicException));
+            }
+            _ => panic!("Expected Metadata response"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_metadata_request_auto_creates_topic() -> Result<()> {
+        let broker = produce_broker();
+        let request = metadata_request(4, Some(vec![named("new-topic")]), true);
+        let response = broker.handle_request(request).await?.unwrap();
+
+        match response.payload {
+            ResponsePayload::Metadata(resp) => {
+                assert_eq!(resp.topics[0].error_code, 0);
+                assert_eq!(resp.topics[0].partitions.len(), DEFAULT_NUM_PARTITIONS as usize);
+            }
+            _ => panic!("Expected Metadata response"),
+        }
+
+        let all = broker.metadata_store.get_all_topic_metadata().await?;
+        assert!(all.iter().any(|t| t.name == "new-topic"));
+        Ok(())
+    }
+
+    fn fetch_partition_request(partition: i32, fetch_offset: i64, partition_max_bytes: i32) -> FetchPartition {
+        FetchPartition {
+            partition,
+            current_leader_epoch: -1,
+            fetch_offset,
+            last_fetched_epoch: -1,
+            log_start_offset: -1,
+            partition_max_bytes,
+        }
+    }
+
+    fn fetch_topics_request(topics: Vec<FetchTopic>, max_bytes: i32) -> KafkaRequest {
+        long_poll_request(topics, max_bytes, 0, 1)
+    }
+
+    fn long_poll_request(topics: Vec<FetchTopic>, max_bytes: i32, max_wait_ms: i32, min_bytes: i32) -> KafkaRequest {
+        KafkaRequest::new(
+            RequestHeader {
+                api_key: FETCH_KEY,
+                api_version: 16,
+                correlation_id: 9,
+                client_id: None,
+            },
+            RequestPayload::Fetch(FetchRequest {
+                replica_id: -1,
+                max_wait_ms,
+                min_bytes,
+                max_bytes,
+                isolation_level: 0,
+                session_id: 0,
+                session_epoch: -1,
+                topics,
+                forgotten_topics_data: vec![],
+                rack_id: String::new(),
+            }),
+        )
+    }
+
+    fn fetch_request(fetch_offset: i64, partition_max_bytes: i32, max_bytes: i32) -> KafkaRequest {
+        fetch_partitions_request(vec![fetch_partition_request(0, fetch_offset, partition_max_bytes)], max_bytes)
+    }
+
+    fn fetch_partitions_request(partitions: Vec<FetchPartition>, max_bytes: i32) -> KafkaRequest {
+        fetch_topics_request(vec![FetchTopic {
+            topic: String::new(),
+            topic_id: KafkaBroker::topic_id_to_bytes("00000000-0000-0000-0000-000000000001"),
+            partitions,
+        }], max_bytes)
+    }
+
+    fn fetch_broker() -> KafkaBroker {
+        let mut other_topic = test_topic();
+        other_topic.name = "other-topic".to_string();
+        other_topic.topic_id = "00000000-0000-0000-0000-000000000002".to_string();
+        KafkaBroker::new(
+            Box::new(MockLogStore::new()),
+            Box::new(MockMetadataStore::new(vec![test_topic(), other_topic])),
+            test_node(),
+        )
+    }
+
+    async fn fetch_partition(request: KafkaRequest) -> Result<FetchablePartitionResponse> {
+        match fetch_broker().handle_request(request).await?.unwrap().payload {
+            ResponsePayload::Fetch(resp) => Ok(resp.responses[0].partitions[0].clone()),
+            _ => panic!("Expected Fetch response"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_respects_byte_limits() -> Result<()> {
+        let partition = fetch_partition(fetch_request(0, 250, 1024)).await?;
+        assert_eq!(partition.error_code, 0);
+        assert_eq!(partition.high_watermark, 3);
+        assert_eq!(partition.last_stable_offset, 3);
+        assert_eq!(partition.log_start_offset, 0);
+        assert_eq!(partition.records.unwrap().len(), 200);
+
+        let partition = fetch_partition(fetch_request(1, 1024, 150)).await?;
+        assert_eq!(partition.records.unwrap().len(), 100);
+
+        // 한도보다 큰 배치라도 하나는 돌려줘야 컨슈머가 진행할 수 있음
+        let partition = fetch_partition(fetch_request(0, 50, 50)).await?;
+        assert_eq!(partition.records.unwrap().len(), 100);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_legacy_produce_and_fetch_are_converted() -> Result<()> {
+        use crate::adapters::outgoing::disk_store::DiskMessageStore;
+        use crate::adapters::outgoing::protocol::legacy_message::{read_message_set, LegacyMessage};
+
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let broker = KafkaBroker::new(
+            Box::new(DiskMessageStore::new(dir.path().to_path_buf())),
+            Box::new(MockMetadataStore::new(vec![test_topic()])),
+            test_node(),
+        );
+
+        // Produce v2 는 magic v1 메시지 셋을 보냄
+        let message_set: Vec<u8> = (0..2)
+            .flat_map(|i| LegacyMessage::new(i, 1, 1000 + i, None, Some(vec![b'a' + i as u8])).to_bytes())
+            .collect();
+        let mut request = produce_request(1);
+        request.header.api_version = 2;
+        if let RequestPayload::Produce(produce) = &mut request.payload {
+            produce.topics.truncate(1);
+            produce.topics[0].partitions = vec![ProducePartition { index: 0, records: Some(message_set) }];
+        }
+        let ResponsePayload::Produce(resp) = broker.handle_request(request).await?.unwrap().payload else {
+            panic!("Expected Produce response");
+        };
+        assert_eq!(resp.responses[0].partitions[0].error_code, 0);
+
+        for (api_version, magic) in [(0, 0), (3, 1), (4, 2)] {
+            let mut request = fetch_topics_request(vec![FetchTopic {
+                topic: "test-topic".to_string(),
+                topic_id: [0; 16],
+                partitions: vec![fetch_partition_request(0, 0, 1024)],
+            }], 1024);
+            request.header.api_version = api_version;
+            let ResponsePayload::Fetch(resp) = broker.handle_request(request).await?.unwrap().payload else {
+                panic!("Expected Fetch response");
+            };
+            let records = resp.responses[0].partitions[0].records.clone().unwrap();
+            assert_eq!(records[16] as i8, magic, "v{}", api_version);
+            if magic < 2 {
+                let messages = read_message_set(&records)?;
+                assert_eq!(messages.iter().map(|m| m.offset).collect::<Vec<_>>(), vec![0, 1]);
+                assert_eq!(messages[1].value, Some(b"b".to_vec()));
+                assert_eq!(messages[1].timestamp, if magic == 0 { -1 } else { 1001 });
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_by_topic_name_before_v13() -> Result<()> {
+        let mut request = fetch_topics_request(vec![
+            FetchTopic {
+                topic: "test-topic".to_string(),
+                topic_id: [0; 16],
+                partitions: vec![fetch_partition_request(0, 0, 1024)],
+            },
+            FetchTopic {
+                topic: "missing-topic".to_string(),
+                topic_id: [0; 16],
+                partitions: vec![fetch_partition_request(0, 0, 1024)],
+            },
+        ], 1024);
+        request.header.api_version = 12;
+        if let RequestPayload::Fetch(fetch) = &mut request.payload {
+            fetch.isolation_level = 1;
+        }
+
+        let response = fetch_broker().handle_request(request).await?.unwrap();
+        let ResponsePayload::Fetch(resp) = response.payload else {
+            panic!("Expected Fetch response");
+        };
+        assert_eq!(resp.responses[0].topic, "test-topic");
+        let partition = &resp.responses[0].partitions[0];
+        assert_eq!(partition.records.as_ref().unwrap().len(), 300);
+        assert_eq!(partition.aborted_transactions, Some(vec![]));
+        assert_eq!(partition.preferred_read_replica, -1);
+        assert_eq!(resp.responses[1].partitions[0].error_code, i16::from(ErrorCode::UnknownTopicOrPartition));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_validates_leader_epochs() -> Result<()> {
+        let mut topic = test_topic();
+        topic.partitions[0].leader_epoch = 2;
+        topic.partitions[0].leader_id = 2;
+        let broker = KafkaBroker::new(
+            Box::new(MockLogStore::new()),
+            Box::new(MockMetadataStore::new(vec![topic])),
+            BrokerMetadata { node_id: 2, ..test_node() },
+        );
+        let fetch = |current_leader_epoch: i32, last_fetched_epoch: i32, fetch_offset: i64| {
+            let mut partition = fetch_partition_request(0, fetch_offset, 1024);
+            partition.current_leader_epoch = current_leader_epoch;
+            partition.last_fetched_epoch = last_fetched_epoch;
+            fetch_partitions_request(vec![partition], 1024)
+        };
+
+        // 오래된 epoch 는 FENCED_LEADER_EPOCH 와 함께 현재 리더를 알려줌
+        let ResponsePayload::Fetch(resp) = broker.handle_request(fetch(1, -1, 0)).await?.unwrap().payload else {
+            panic!("Expected Fetch response");
+        };
+        let partition = &resp.responses[0].partitions[0];
+        assert_eq!(partition.error_code, i16::from(ErrorCode::FencedLeaderEpoch));
+        assert_eq!(partition.current_leader, Some(LeaderIdAndEpoch { leader_id: 2, leader_epoch: 2 }));
+        assert_eq!(resp.node_endpoints.iter().map(|node| node.node_id).collect::<Vec<_>>(), vec![2]);
+
+        let ResponsePayload::Fetch(resp) = broker.handle_request(fetch(3, -1, 0)).await?.unwrap().payload else {
+            panic!("Expected Fetch response");
+        };
+        assert_eq!(resp.responses[0].partitions[0].error_code, i16::from(ErrorCode::UnknownLeaderEpoch));
+        assert!(resp.node_endpoints.is_empty());
+
+        // 이전 epoch 로 받은 offset 이 리더의 epoch 시작보다 뒤면 잘라야 함
+        let ResponsePayload::Fetch(resp) = broker.handle_request(fetch(2, 1, 2)).await?.unwrap().payload else {
+            panic!("Expected Fetch response");
+        };
+        let partition = &resp.responses[0].partitions[0];
+        assert_eq!(partition.error_code, 0);
+        assert_eq!(partition.diverging_epoch, Some(EpochEndOffset { epoch: 1, end_offset: 0 }));
+        assert!(partition.records.is_none());
+
+        let ResponsePayload::Fetch(resp) = broker.handle_request(fetch(2, 2, 2)).await?.unwrap().payload else {
+            panic!("Expected Fetch response");
+        };
+        assert!(resp.responses[0].partitions[0].diverging_epoch.is_none());
+        assert!(resp.responses[0].partitions[0].records.is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_reports_offsets_after_log_start_moves() -> Result<()> {
+        use crate::adapters::outgoing::disk_store::DiskMessageStore;
+        use crate::adapters::outgoing::kraft_metadata_store::KraftMetadataStore;
+        use crate::adapters::outgoing::log::batch::tests::record_batch;
+        use crate::adapters::outgoing::log::partition_log::{LogConfig, MIN_SEGMENT_BYTES};
+
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        // 배치마다 세그먼트 하나
+        let log_config = LogConfig { segment_bytes: MIN_SEGMENT_BYTES, ..LogConfig::default() };
+        let open_broker = || KafkaBroker::new(
+            Box::new(DiskMessageStore::with_config(dir.path().to_path_buf(), log_config.clone())),
+            Box::new(KraftMetadataStore::new(dir.path().to_path_buf())),
+            test_node(),
+        );
+        let broker = open_broker();
+        broker.handle_request(create_topics_request(7, vec![creatable_topic("foo", 1, 1)], false)).await?;
+        for _ in 0..5 {
+            broker.message_store.store_message("foo", 0, &record_batch(0, 0)).await?;
+        }
+        let topic_id = broker.metadata_store.get_topic_metadata_by_names(vec!["foo".to_string()]).await?.unwrap()[0].topic_id.clone();
+        let fetch = |fetch_offset: i64| fetch_topics_request(vec![FetchTopic {
+            topic: String::new(),
+            topic_id: KafkaBroker::topic_id_to_bytes(&topic_id),
+            partitions: vec![fetch_partition_request(0, fetch_offset, 1024)],
+        }], 1024);
+
+        // retention 처럼 앞의 두 세그먼트를 지우고 다시 열면 log start offset 이 올라감
+        drop(broker);
+        for entry in std::fs::read_dir(dir.path().join("foo-0")).map_err(ApplicationError::Io)? {
+            let path = entry.map_err(ApplicationError::Io)?.path();
+            if path.file_stem().and_then(|stem| stem.to_str()).is_some_and(|stem| stem.parse::<i64>().is_ok_and(|base| base < 2)) {
+                std::fs::remove_file(path).map_err(ApplicationError::Io)?;
+            }
+        }
+        let broker = open_broker();
+
+        let ResponsePayload::Fetch(resp) = broker.handle_request(fetch(3)).await?.unwrap().payload else {
+            panic!("Expected Fetch response");
+        };
+        let partition = &resp.responses[0].partitions[0];
+        assert_eq!(partition.error_code, 0);
+        assert_eq!((partition.high_watermark, partition.last_stable_offset, partition.log_start_offset), (5, 5, 2));
+        assert!(partition.records.is_some());
+
+        let ResponsePayload::Fetch(resp) = broker.handle_request(fetch(1)).await?.unwrap().payload else {
+            panic!("Expected Fetch response");
+        };
+        assert_eq!(resp.responses[0].partitions[0].error_code, i16::from(ErrorCode::OffsetOutOfRange));
+
+        let partitions = list_offsets(&broker, list_offsets_request(7, "foo", &[EARLIEST_TIMESTAMP, LATEST_TIMESTAMP])).await?;
+        assert_eq!(partitions.iter().map(|p| p.offset).collect::<Vec<_>>(), vec![2, 5]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_offset_out_of_range() -> Result<()> {
+        let partition = fetch_partition(fetch_request(3, 1024, 1024)).await?;
+        assert_eq!(partition.error_code, 0);
+        assert!(partition.records.is_none());
+
+        let partition = fetch_partition(fetch_request(5, 1024, 1024)).await?;
+        assert_eq!(partition.error_code, i16::from(ErrorCode::OffsetOutOfRange));
+        assert_eq!(partition.high_watermark, -1);
+        assert!(partition.records.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_multiple_topics_and_partitions() -> Result<()> {
+        let request = fetch_topics_request(vec![
+            FetchTopic {
+                topic: String::new(),
+                topic_id: KafkaBroker::topic_id_to_bytes("00000000-0000-0000-0000-000000000001"),
+                partitions: vec![
+                    fetch_partition_request(0, 0, 1024),
+                    fetch_partition_request(7, 0, 1024),
+                ],
+            },
+            FetchTopic {
+                topic: String::new(),
+                topic_id: KafkaBroker::topic_id_to_bytes("00000000-0000-0000-0000-0000000000aa"),
+                partitions: vec![fetch_partition_request(0, 0, 1024)],
+            },
+            FetchTopic {
+                topic: String::new(),
+                topic_id: KafkaBroker::topic_id_to_bytes("00000000-0000-0000-0000-000000000002"),
+                partitions: vec![
+                    fetch_partition_request(0, 1, 1024),
+                    fetch_partition_request(0, 0, 1024),
+                ],
+            },
+        ], 350);
+
+        let response = fetch_broker().handle_request(request).await?.unwrap();
+        let ResponsePayload::Fetch(resp) = response.payload else {
+            panic!("Expected Fetch response");
+        };
+        assert_eq!(resp.responses.len(), 3);
+
+        let first = &resp.responses[0].partitions;
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[0].records.as_ref().unwrap().len(), 300);
+        assert_eq!(first[1].error_code, i16::from(ErrorCode::UnknownTopicOrPartition));
+
+        assert_eq!(resp.responses[1].partitions[0].error_code, i16::from(ErrorCode::UnknownTopicId));
+
+        // 응답 한도 (350) 중 남은 50 바이트로는 배치를 더 담을 수 없음
+        let third = &resp.responses[2].partitions;
+        assert_eq!(third.len(), 2);
+        assert_eq!(third[0].error_code, 0);
+        assert!(third[0].records.is_none());
+        assert!(third[1].records.is_none());
+        Ok(())
+    }
+
+    fn test_topic_fetch(fetch_offset: i64) -> Vec<FetchTopic> {
+        vec![FetchTopic {
+            topic: String::new(),
+            topic_id: KafkaBroker::topic_id_to_bytes("00000000-0000-0000-0000-000000000001"),
+            partitions: vec![fetch_partition_request(0, fetch_offset, 1024)],
+        }]
+    }
+
+    fn fetched_bytes(response: KafkaResponse) -> usize {
+        match response.payload {
+            ResponsePayload::Fetch(resp) => resp.responses[0].partitions[0].records.as_ref().map_or(0, |r| r.len()),
+            _ => panic!("Expected Fetch response"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_long_poll_fetch_woken_by_produce() -> Result<()> {
+        let broker = std::sync::Arc::new(fetch_broker());
+
+        let fetcher = broker.clone();
+        let started = std::time::Instant::now();
+        let (parked, ready) = ParkSignal::new();
+        let fetch = tokio::spawn(async move {
+            fetcher.handle_parkable_request(long_poll_request(test_topic_fetch(3), 1024, 10_000, 1), parked).await
+        });
+
+        // purgatory 에 걸리면 응답 전에 커넥션을 풀어줌
+        assert!(ready.await.is_ok());
+        assert!(!fetch.is_finished());
+        broker.handle_request(produce_request(1)).await?;
+
+        let response = fetch.await.unwrap()?.unwrap();
+        assert_eq!(fetched_bytes(response), 100);
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_long_poll_fetch_times_out() -> Result<()> {
+        let broker = fetch_broker();
+
+        let started = std::time::Instant::now();
+        let response = broker.handle_request(long_poll_request(test_topic_fetch(3), 1024, 50, 1)).await?.unwrap();
+        assert_eq!(fetched_bytes(response), 0);
+        assert!(started.elapsed() >= std::time::Duration::from_millis(50));
+
+        // 이미 min_bytes 만큼 있으면 기다리지 않음
+        let started = std::time::Instant::now();
+        let response = broker.handle_request(long_poll_request(test_topic_fetch(0), 1024, 10_000, 200)).await?.unwrap();
+        assert_eq!(fetched_bytes(response), 300);
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_session_errors() -> Result<()> {
+        let broker = fetch_broker();
+
+        let mut request = fetch_request(0, 1024, 1024);
+        if let RequestPayload::Fetch(fetch) = &mut request.payload {
+            fetch.session_id = 1234;
+            fetch.session_epoch = 1;
+        }
+        let response = broker.handle_request(request).await?.unwrap();
+        assert_eq!(response.error_code, i16::from(ErrorCode::FetchSessionIdNotFound));
+
+        // 새 세션을 만들면 session id 가 응답에 실림
+        let mut request = fetch_request(0, 1024, 1024);
+        if let RequestPayload::Fetch(fetch) = &mut request.payload {
+            fetch.session_epoch = 0;
+        }
+        let response = broker.handle_request(request).await?.unwrap();
+        let ResponsePayload::Fetch(resp) = response.payload else {
+            panic!("Expected Fetch response");
+        };
+        assert_ne!(resp.session_id, 0);
+        assert_eq!(resp.responses[0].partitions.len(), 1);
+        Ok(())
+    }
+
+    fn list_offsets_request(api_version: i16, topic_name: &str, timestamps: &[i64]) -> KafkaRequest {
+        KafkaRequest::new(
+            RequestHeader {
+                api_key: LIST_OFFSETS_KEY,
+                api_version,
+                correlation_id: 7,
+                client_id: None,
+            },
+            RequestPayload::ListOffsets(ListOffsetsRequest {
+                replica_id: -1,
+                isolation_level: 0,
+                topics: vec![ListOffsetsTopic {
+                    name: topic_name.to_string(),
+                    partitions: timestamps.iter()
+                        .map(|&timestamp| ListOffsetsPartition {
+                            partition_index: 0,
+                            current_leader_epoch: -1,
+                            timestamp,
+                        })
+                        .collect(),
+                }],
+            }),
+        )
+    }
+
+    async fn list_offsets(broker: &KafkaBroker, request: KafkaRequest) -> Result<Vec<ListOffsetsPartitionResponse>> {
+        let response = broker.handle_request(request).await?.unwrap();
+        match response.payload {
+            ResponsePayload::ListOffsets(mut response) => Ok(response.topics.remove(0).partitions),
+            _ => panic!("Expected ListOffsets response"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_list_offsets_request() -> Result<()> {
+        let broker = fetch_broker();
+
+        let partitions = list_offsets(&broker, list_offsets_request(8, "test-topic", &[
+            EARLIEST_TIMESTAMP, LATEST_TIMESTAMP, 1500, 5000, MAX_TIMESTAMP, EARLIEST_LOCAL_TIMESTAMP,
+        ])).await?;
+        let offsets: Vec<(i16, i64, i64)> = partitions.iter()
+            .map(|p| (p.error_code, p.timestamp, p.offset))
+            .collect();
+        assert_eq!(offsets, vec![
+            (0, -1, 0),
+            (0, -1, 3),
+            (0, 2000, 2),
+            // 해당 timestamp 이후 레코드가 없으면 -1
+            (0, -1, -1),
+            (0, 2000, 2),
+            (0, -1, 0),
+        ]);
+
+        // max timestamp 는 v7, earliest local 은 v8 부터
+        let partitions = list_offsets(&broker, list_offsets_request(6, "test-topic", &[MAX_TIMESTAMP, EARLIEST_LOCAL_TIMESTAMP])).await?;
+        assert!(partitions.iter().all(|p| p.error_code == i16::from(ErrorCode::UnsupportedVersion)));
+
+        let partitions = list_offsets(&broker, list_offsets_request(8, "unknown-topic", &[LATEST_TIMESTAMP])).await?;
+        assert_eq!(partitions[0].error_code, i16::from(ErrorCode::UnknownTopicOrPartition));
+        assert_eq!(partitions[0].offset, -1);
+        Ok(())
+    }
+
+    fn create_topics_request(api_version: i16, topics: Vec<CreatableTopic>, validate_only: bool) -> KafkaRequest {
+        KafkaRequest::new(
+            RequestHeader {
+                api_key: CREATE_TOPICS_KEY,
+                api_version,
+                correlation_id: 11,
+                client_id: None,
+            },
+            RequestPayload::CreateTopics(CreateTopicsRequest {
+                topics,
+                timeout_ms: 30000,
+                validate_only,
+            }),
+        )
+    }
+
+    fn creatable_topic(name: &str, num_partitions: i32, replication_factor: i16) -> CreatableTopic {
+        CreatableTopic {
+            name: name.to_string(),
+            num_partitions,
+            replication_factor,
+            assignments: vec![],
+            configs: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_create_topics_request() -> Result<()> {
+        use crate::adapters::outgoing::disk_store::DiskMessageStore;
+        use crate::adapters::outgoing::kraft_metadata_store::KraftMetadataStore;
+
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let broker = KafkaBroker::new(
+            Box::new(DiskMessageStore::new(dir.path().to_path_buf())),
+            Box::new(KraftMetadataStore::new(dir.path().to_path_buf())),
+            test_node(),
+        ).with_num_partitions(2);
+
+        let topics = vec![
+            CreatableTopic {
+                configs: vec![CreatableTopicConfig { name: "cleanup.policy".to_string(), value: Some("compact".to_string()) }],
+                ..creatable_topic("foo", 3, 1)
+            },
+            creatable_topic("defaults", -1, -1),
+            CreatableTopic {
+                assignments: vec![
+                    CreatableReplicaAssignment { partition_index: 1, broker_ids: vec![1] },
+                    CreatableReplicaAssignment { partition_index: 0, broker_ids: vec![1] },
+                ],
+                ..creatable_topic("manual", -1, -1)
+            },
+            creatable_topic("replicated", 1, 3),
+            creatable_topic("empty", 0, 1),
+            creatable_topic("huge", i32::MAX, 1),
+            creatable_topic("bad/name", 1, 1),
+            CreatableTopic {
+                assignments: vec![CreatableReplicaAssignment { partition_index: 0, broker_ids: vec![2] }],
+                ..creatable_topic("unknown-broker", -1, -1)
+            },
+            CreatableTopic {
+                configs: vec![CreatableTopicConfig { name: "segment.bytes".to_string(), value: Some("abc".to_string()) }],
+                ..creatable_topic("bad-config", 1, 1)
+            },
+        ];
+        let ResponsePayload::CreateTopics(resp) = broker.handle_request(create_topics_request(7, topics, false)).await?.unwrap().payload else {
+            panic!("Expected CreateTopics response");
+        };
+        let results: Vec<(&str, ErrorCode, i32, i16)> = resp.topics.iter()
+            .map(|t| (t.name.as_str(), ErrorCode::from(t.error_code), t.num_partitions, t.replication_factor))
+            .collect();
+        assert_eq!(results, vec![
+            ("foo", ErrorCode::None, 3, 1),
+            ("defaults", ErrorCode::None, 2, 1),
+            ("manual", ErrorCode::None, 2, 1),
+            ("replicated", ErrorCode::InvalidReplicationFactor, -1, -1),
+            ("empty", ErrorCode::InvalidPartitions, -1, -1),
+            ("huge", ErrorCode::InvalidPartitions, -1, -1),
+            ("bad/name", ErrorCode::InvalidTopicException, -1, -1),
+            ("unknown-broker", ErrorCode::InvalidReplicaAssignment, -1, -1),
+            ("bad-config", ErrorCode::InvalidConfig, -1, -1),
+        ]);
+        assert_ne!(resp.topics[0].topic_id, [0; 16]);
+        assert_eq!(resp.topics[0].configs.as_ref().unwrap()[0].value.as_deref(), Some("compact"));
+        assert!(resp.topics[3].error_message.is_some());
+        assert!(dir.path().join("foo-2").is_dir());
+        assert!(!dir.path().join("bad-config-0").exists());
+        assert!(!dir.path().join("huge-0").exists());
+
+        // 이미 있는 토픽, 요청 안의 중복 이름
+        let topics = vec![creatable_topic("foo", 1, 1), creatable_topic("dup", 1, 1), creatable_topic("dup", 1, 1)];
+        let ResponsePayload::CreateTopics(resp) = broker.handle_request(create_topics_request(5, topics, false)).await?.unwrap().payload else {
+            panic!("Expected CreateTopics response");
+        };
+        let errors: Vec<ErrorCode> = resp.topics.iter().map(|t| ErrorCode::from(t.error_code)).collect();
+        assert_eq!(errors, vec![ErrorCode::TopicAlreadyExists, ErrorCode::InvalidRequest, ErrorCode::InvalidRequest]);
+
+        // 새로 만든 토픽은 바로 Metadata 에 보여야 함
+        let resp = broker.handle_request(metadata_request(12, None, false)).await?.unwrap();
+        let ResponsePayload::Metadata(metadata) = resp.payload else {
+            panic!("Expected Metadata response");
+        };
+        let names: Vec<Option<&str>> = metadata.topics.iter().map(|t| t.name.as_deref()).collect();
+        assert_eq!(names, vec![Some("defaults"), Some("foo"), Some("manual")]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_topics_validate_only() -> Result<()> {
+        let broker = KafkaBroker::new(
+            Box::new(FailingMessageStore),
+            Box::new(MockMetadataStore::new(vec![])),
+            test_node(),
+        );
+
+        let request = create_topics_request(2, vec![creatable_topic("foo", 4, -1)], true);
+        let ResponsePayload::CreateTopics(resp) = broker.handle_request(request).await?.unwrap().payload else {
+            panic!("Expected CreateTopics response");
+        };
+        assert_eq!(resp.topics[0].error_code, 0);
+        assert_eq!(resp.topics[0].num_partitions, 4);
+        assert_eq!(resp.topics[0].topic_id, [0; 16]);
+        assert!(broker.metadata_store.get_all_topic_metadata().await?.is_empty());
+
+        // 실제로 만들 때 파티션 디렉토리를 못 만들면 저장소 에러
+        let request = create_topics_request(2, vec![creatable_topic("foo", 4, -1)], false);
+        let ResponsePayload::CreateTopics(resp) = broker.handle_request(request).await?.unwrap().payload else {
+            panic!("Expected CreateTopics response");
+        };
+        assert_eq!(ErrorCode::from(resp.topics[0].error_code), ErrorCode::KafkaStorageError);
+        // 메타데이터는 되돌려져서 다시 시도해도 TOPIC_ALREADY_EXISTS 가 아님
+        assert!(broker.metadata_store.get_all_topic_metadata().await?.is_empty());
+        let request = create_topics_request(2, vec![creatable_topic("foo", 4, -1)], false);
+        let ResponsePayload::CreateTopics(resp) = broker.handle_request(request).await?.unwrap().payload else {
+            panic!("Expected CreateTopics response");
+        };
+        assert_eq!(ErrorCode::from(resp.topics[0].error_code), ErrorCode::KafkaStorageError);
+        Ok(())
+    }
+
+    fn delete_topics_request(api_version: i16, topics: Vec<DeleteTopicState>) -> KafkaRequest {
+        KafkaRequest::new(
+            RequestHeader {
+                api_key: DELETE_TOPICS_KEY,
+                api_version,
+                correlation_id: 12,
+                client_id: None,
+            },
+            RequestPayload::DeleteTopics(DeleteTopicsRequest {
+                topics,
+                timeout_ms: 30000,
+            }),
+        )
+    }
+
+    fn delete_topic_by_name(name: &str) -> DeleteTopicState {
+        DeleteTopicState { name: Some(name.to_string()), topic_id: [0; 16] }
+    }
+
+    #[tokio::test]
+    async fn test_handle_delete_topics_request() -> Result<()> {
+        use crate::adapters::outgoing::disk_store::DiskMessageStore;
+        use crate::adapters::outgoing::kraft_metadata_store::KraftMetadataStore;
+
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let broker = KafkaBroker::new(
+            Box::new(DiskMessageStore::new(dir.path().to_path_buf())),
+            Box::new(KraftMetadataStore::new(dir.path().to_path_buf())),
+            test_node(),
+        );
+        let topics = vec![creatable_topic("foo", 2, 1), creatable_topic("bar", 1, 1), creatable_topic("baz", 1, 1)];
+        let ResponsePayload::CreateTopics(created) = broker.handle_request(create_topics_request(7, topics, false)).await?.unwrap().payload else {
+            panic!("Expected CreateTopics response");
+        };
+        let bar_id = created.topics[1].topic_id;
+
+        // v5 는 이름으로만 지움
+        let request = delete_topics_request(5, vec![delete_topic_by_name("foo"), delete_topic_by_name("missing")]);
+        let ResponsePayload::DeleteTopics(resp) = broker.handle_request(request).await?.unwrap().payload else {
+            panic!("Expected DeleteTopics response");
+        };
+        let results: Vec<(Option<&str>, ErrorCode)> = resp.responses.iter()
+            .map(|t| (t.name.as_deref(), ErrorCode::from(t.error_code)))
+            .collect();
+        assert_eq!(results, vec![(Some("foo"), ErrorCode::None), (Some("missing"), ErrorCode::UnknownTopicOrPartition)]);
+        assert_eq!(resp.responses[0].topic_id, created.topics[0].topic_id);
+        assert!(!dir.path().join("foo-0").exists());
+        assert!(!dir.path().join("foo-1").exists());
+
+        // v6 는 토픽 ID 로도 지울 수 있음
+        let request = delete_topics_request(6, vec![
+            DeleteTopicState { name: None, topic_id: bar_id },
+            DeleteTopicState { name: None, topic_id: [7; 16] },
+            DeleteTopicState { name: Some("baz".to_string()), topic_id: created.topics[2].topic_id },
+            delete_topic_by_name("dup"),
+            delete_topic_by_name("dup"),
+        ]);
+        let ResponsePayload::DeleteTopics(resp) = broker.handle_request(request).await?.unwrap().payload else {
+            panic!("Expected DeleteTopics response");
+        };
+        let results: Vec<(Option<&str>, ErrorCode)> = resp.responses.iter()
+            .map(|t| (t.name.as_deref(), ErrorCode::from(t.error_code)))
+            .collect();
+        assert_eq!(results, vec![
+            (Some("bar"), ErrorCode::None),
+            (None, ErrorCode::UnknownTopicId),
+            (Some("baz"), ErrorCode::InvalidRequest),
+            (Some("dup"), ErrorCode::InvalidRequest),
+            (Some("dup"), ErrorCode::InvalidRequest),
+        ]);
+        assert!(resp.responses[0].error_message.is_none());
+        assert!(resp.responses[1].error_message.is_some());
+
+        // 지운 토픽은 바로 Metadata 에서 빠져야 함
+        let resp = broker.handle_request(metadata_request(12, None, false)).await?.unwrap();
+        let ResponsePayload::Metadata(metadata) = resp.payload else {
+            panic!("Expected Metadata response");
+        };
+        let names: Vec<Option<&str>> = metadata.topics.iter().map(|t| t.name.as_deref()).collect();
+        assert_eq!(names, vec![Some("baz")]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_auto_created_topic_is_writable_after_delete() -> Result<()> {
+        use crate::adapters::outgoing::disk_store::DiskMessageStore;
+        use crate::adapters::outgoing::kraft_metadata_store::KraftMetadataStore;
+        use crate::adapters::outgoing::protocol::record_batch::{Record, RecordBatch};
+
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let broker = KafkaBroker::new(
+            Box::new(DiskMessageStore::new(dir.path().to_path_buf())),
+            Box::new(KraftMetadataStore::new(dir.path().to_path_buf())),
+            test_node(),
+        ).with_num_partitions(1);
+        broker.handle_request(create_topics_request(7, vec![creatable_topic("test-topic", 1, 1)], false)).await?;
+        broker.handle_request(delete_topics_request(5, vec![delete_topic_by_name("test-topic")])).await?;
+
+        // 지운 이름을 Metadata 가 자동으로 다시 만들면 바로 Produce 할 수 있어야 함
+        let ResponsePayload::Metadata(metadata) = broker.handle_request(metadata_request(12, Some(vec![named("test-topic")]), true)).await?.unwrap().payload else {
+            panic!("Expected Metadata response");
+        };
+        assert_eq!(ErrorCode::from(metadata.topics[0].error_code), ErrorCode::None);
+
+        let records = RecordBatch::new(0, 0, 1000, vec![Record::new(0, None, Some(b"value".to_vec()))]).to_bytes()?;
+        let mut request = produce_request(-1);
+        let RequestPayload::Produce(produce) = &mut request.payload else { unreachable!() };
+        produce.topics = vec![ProduceTopic {
+            name: "test-topic".to_string(),
+            partitions: vec![ProducePartition { index: 0, records: Some(records) }],
+        }];
+        let ResponsePayload::Produce(resp) = broker.handle_request(request).await?.unwrap().payload else {
+            panic!("Expected Produce response");
+        };
+        let partition = &resp.responses[0].partitions[0];
+        assert_eq!((ErrorCode::from(partition.error_code), partition.base_offset), (ErrorCode::None, 0));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_auto_create_rolls_back_metadata_on_storage_failure() -> Result<()> {
+        let broker = KafkaBroker::new(
+            Box::new(FailingMessageStore),
+            Box::new(MockMetadataStore::new(vec![])),
+            test_node(),
+        );
+
+        // 파티션 디렉토리를 못 만들면 메타데이터에도 토픽이 남지 않아야 함
+        let ResponsePayload::Metadata(metadata) = broker.handle_request(metadata_request(12, Some(vec![named("test-topic")]), true)).await?.unwrap().payload else {
+            panic!("Expected Metadata response");
+        };
+        assert_eq!(ErrorCode::from(metadata.topics[0].error_code), ErrorCode::KafkaStorageError);
+        assert!(broker.metadata_store.get_all_topic_metadata().await?.is_empty());
+        Ok(())
+    }
+
+    fn create_partitions_request(topics: Vec<CreatePartitionsTopic>, validate_only: bool) -> KafkaRequest {
+        KafkaRequest::new(
+            RequestHeader {
+                api_key: CREATE_PARTITIONS_KEY,
+                api_version: 3,
+                correlation_id: 13,
+                client_id: None,
+            },
+            RequestPayload::CreatePartitions(CreatePartitionsRequest {
+                topics,
+                timeout_ms: 30000,
+                validate_only,
+            }),
+        )
+    }
+
+    fn partitions_topic(name: &str, count: i32, assignments: Option<Vec<Vec<i32>>>) -> CreatePartitionsTopic {
+        CreatePartitionsTopic { name: name.to_string(), count, assignments }
+    }
+
+    #[tokio::test]
+    async fn test_handle_create_partitions_request() -> Result<()> {
+        use crate::adapters::outgoing::disk_store::DiskMessageStore;
+        use crate::adapters::outgoing::kraft_metadata_store::KraftMetadataStore;
+
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let broker = KafkaBroker::new(
+            Box::new(DiskMessageStore::new(dir.path().to_path_buf())),
+            Box::new(KraftMetadataStore::new(dir.path().to_path_buf())),
+            test_node(),
+        );
+        let topics = vec![creatable_topic("foo", 1, 1), creatable_topic("bar", 2, 1)];
+        broker.handle_request(create_topics_request(7, topics, false)).await?;
+
+        let request = create_partitions_request(vec![
+            partitions_topic("foo", 3, None),
+            partitions_topic("bar", 4, Some(vec![vec![1], vec![1]])),
+            partitions_topic("missing", 2, None),
+        ], false);
+        let ResponsePayload::CreatePartitions(resp) = broker.handle_request(request).await?.unwrap().payload else {
+            panic!("Expected CreatePartitions response");
+        };
+        let errors: Vec<(&str, ErrorCode)> = resp.results.iter().map(|r| (r.name.as_str(), ErrorCode::from(r.error_code))).collect();
+        assert_eq!(errors, vec![("foo", ErrorCode::None), ("bar", ErrorCode::None), ("missing", ErrorCode::UnknownTopicOrPartition)]);
+        assert!(dir.path().join("foo-2").is_dir());
+        assert!(dir.path().join("bar-3").is_dir());
+
+        let request = create_partitions_request(vec![
+            partitions_topic("foo", 3, None),
+            partitions_topic("bar", 2, None),
+            partitions_topic("baz", 1, None),
+            partitions_topic("baz", 1, None),
+        ], false);
+        let ResponsePayload::CreatePartitions(resp) = broker.handle_request(request).await?.unwrap().payload else {
+            panic!("Expected CreatePartitions response");
+        };
+        let errors: Vec<ErrorCode> = resp.results.iter().map(|r| ErrorCode::from(r.error_code)).collect();
+        assert_eq!(errors, vec![ErrorCode::InvalidPartitions, ErrorCode::InvalidPartitions, ErrorCode::InvalidRequest, ErrorCode::InvalidRequest]);
+        assert_eq!(resp.results[0].error_message.as_deref(), Some("Topic already has 3 partition(s)."));
+
+        // 늘어난 파티션은 바로 DescribeTopicPartitions 와 Metadata 에 보여야 함
+        let request = KafkaRequest::new(
+            RequestHeader {
+                api_key: DESCRIBE_TOPIC_PARTITIONS_KEY,
+                api_version: 0,
+                correlation_id: 14,
+                client_id: None,
+            },
+            RequestPayload::DescribeTopicPartitions(DescribeTopicPartitionsRequest {
+                topics: vec![TopicRequest { topic_name: "foo".to_string(), partitions: vec![] }],
+            }),
+        );
+        let ResponsePayload::DescribeTopicPartitions(resp) = broker.handle_request(request).await?.unwrap().payload else {
+            panic!("Expected DescribeTopicPartitions response");
+        };
+        assert_eq!(resp.topics[0].partitions.iter().map(|p| p.partition_id).collect::<Vec<_>>(), vec![0, 1, 2]);
+
+        let ResponsePayload::Metadata(metadata) = broker.handle_request(metadata_request(12, None, false)).await?.unwrap().payload else {
+            panic!("Expected Metadata response");
+        };
+        let counts: Vec<(Option<&str>, usize)> = metadata.topics.iter().map(|t| (t.name.as_deref(), t.partitions.len())).collect();
+        assert_eq!(counts, vec![(Some("bar"), 4), (Some("foo"), 3)]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_describe_topic_partitions_reports_assigned_replicas() -> Result<()> {
+        use crate::adapters::incoming::kafka_protocol_parser::KafkaProtocolParser;
+        use crate::adapters::incoming::protocol::codec::Decodable;
+        use crate::adapters::incoming::protocol::schema::{describe_topic_partitions_response, response_header};
+        use crate::adapters::outgoing::disk_store::DiskMessageStore;
+        use crate::adapters::outgoing::kraft_metadata_store::KraftMetadataStore;
+
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let broker = KafkaBroker::new(
+            Box::new(DiskMessageStore::new(dir.path().to_path_buf())),
+            Box::new(KraftMetadataStore::new(dir.path().to_path_buf())),
+            BrokerMetadata { node_id: 2, ..test_node() },
+        );
+        broker.handle_request(create_topics_request(7, vec![creatable_topic("foo", 1, 1)], false)).await?;
+        let request = create_partitions_request(vec![partitions_topic("foo", 2, Some(vec![vec![2]]))], false);
+        broker.handle_request(request).await?;
+
+        let request = KafkaRequest::new(
+            RequestHeader {
+                api_key: DESCRIBE_TOPIC_PARTITIONS_KEY,
+                api_version: 0,
+                correlation_id: 14,
+                client_id: None,
+            },
+            RequestPayload::DescribeTopicPartitions(DescribeTopicPartitionsRequest {
+                topics: vec![TopicRequest { topic_name: "foo".to_string(), partitions: vec![] }],
+            }),
+        );
+        let response = broker.handle_request(request).await?.unwrap();
+        let encoded = KafkaProtocolParser::new().encode_response(response);
+        let mut buf = bytes::Bytes::from(encoded[4..].to_vec());
+        response_header::ResponseHeaderData::decode(&mut buf, 1)?;
+        let decoded = describe_topic_partitions_response::DescribeTopicPartitionsResponseData::decode(&mut buf, 0)?;
+
+        let partitions: Vec<(i32, i32, Vec<i32>, Vec<i32>)> = decoded.topics[0].partitions.iter()
+            .map(|p| (p.partition_index, p.leader_id, p.replica_nodes.clone(), p.isr_nodes.clone()))
+            .collect();
+        assert_eq!(partitions, vec![(0, 2, vec![2], vec![2]), (1, 2, vec![2], vec![2])]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_responses_use_configured_node_id() -> Result<()> {
+        use crate::adapters::outgoing::disk_store::DiskMessageStore;
+        use crate::adapters::outgoing::kraft_metadata_store::KraftMetadataStore;
+        use crate::config::server_config::ServerConfig;
+
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let server_config = ServerConfig::load(None, &[
+            ("node.id".to_string(), "2".to_string()),
+            ("log.dirs".to_string(), dir.path().to_string_lossy().to_string()),
+        ])?;
+        let broker = KafkaBroker::new(
+            Box::new(DiskMessageStore::with_config(server_config.log_dir.clone(), server_config.log_config())),
+            Box::new(KraftMetadataStore::new(server_config.metadata_log_dir.clone())),
+            server_config.broker_metadata(),
+        );
+        broker.handle_request(create_topics_request(7, vec![creatable_topic("foo", 1, 1)], false)).await?;
+
+        let ResponsePayload::Metadata(metadata) = broker.handle_request(metadata_request(12, None, false)).await?.unwrap().payload else {
+            panic!("Expected Metadata response");
+        };
+        assert_eq!(metadata.brokers.iter().map(|b| b.node_id).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(metadata.controller_id, 2);
+        let partition = &metadata.topics[0].partitions[0];
+        assert_eq!((partition.leader_id, partition.replica_nodes.clone(), partition.isr_nodes.clone()), (2, vec![2], vec![2]));
+
+        let request = KafkaRequest::new(
+            RequestHeader {
+                api_key: DESCRIBE_TOPIC_PARTITIONS_KEY,
+                api_version: 0,
+                correlation_id: 14,
+                client_id: None,
+            },
+            RequestPayload::DescribeTopicPartitions(DescribeTopicPartitionsRequest {
+                topics: vec![TopicRequest { topic_name: "foo".to_string(), partitions: vec![] }],
+            }),
+        );
+        let ResponsePayload::DescribeTopicPartitions(resp) = broker.handle_request(request).await?.unwrap().payload else {
+            panic!("Expected DescribeTopicPartitions response");
+        };
+        assert_eq!(resp.topics[0].partitions[0].leader_id, 2);
+        assert_eq!(resp.topics[0].partitions[0].replica_nodes, vec![2]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_partitions_validation() -> Result<()> {
+        let broker = KafkaBroker::new(
+            Box::new(FailingMessageStore),
+            Box::new(MockMetadataStore::new(vec![])),
+            test_node(),
+        );
+        broker.metadata_store.create_topic("foo", 1, vec![1]).await?;
+
+        let request = create_partitions_request(vec![
+            partitions_topic("foo", 2, None),
+            partitions_topic("foo", 3, Some(vec![vec![1]])),
+        ], true);
+        let ResponsePayload::CreatePartitions(resp) = broker.handle_request(request).await?.unwrap().payload else {
+            panic!("Expected CreatePartitions response");
+        };
+        assert_eq!(resp.results.iter().map(|r| ErrorCode::from(r.error_code)).collect::<Vec<_>>(), vec![ErrorCode::InvalidRequest; 2]);
+
+        let request = create_partitions_request(vec![partitions_topic("foo", i32::MAX, None)], true);
+        let ResponsePayload::CreatePartitions(resp) = broker.handle_request(request).await?.unwrap().payload else {
+            panic!("Expected CreatePartitions response");
+        };
+        assert_eq!(ErrorCode::from(resp.results[0].error_code), ErrorCode::InvalidPartitions);
+
+        for (assignments, error) in [
+            (Some(vec![vec![1]]), ErrorCode::InvalidReplicaAssignment),
+            (Some(vec![vec![1], vec![2]]), ErrorCode::InvalidReplicaAssignment),
+            (Some(vec![vec![1], vec![1, 1]]), ErrorCode::InvalidReplicaAssignment),
+            (Some(vec![vec![1], vec![]]), ErrorCode::InvalidReplicaAssignment),
+            (Some(vec![vec![1], vec![1]]), ErrorCode::None),
+            (None, ErrorCode::None),
+        ] {
+            let request = create_partitions_request(vec![partitions_topic("foo", 3, assignments.clone())], true);
+            let ResponsePayload::CreatePartitions(resp) = broker.handle_request(request).await?.unwrap().payload else {
+                panic!("Expected CreatePartitions response");
+            };
+            assert_eq!(ErrorCode::from(resp.results[0].error_code), error, "{:?}", assignments);
+        }
+        // validate_only 면 파티션 수가 그대로
+        assert_eq!(broker.metadata_store.get_all_topic_metadata().await?[0].partitions.len(), 1);
+
+        // 메타데이터는 기록됐지만 디렉토리를 못 만들면 저장소 에러
+        let request = create_partitions_request(vec![partitions_topic("foo", 2, None)], false);
+        let ResponsePayload::CreatePartitions(resp) = broker.handle_request(request).await?.unwrap().payload else {
+            panic!("Expected CreatePartitions response");
+        };
+        assert_eq!(ErrorCode::from(resp.results[0].error_code), ErrorCode::KafkaStorageError);
+        Ok(())
+    }
+}
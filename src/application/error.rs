@@ -1,3 +1,4 @@
+use crate::adapters::incoming::protocol::messages::ErrorCode;
 use crate::domain::error::DomainError;
 use thiserror::Error;
 
@@ -9,6 +10,40 @@ pub enum ApplicationError {
     Io(#[from] std::io::Error),
     #[error("Protocol error: {0}")]
     Protocol(String),
+    #[error("Invalid config: {0}")]
+    Config(String),
 }
 
-pub type Result<T> = std::result::Result<T, ApplicationError>; 
\ No newline at end of file
+pub type Result<T> = std::result::Result<T, ApplicationError>;
+/// 처리 중 생긴 에러를 응답에 실을 Kafka 에러 코드로 바꿈
+/// 저장소 IO 실패는 클라이언트가 재시도할 수 있도록 KAFKA_STORAGE_ERROR
+impl From<&ApplicationError> for ErrorCode {
+    fn from(error: &ApplicationError) -> Self {
+        match error {
+            ApplicationError::Domain(DomainError::OffsetOutOfRange(_)) => ErrorCode::OffsetOutOfRange,
+            ApplicationError::Domain(DomainError::UnsupportedVersion) => ErrorCode::UnsupportedVersion,
+            ApplicationError::Domain(DomainError::UnsupportedCompressionType) => ErrorCode::UnsupportedCompressionType,
+            ApplicationError::Domain(DomainError::TopicAlreadyExists(_)) => ErrorCode::TopicAlreadyExists,
+            ApplicationError::Domain(DomainError::UnknownTopicId(_)) => ErrorCode::UnknownTopicId,
+            ApplicationError::Domain(DomainError::UnknownTopicOrPartition(_)) => ErrorCode::UnknownTopicOrPartition,
+            ApplicationError::Domain(DomainError::InvalidRequest | DomainError::InvalidProtocol(_)) => ErrorCode::InvalidRequest,
+            ApplicationError::Io(_) => ErrorCode::KafkaStorageError,
+            ApplicationError::Protocol(_) => ErrorCode::InvalidRequest,
+            ApplicationError::Config(_) => ErrorCode::InvalidConfig,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_application_error_to_error_code() {
+        let io = ApplicationError::Io(std::io::Error::other("disk"));
+        assert_eq!(ErrorCode::from(&io), ErrorCode::KafkaStorageError);
+        assert!(ErrorCode::from(&io).is_retriable());
+        assert_eq!(ErrorCode::from(&ApplicationError::Domain(DomainError::OffsetOutOfRange(5))), ErrorCode::OffsetOutOfRange);
+        assert_eq!(ErrorCode::from(&ApplicationError::Protocol("bad".to_string())), ErrorCode::InvalidRequest);
+    }
+}
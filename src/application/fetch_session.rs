@@ -0,0 +1,352 @@
+use crate::adapters::incoming::protocol::messages::{
+    ErrorCode, FetchPartition, FetchRequest, FetchTopic, FetchableTopicResponse,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 새 세션을 만들자는 epoch
+pub const INITIAL_EPOCH: i32 = 0;
+/// 세션을 쓰지 않거나 닫자는 epoch
+pub const FINAL_EPOCH: i32 = -1;
+/// max.incremental.fetch.session.cache.slots 기본값
+pub const DEFAULT_MAX_SESSIONS: usize = 1000;
+/// 이 시간 안에 쓰인 세션은 더 큰 세션에게만 자리를 내줌
+const MIN_EVICTION_AGE: Duration = Duration::from_secs(120);
+
+/// (topic id, topic 이름, partition). 버전에 따라 둘 중 하나만 채워져 있음
+type SessionPartitionKey = ([u8; 16], String, i32);
+
+/// 세션에 속한 파티션의 마지막 fetch 파라미터와 마지막으로 응답한 offset 들
+struct CachedPartition {
+    fetch: FetchPartition,
+    high_watermark: i64,
+    last_stable_offset: i64,
+    log_start_offset: i64,
+    /// 아직 한 번도 응답에 포함되지 않았으면 false
+    sent: bool,
+}
+
+struct FetchSession {
+    /// 다음 요청에서 기대하는 epoch
+    epoch: i32,
+    partitions: BTreeMap<SessionPartitionKey, CachedPartition>,
+    last_used: Instant,
+}
+
+/// 이번 Fetch 가 어떤 세션에서 어떤 파티션들을 읽어야 하는지
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchSessionContext {
+    /// 응답에 실을 session id. 세션이 없으면 0
+    pub session_id: i32,
+    /// true 면 바뀐 파티션만 응답
+    pub incremental: bool,
+    pub topics: Vec<FetchTopic>,
+}
+
+/// KIP-227 증분 fetch 세션 캐시
+pub struct FetchSessionCache {
+    max_sessions: usize,
+    sessions: Mutex<HashMap<i32, FetchSession>>,
+}
+
+impl FetchSessionCache {
+    pub fn new(max_sessions: usize) -> Self {
+        Self {
+            max_sessions,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// session_id / session_epoch 에 따라 세션을 만들거나, 닫거나, 갱신하고 읽을 파티션 목록을 돌려줌
+    pub fn resolve(&self, request: &FetchRequest) -> Result<FetchSessionContext, ErrorCode> {
+        let mut sessions = self.sessions.lock().unwrap();
+        match (request.session_id, request.session_epoch) {
+            (0, FINAL_EPOCH) => Ok(Self::sessionless(request)),
+            (0, INITIAL_EPOCH) => {
+                let session_id = self.create_session(&mut sessions, request);
+                println!("[DEBUG] Created fetch session {} with {} topics", session_id, request.topics.len());
+                Ok(FetchSessionContext {
+                    session_id,
+                    incremental: false,
+                    topics: request.topics.clone(),
+                })
+            },
+            (0, _) => Err(ErrorCode::InvalidFetchSessionEpoch),
+            (session_id, FINAL_EPOCH) => {
+                println!("[DEBUG] Closing fetch session {}", session_id);
+                sessions.remove(&session_id);
+                Ok(Self::sessionless(request))
+            },
+            (session_id, epoch) => {
+                let session = sessions.get_mut(&session_id).ok_or(ErrorCode::FetchSessionIdNotFound)?;
+                if session.epoch != epoch {
+                    println!("[DEBUG] Fetch session {} expected epoch {}, got {}", session_id, session.epoch, epoch);
+                    return Err(ErrorCode::InvalidFetchSessionEpoch);
+                }
+
+                for topic in &request.topics {
+                    for partition in &topic.partitions {
+                        let key = (topic.topic_id, topic.topic.clone(), partition.partition);
+                        match session.partitions.get_mut(&key) {
+                            Some(cached) => cached.fetch = partition.clone(),
+                            None => {
+                                session.partitions.insert(key, CachedPartition::new(partition.clone()));
+                            },
+                        }
+                    }
+                }
+                for forgotten in &request.forgotten_topics_data {
+                    for partition in &forgotten.partitions {
+                        session.partitions.remove(&(forgotten.topic_id, forgotten.topic.clone(), *partition));
+                    }
+                }
+                session.epoch = next_epoch(epoch);
+                session.last_used = Instant::now();
+
+                Ok(FetchSessionContext {
+                    session_id,
+                    incremental: true,
+                    topics: session.topics(),
+                })
+            },
+        }
+    }
+
+    /// 응답한 offset 들을 세션에 기록하고, 증분 세션이면 바뀐 파티션만 남김
+    pub fn complete(&self, context: &FetchSessionContext, responses: Vec<FetchableTopicResponse>) -> Vec<FetchableTopicResponse> {
+        if context.session_id == 0 {
+            return responses;
+        }
+        let mut sessions = self.sessions.lock().unwrap();
+        let Some(session) = sessions.get_mut(&context.session_id) else {
+            return responses;
+        };
+
+        responses.into_iter()
+            .filter_map(|mut topic| {
+                topic.partitions.retain(|partition| {
+                    match session.partitions.get_mut(&(topic.topic_id, topic.topic.clone(), partition.partition_index)) {
+                        Some(cached) => {
+                            let changed = !cached.sent
+                                || partition.records.is_some()
+                                || partition.error_code != i16::from(ErrorCode::None)
+                                || cached.high_watermark != partition.high_watermark
+                                || cached.last_stable_offset != partition.last_stable_offset
+                                || cached.log_start_offset != partition.log_start_offset;
+                            cached.high_watermark = partition.high_watermark;
+                            cached.last_stable_offset = partition.last_stable_offset;
+                            cached.log_start_offset = partition.log_start_offset;
+                            cached.sent = true;
+                            !context.incremental || changed
+                        },
+                        None => true,
+                    }
+                });
+                (!topic.partitions.is_empty()).then_some(topic)
+            })
+            .collect()
+    }
+
+    fn sessionless(request: &FetchRequest) -> FetchSessionContext {
+        FetchSessionContext {
+            session_id: 0,
+            incremental: false,
+            topics: request.topics.clone(),
+        }
+    }
+
+    /// 새 세션을 등록하고 id 를 반환. 자리가 없고 비울 세션도 없으면 0 (세션 없이 응답)
+    fn create_session(&self, sessions: &mut HashMap<i32, FetchSession>, request: &FetchRequest) -> i32 {
+        let partitions: BTreeMap<SessionPartitionKey, CachedPartition> = request.topics.iter()
+            .flat_map(|topic| topic.partitions.iter().map(move |p| ((topic.topic_id, topic.topic.clone(), p.partition), CachedPartition::new(p.clone()))))
+            .collect();
+
+        if sessions.len() >= self.max_sessions && !Self::evict_one(sessions, partitions.len()) {
+            println!("[DEBUG] Fetch session cache is full ({} sessions)", sessions.len());
+            return 0;
+        }
+
+        let session_id = loop {
+            let candidate = (uuid::Uuid::new_v4().as_u128() as i32) & i32::MAX;
+            if candidate != 0 && !sessions.contains_key(&candidate) {
+                break candidate;
+            }
+        };
+        sessions.insert(session_id, FetchSession {
+            epoch: next_epoch(INITIAL_EPOCH),
+            partitions,
+            last_used: Instant::now(),
+        });
+        session_id
+    }
+
+    /// 가장 오래 안 쓰인 세션이 충분히 오래됐으면 그걸, 아니면 새 세션보다 작은 세션 중 가장 작은 걸 비움
+    fn evict_one(sessions: &mut HashMap<i32, FetchSession>, new_session_size: usize) -> bool {
+        let now = Instant::now();
+        let stale = sessions.iter()
+            .min_by_key(|(_, session)| session.last_used)
+            .filter(|(_, session)| now.duration_since(session.last_used) >= MIN_EVICTION_AGE)
+            .map(|(id, _)| *id);
+        let victim = stale.or_else(|| sessions.iter()
+            .filter(|(_, session)| session.partitions.len() < new_session_size)
+            .min_by_key(|(_, session)| (session.partitions.len(), session.last_used))
+            .map(|(id, _)| *id));
+
+        match victim {
+            Some(id) => {
+                println!("[DEBUG] Evicting fetch session {}", id);
+                sessions.remove(&id);
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+impl Default for FetchSessionCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_SESSIONS)
+    }
+}
+
+impl CachedPartition {
+    fn new(fetch: FetchPartition) -> Self {
+        Self {
+            fetch,
+            high_watermark: -1,
+            last_stable_offset: -1,
+            log_start_offset: -1,
+            sent: false,
+        }
+    }
+}
+
+impl FetchSession {
+    /// 세션의 파티션들을 토픽별로 묶음
+    fn topics(&self) -> Vec<FetchTopic> {
+        let mut topics: Vec<FetchTopic> = Vec::new();
+        for ((topic_id, name, _), cached) in &self.partitions {
+            match topics.last_mut() {
+                Some(topic) if topic.topic_id == *topic_id && topic.topic == *name => topic.partitions.push(cached.fetch.clone()),
+                _ => topics.push(FetchTopic {
+                    topic: name.clone(),
+                    topic_id: *topic_id,
+                    partitions: vec![cached.fetch.clone()],
+                }),
+            }
+        }
+        topics
+    }
+}
+
+/// epoch 는 1 부터 증가하고 i32::MAX 다음엔 다시 1
+fn next_epoch(epoch: i32) -> i32 {
+    if epoch == i32::MAX { 1 } else { epoch + 1 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::incoming::protocol::messages::{FetchablePartitionResponse, ForgottenTopic};
+
+    fn partition(index: i32, fetch_offset: i64) -> FetchPartition {
+        FetchPartition {
+            partition: index,
+            current_leader_epoch: -1,
+            fetch_offset,
+            last_fetched_epoch: -1,
+            log_start_offset: -1,
+            partition_max_bytes: 1024,
+        }
+    }
+
+    fn request(session_id: i32, session_epoch: i32, partitions: Vec<FetchPartition>, forgotten: Vec<i32>) -> FetchRequest {
+        FetchRequest {
+            replica_id: -1,
+            max_wait_ms: 0,
+            min_bytes: 1,
+            max_bytes: 1024,
+            isolation_level: 0,
+            session_id,
+            session_epoch,
+            topics: if partitions.is_empty() { vec![] } else { vec![FetchTopic { topic: String::new(), topic_id: [1; 16], partitions }] },
+            forgotten_topics_data: if forgotten.is_empty() {
+                vec![]
+            } else {
+                vec![ForgottenTopic { topic: String::new(), topic_id: [1; 16], partitions: forgotten }]
+            },
+            rack_id: String::new(),
+        }
+    }
+
+    fn response(partitions: Vec<(i32, i64, bool)>) -> Vec<FetchableTopicResponse> {
+        vec![FetchableTopicResponse {
+            topic: String::new(),
+            topic_id: [1; 16],
+            partitions: partitions.into_iter()
+                .map(|(index, high_watermark, has_records)| FetchablePartitionResponse {
+                    partition_index: index,
+                    error_code: 0,
+                    high_watermark,
+                    last_stable_offset: high_watermark,
+                    log_start_offset: 0,
+                    records: has_records.then(|| vec![0; 10]),
+                    ..FetchablePartitionResponse::error(index, 0)
+                })
+                .collect(),
+        }]
+    }
+
+    #[test]
+    fn test_incremental_fetch_session() {
+        let cache = FetchSessionCache::new(10);
+
+        let full = cache.resolve(&request(0, INITIAL_EPOCH, vec![partition(0, 0), partition(1, 0)], vec![])).unwrap();
+        assert_ne!(full.session_id, 0);
+        assert!(!full.incremental);
+        let sent = cache.complete(&full, response(vec![(0, 5, true), (1, 0, false)]));
+        assert_eq!(sent[0].partitions.len(), 2);
+
+        // 요청에 파티션이 없어도 세션의 파티션 전체를 읽고, 바뀐 것만 응답
+        let incremental = cache.resolve(&request(full.session_id, 1, vec![], vec![])).unwrap();
+        assert!(incremental.incremental);
+        assert_eq!(incremental.topics[0].partitions.len(), 2);
+        let sent = cache.complete(&incremental, response(vec![(0, 5, false), (1, 3, false)]));
+        assert_eq!(sent[0].partitions.len(), 1);
+        assert_eq!(sent[0].partitions[0].partition_index, 1);
+
+        // 아무것도 안 바뀌면 토픽도 빠짐
+        let incremental = cache.resolve(&request(full.session_id, 2, vec![partition(0, 5)], vec![1])).unwrap();
+        assert_eq!(incremental.topics[0].partitions, vec![partition(0, 5)]);
+        assert!(cache.complete(&incremental, response(vec![(0, 5, false)])).is_empty());
+
+        // epoch 가 맞지 않거나 세션이 없으면 에러
+        assert_eq!(cache.resolve(&request(full.session_id, 2, vec![], vec![])), Err(ErrorCode::InvalidFetchSessionEpoch));
+        assert_eq!(cache.resolve(&request(full.session_id + 1, 1, vec![], vec![])), Err(ErrorCode::FetchSessionIdNotFound));
+        assert_eq!(cache.resolve(&request(0, 3, vec![], vec![])), Err(ErrorCode::InvalidFetchSessionEpoch));
+
+        // FINAL_EPOCH 로 세션을 닫으면 이후 요청은 세션을 찾지 못함
+        let closed = cache.resolve(&request(full.session_id, FINAL_EPOCH, vec![partition(0, 5)], vec![])).unwrap();
+        assert_eq!(closed.session_id, 0);
+        assert_eq!(cache.resolve(&request(full.session_id, 3, vec![], vec![])), Err(ErrorCode::FetchSessionIdNotFound));
+    }
+
+    #[test]
+    fn test_fetch_session_cache_eviction() {
+        let cache = FetchSessionCache::new(1);
+
+        let small = cache.resolve(&request(0, INITIAL_EPOCH, vec![partition(0, 0)], vec![])).unwrap();
+        assert_ne!(small.session_id, 0);
+
+        // 최근에 쓰인 세션은 같거나 작은 세션에게 자리를 내주지 않음
+        let rejected = cache.resolve(&request(0, INITIAL_EPOCH, vec![partition(1, 0)], vec![])).unwrap();
+        assert_eq!(rejected.session_id, 0);
+
+        // 더 큰 세션은 작은 세션을 밀어냄
+        let large = cache.resolve(&request(0, INITIAL_EPOCH, vec![partition(0, 0), partition(1, 0)], vec![])).unwrap();
+        assert_ne!(large.session_id, 0);
+        assert_eq!(cache.resolve(&request(small.session_id, 1, vec![], vec![])), Err(ErrorCode::FetchSessionIdNotFound));
+        assert!(cache.resolve(&request(large.session_id, 1, vec![], vec![])).is_ok());
+    }
+}
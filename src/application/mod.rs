@@ -1,4 +1,6 @@
 pub mod broker;
 pub mod error;
+pub mod fetch_session;
+pub mod purgatory;
 
 pub use error::{ApplicationError, Result}; 
\ No newline at end of file
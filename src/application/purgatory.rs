@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+/// (토픽 이름, 파티션)
+pub type TopicPartition = (String, i32);
+
+/// 조건이 채워질 때까지 응답을 미뤄둔 Fetch 들이 기다리는 곳
+/// Fetch 는 요청한 파티션들에 자신의 Notify 를 걸어두고, Produce 가 그 파티션에 append 하면 깨움
+pub struct FetchPurgatory {
+    watchers: Mutex<HashMap<TopicPartition, Vec<Weak<Notify>>>>,
+}
+
+/// 하나의 미뤄진 Fetch. 드롭되면 watcher 목록에서 자연스럽게 빠짐 (Weak)
+pub struct DelayedFetch {
+    notify: Arc<Notify>,
+    deadline: Instant,
+}
+
+impl FetchPurgatory {
+    pub fn new() -> Self {
+        Self {
+            watchers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 파티션들을 지켜보기 시작. 데이터를 확인하기 전에 호출해야 그 사이의 append 를 놓치지 않음
+    pub fn watch(&self, partitions: &[TopicPartition], max_wait_ms: i32) -> DelayedFetch {
+        let notify = Arc::new(Notify::new());
+        let mut watchers = self.watchers.lock().unwrap();
+        for key in partitions {
+            let list = watchers.entry(key.clone()).or_default();
+            list.retain(|watcher| watcher.strong_count() > 0);
+            list.push(Arc::downgrade(&notify));
+        }
+
+        DelayedFetch {
+            notify,
+            deadline: Instant::now() + Duration::from_millis(max_wait_ms.max(0) as u64),
+        }
+    }
+
+    /// 파티션에 새 데이터가 들어왔음을 기다리는 Fetch 들에게 알림
+    pub fn notify(&self, topic_name: &str, partition: i32) {
+        let mut watchers = self.watchers.lock().unwrap();
+        let key = (topic_name.to_string(), partition);
+        if let Some(list) = watchers.get_mut(&key) {
+            list.retain(|watcher| match watcher.upgrade() {
+                Some(notify) => {
+                    notify.notify_one();
+                    true
+                },
+                None => false,
+            });
+            if list.is_empty() {
+                watchers.remove(&key);
+            }
+        }
+    }
+}
+
+impl Default for FetchPurgatory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DelayedFetch {
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+
+    /// 지켜보는 파티션에 append 가 있거나 max_wait_ms 가 지날 때까지 대기
+    /// append 로 깨어났으면 true
+    pub async fn wait(&self) -> bool {
+        tokio::time::timeout_at(self.deadline, self.notify.notified()).await.is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(topic: &str, partition: i32) -> TopicPartition {
+        (topic.to_string(), partition)
+    }
+
+    #[tokio::test]
+    async fn test_delayed_fetch_woken_by_append() {
+        let purgatory = Arc::new(FetchPurgatory::new());
+        let delayed = purgatory.watch(&[key("foo", 0), key("foo", 1)], 10_000);
+
+        // 대기 전에 들어온 append 도 놓치지 않아야 함
+        purgatory.notify("foo", 1);
+        assert!(delayed.wait().await);
+
+        let notifier = purgatory.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            notifier.notify("foo", 0);
+        });
+        assert!(delayed.wait().await);
+        assert!(!delayed.is_expired());
+    }
+
+    #[tokio::test]
+    async fn test_delayed_fetch_expires() {
+        let purgatory = FetchPurgatory::new();
+        let delayed = purgatory.watch(&[key("foo", 0)], 20);
+
+        // 다른 파티션의 append 는 상관없음
+        purgatory.notify("foo", 1);
+        assert!(!delayed.wait().await);
+        assert!(delayed.is_expired());
+
+        drop(delayed);
+        purgatory.notify("foo", 0);
+        assert!(purgatory.watchers.lock().unwrap().is_empty());
+    }
+}
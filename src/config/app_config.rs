@@ -1,31 +1,53 @@
 use std::sync::Arc;
-use std::path::PathBuf;
+use std::time::Duration;
 use crate::application::broker::KafkaBroker;
 use crate::adapters::outgoing::disk_store::DiskMessageStore;
 use crate::adapters::outgoing::kraft_metadata_store::KraftMetadataStore;
 use crate::ports::incoming::message_handler::MessageHandler;
 use crate::adapters::incoming::kafka_protocol_parser::KafkaProtocolParser;
+use crate::config::server_config::ServerConfig;
+use crate::ports::outgoing::metadata_store::MetadataStore;
+use crate::Result;
 
 pub struct AppConfig {
     pub broker: Arc<dyn MessageHandler>,
     pub protocol_parser: KafkaProtocolParser,
+    pub listener_address: String,
+    pub socket_request_max_bytes: i32,
 }
 
 impl AppConfig {
-    pub fn new(server_properties_path: &str) -> Self {
-        let log_dir = PathBuf::from("/tmp/kraft-combined-logs");
-        
+    pub async fn new(server_config: &ServerConfig) -> Result<Self> {
+        println!("[CONFIG] {:?}", server_config);
+
         // Initialize stores
-        let message_store = Box::new(DiskMessageStore::new(log_dir.clone()));
-        let metadata_store = Box::new(KraftMetadataStore::new(log_dir));
+        let metadata_store = Box::new(KraftMetadataStore::new(server_config.metadata_log_dir.clone()));
+        let mut message_store = DiskMessageStore::with_config(server_config.log_dir.clone(), server_config.log_config());
+        // 메타데이터 로그에 기록된 토픽 설정을 다시 적용
+        for topic in metadata_store.get_all_topic_metadata().await? {
+            let configs = metadata_store.get_topic_configs(&topic.name).await?;
+            if configs.is_empty() {
+                continue;
+            }
+            match server_config.log_config().with_topic_configs(&configs) {
+                Ok(config) => message_store = message_store.with_topic_config(&topic.name, config),
+                Err(e) => println!("[DEBUG] Ignoring configs of topic {}: {}", topic.name, e),
+            }
+        }
+        let message_store = Box::new(message_store);
+        message_store.spawn_deleted_log_cleanup();
+        message_store.spawn_retention_check(Duration::from_millis(server_config.log_retention_check_interval_ms));
 
         // Initialize broker with both stores
-        let broker = Arc::new(KafkaBroker::new(message_store, metadata_store));
+        let broker = KafkaBroker::new(message_store, metadata_store, server_config.broker_metadata())
+            .with_num_partitions(server_config.num_partitions);
         let protocol_parser = KafkaProtocolParser::new();
 
-        Self {
-            broker,
+        Ok(Self {
+            broker: Arc::new(broker),
             protocol_parser,
-        }
+            listener_address: server_config.bind_address(),
+            socket_request_max_bytes: server_config.socket_request_max_bytes,
+        })
     }
-} 
\ No newline at end of file
+}
@@ -1,6 +1,9 @@
 mod app_config;
+pub mod server_config;
+#[cfg(test)]
 mod test_config;
 
 pub use app_config::AppConfig;
+pub use server_config::{CommandLine, ServerConfig};
 #[cfg(test)]
-pub use test_config::create_test_config; 
\ No newline at end of file
+pub use test_config::create_test_config;
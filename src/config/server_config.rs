@@ -0,0 +1,510 @@
+use crate::adapters::incoming::protocol::constants::{DEFAULT_NUM_PARTITIONS, MAX_PARTITIONS_PER_TOPIC};
+use crate::adapters::incoming::tcp_adapter::DEFAULT_SOCKET_REQUEST_MAX_BYTES;
+use crate::adapters::outgoing::log::partition_log::{LogConfig, MAX_SEGMENT_BYTES, MIN_SEGMENT_BYTES};
+use crate::adapters::outgoing::protocol::compression::CompressionConfig;
+use crate::adapters::outgoing::protocol::record_batch::TimestampType;
+use crate::application::error::ApplicationError;
+use crate::domain::message::BrokerMetadata;
+use crate::Result;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// log.dirs 가 없을 때의 로그 디렉토리
+const DEFAULT_LOG_DIR: &str = "/tmp/kraft-combined-logs";
+const DEFAULT_LISTENERS: &str = "PLAINTEXT://:9092";
+/// advertised.listeners 의 host 가 비어있을 때 클라이언트에게 알려줄 host
+const DEFAULT_ADVERTISED_HOST: &str = "localhost";
+
+const HOUR_MS: i64 = 60 * 60 * 1000;
+const DEFAULT_RETENTION_CHECK_INTERVAL_MS: u64 = 5 * 60 * 1000;
+
+/// listeners / advertised.listeners 의 항목 하나 (`NAME://host:port`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Listener {
+    pub name: String,
+    /// 비어있으면 모든 인터페이스
+    pub host: String,
+    pub port: u16,
+}
+
+/// server.properties 를 타입에 맞게 해석하고 검증한 브로커 설정
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerConfig {
+    pub node_id: i32,
+    /// controller.listener.names 에 속한 리스너는 뺀, 클라이언트 요청을 받는 리스너들
+    pub listeners: Vec<Listener>,
+    pub advertised_listeners: Vec<Listener>,
+    pub rack: Option<String>,
+    pub log_dir: PathBuf,
+    /// metadata.log.dir, 없으면 log_dir
+    pub metadata_log_dir: PathBuf,
+    pub num_partitions: u32,
+    /// -1 이면 무제한 (log.retention.ms > minutes > hours 순으로 적용)
+    pub log_retention_ms: i64,
+    pub log_retention_bytes: i64,
+    /// 오래된 세그먼트를 지울지 검사하는 주기
+    pub log_retention_check_interval_ms: u64,
+    pub log_segment_bytes: u64,
+    pub log_roll_ms: i64,
+    pub log_index_interval_bytes: u64,
+    pub compression_type: CompressionConfig,
+    pub log_message_timestamp_type: TimestampType,
+    pub socket_request_max_bytes: i32,
+}
+
+impl Listener {
+    fn parse(value: &str) -> Result<Self> {
+        let invalid = || config_error(format!("invalid listener: {}", value));
+        let (name, address) = value.split_once("://").ok_or_else(invalid)?;
+        let (host, port) = address.rsplit_once(':').ok_or_else(invalid)?;
+        if name.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            // IPv6 주소는 [::1] 처럼 대괄호로 감쌈
+            host: host.trim_start_matches('[').trim_end_matches(']').to_string(),
+            port: port.parse().map_err(|_| invalid())?,
+        })
+    }
+
+    /// TcpListener::bind 에 넘길 주소
+    pub fn bind_address(&self) -> String {
+        match self.host.as_str() {
+            "" => format!("0.0.0.0:{}", self.port),
+            host if host.contains(':') => format!("[{}]:{}", host, self.port),
+            host => format!("{}:{}", host, self.port),
+        }
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self::from_properties(&HashMap::new()).expect("default config is valid")
+    }
+}
+
+impl ServerConfig {
+    /// properties 파일을 읽고 CLI 의 --override 를 덮어써서 설정을 만듦. path 가 없으면 기본값 + override
+    pub fn load(path: Option<&Path>, overrides: &[(String, String)]) -> Result<Self> {
+        let mut properties = match path {
+            Some(path) => {
+                let content = std::fs::read_to_string(path).map_err(ApplicationError::Io)?;
+                parse_properties(&content)
+            },
+            None => HashMap::new(),
+        };
+        for (key, value) in overrides {
+            properties.insert(key.clone(), value.clone());
+        }
+        Self::from_properties(&properties)
+    }
+
+    pub fn from_properties(properties: &HashMap<String, String>) -> Result<Self> {
+        let get = |key: &str| properties.get(key).map(|value| value.trim());
+
+        let node_id = parse_value::<i32>(properties, "node.id")?.unwrap_or(1);
+        if node_id < 0 {
+            return Err(config_error(format!("node.id must be non-negative: {}", node_id)));
+        }
+
+        let controller_listener_names: Vec<&str> = get("controller.listener.names")
+            .map(|names| split_list(names).collect())
+            .unwrap_or_default();
+        let listeners: Vec<Listener> = parse_listeners(get("listeners").unwrap_or(DEFAULT_LISTENERS))?
+            .into_iter()
+            .filter(|listener| !controller_listener_names.contains(&listener.name.as_str()))
+            .collect();
+        if listeners.is_empty() {
+            return Err(config_error("listeners must contain a non-controller listener".to_string()));
+        }
+        let advertised_listeners = match get("advertised.listeners") {
+            Some(value) => parse_listeners(value)?,
+            None => listeners.clone(),
+        };
+        if advertised_listeners.is_empty() {
+            return Err(config_error("advertised.listeners must not be empty".to_string()));
+        }
+
+        // log.dirs 가 log.dir 보다 우선
+        let log_dirs: Vec<PathBuf> = split_list(get("log.dirs").or(get("log.dir")).unwrap_or(DEFAULT_LOG_DIR))
+            .map(PathBuf::from)
+            .collect();
+        let log_dir = match log_dirs.as_slice() {
+            [log_dir] => log_dir.clone(),
+            [] => return Err(config_error("log.dirs must not be empty".to_string())),
+            _ => return Err(config_error(format!("only one log directory is supported: {:?}", log_dirs))),
+        };
+        let metadata_log_dir = get("metadata.log.dir").map_or_else(|| log_dir.clone(), PathBuf::from);
+
+        let num_partitions = parse_value::<u32>(properties, "num.partitions")?.unwrap_or(DEFAULT_NUM_PARTITIONS);
+        if num_partitions == 0 {
+            return Err(config_error("num.partitions must be at least 1".to_string()));
+        }
+        if num_partitions > MAX_PARTITIONS_PER_TOPIC as u32 {
+            return Err(config_error(format!("num.partitions must be at most {}: {}", MAX_PARTITIONS_PER_TOPIC, num_partitions)));
+        }
+
+        let log_retention_ms = match (
+            parse_value::<i64>(properties, "log.retention.ms")?,
+            parse_value::<i64>(properties, "log.retention.minutes")?,
+            parse_value::<i64>(properties, "log.retention.hours")?,
+        ) {
+            (Some(ms), _, _) => ms,
+            (None, Some(minutes), _) => minutes.saturating_mul(60 * 1000),
+            (None, None, Some(hours)) => hours.saturating_mul(HOUR_MS),
+            (None, None, None) => 168 * HOUR_MS,
+        };
+        // 음수는 모두 무제한으로 취급 (Kafka 와 같음)
+        let log_retention_ms = if log_retention_ms < 0 { -1 } else { log_retention_ms };
+        let log_retention_bytes = parse_value::<i64>(properties, "log.retention.bytes")?.unwrap_or(-1).max(-1);
+        let log_retention_check_interval_ms = parse_value::<u64>(properties, "log.retention.check.interval.ms")?
+            .unwrap_or(DEFAULT_RETENTION_CHECK_INTERVAL_MS);
+        if log_retention_check_interval_ms < 1 {
+            return Err(config_error(format!("log.retention.check.interval.ms must be at least 1: {}", log_retention_check_interval_ms)));
+        }
+
+        let defaults = LogConfig::default();
+        let log_segment_bytes = parse_value::<u64>(properties, "log.segment.bytes")?.unwrap_or(defaults.segment_bytes);
+        if log_segment_bytes < MIN_SEGMENT_BYTES {
+            return Err(config_error(format!("log.segment.bytes must be at least {}: {}", MIN_SEGMENT_BYTES, log_segment_bytes)));
+        }
+        if log_segment_bytes > MAX_SEGMENT_BYTES {
+            return Err(config_error(format!("log.segment.bytes must be at most {}: {}", MAX_SEGMENT_BYTES, log_segment_bytes)));
+        }
+        let log_roll_ms = match (parse_value::<i64>(properties, "log.roll.ms")?, parse_value::<i64>(properties, "log.roll.hours")?) {
+            (Some(ms), _) => ms,
+            (None, Some(hours)) => hours.saturating_mul(HOUR_MS),
+            (None, None) => defaults.segment_ms,
+        };
+        if log_roll_ms < 1 {
+            return Err(config_error(format!("log.roll.ms must be at least 1: {}", log_roll_ms)));
+        }
+        let log_index_interval_bytes = parse_value::<u64>(properties, "log.index.interval.bytes")?
+            .unwrap_or(defaults.index_interval_bytes);
+
+        let compression_type = match get("compression.type") {
+            Some(value) => value.parse::<CompressionConfig>()
+                .map_err(|_| config_error(format!("invalid compression.type: {}", value)))?,
+            None => CompressionConfig::Producer,
+        };
+        let log_message_timestamp_type = match get("log.message.timestamp.type") {
+            Some(value) => value.parse::<TimestampType>()
+                .map_err(|_| config_error(format!("invalid log.message.timestamp.type: {}", value)))?,
+            None => TimestampType::CreateTime,
+        };
+        let socket_request_max_bytes = parse_value::<i32>(properties, "socket.request.max.bytes")?
+            .unwrap_or(DEFAULT_SOCKET_REQUEST_MAX_BYTES);
+        if socket_request_max_bytes < 1 {
+            return Err(config_error(format!("socket.request.max.bytes must be at least 1: {}", socket_request_max_bytes)));
+        }
+
+        Ok(Self {
+            node_id,
+            listeners,
+            advertised_listeners,
+            rack: get("broker.rack").filter(|rack| !rack.is_empty()).map(str::to_string),
+            log_dir,
+            metadata_log_dir,
+            num_partitions,
+            log_retention_ms,
+            log_retention_bytes,
+            log_retention_check_interval_ms,
+            log_segment_bytes,
+            log_roll_ms,
+            log_index_interval_bytes,
+            compression_type,
+            log_message_timestamp_type,
+            socket_request_max_bytes,
+        })
+    }
+
+    /// 클라이언트 요청을 받을 주소 (첫 번째 리스너)
+    pub fn bind_address(&self) -> String {
+        self.listeners[0].bind_address()
+    }
+
+    /// 메타데이터 응답으로 클라이언트에게 알려줄 이 브로커의 정보 (첫 번째 advertised listener)
+    pub fn broker_metadata(&self) -> BrokerMetadata {
+        let listener = &self.advertised_listeners[0];
+        BrokerMetadata {
+            node_id: self.node_id,
+            host: if listener.host.is_empty() { DEFAULT_ADVERTISED_HOST.to_string() } else { listener.host.clone() },
+            port: listener.port as i32,
+            rack: self.rack.clone(),
+        }
+    }
+
+    pub fn log_config(&self) -> LogConfig {
+        LogConfig {
+            segment_bytes: self.log_segment_bytes,
+            segment_ms: self.log_roll_ms,
+            index_interval_bytes: self.log_index_interval_bytes,
+            compression_type: self.compression_type,
+            timestamp_type: self.log_message_timestamp_type,
+            retention_ms: self.log_retention_ms,
+            retention_bytes: self.log_retention_bytes,
+        }
+    }
+}
+
+/// `[server.properties] [--override key=value ...]` 형태의 CLI 인자
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommandLine {
+    pub server_properties_path: Option<PathBuf>,
+    pub overrides: Vec<(String, String)>,
+}
+
+impl CommandLine {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut command_line = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--override" {
+                let value = args.next().ok_or_else(|| config_error("--override requires key=value".to_string()))?;
+                let (key, value) = value.split_once('=')
+                    .ok_or_else(|| config_error(format!("invalid --override: {}", value)))?;
+                command_line.overrides.push((key.trim().to_string(), value.trim().to_string()));
+            } else if command_line.server_properties_path.is_none() && !arg.starts_with("--") {
+                command_line.server_properties_path = Some(PathBuf::from(arg));
+            } else {
+                return Err(config_error(format!("unexpected argument: {}", arg)));
+            }
+        }
+        Ok(command_line)
+    }
+}
+
+/// Java properties 형식: `#` / `!` 주석, `=` / `:` / 공백 구분자, `\` 줄 이음과 이스케이프
+pub fn parse_properties(content: &str) -> HashMap<String, String> {
+    let mut properties = HashMap::new();
+    let mut lines = content.lines();
+    while let Some(line) = lines.next() {
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            continue;
+        }
+
+        // 홀수 개의 \ 로 끝나면 다음 줄 (앞 공백 제외) 과 이어짐
+        let mut logical = line.to_string();
+        while ends_with_continuation(&logical) {
+            logical.pop();
+            match lines.next() {
+                Some(next) => logical.push_str(next.trim_start()),
+                None => break,
+            }
+        }
+
+        let (key, value) = split_key_value(&logical);
+        properties.insert(unescape(key), unescape(value));
+    }
+    properties
+}
+
+fn ends_with_continuation(line: &str) -> bool {
+    line.chars().rev().take_while(|c| *c == '\\').count() % 2 == 1
+}
+
+/// 이스케이프되지 않은 첫 `=`, `:` 또는 공백에서 key 와 value 를 나눔
+fn split_key_value(line: &str) -> (&str, &str) {
+    let mut escaped = false;
+    let mut key_end = line.len();
+    for (i, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '=' || c == ':' || c.is_whitespace() {
+            key_end = i;
+            break;
+        }
+    }
+
+    let rest = line[key_end..].trim_start();
+    let rest = rest.strip_prefix(['=', ':']).map_or(rest, str::trim_start);
+    (&line[..key_end], rest)
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('f') => result.push('\x0c'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(c) => result.push(c),
+                    None => result.push_str(&hex),
+                }
+            },
+            Some(c) => result.push(c),
+            None => {},
+        }
+    }
+    result
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(str::trim).filter(|item| !item.is_empty())
+}
+
+fn parse_listeners(value: &str) -> Result<Vec<Listener>> {
+    split_list(value).map(Listener::parse).collect()
+}
+
+fn parse_value<T: FromStr>(properties: &HashMap<String, String>, key: &str) -> Result<Option<T>> {
+    properties.get(key)
+        .map(|value| value.trim().parse::<T>()
+            .map_err(|_| config_error(format!("invalid value for {}: {}", key, value))))
+        .transpose()
+}
+
+fn config_error(message: String) -> ApplicationError {
+    ApplicationError::Config(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::outgoing::protocol::record_batch::CompressionType;
+
+    fn properties(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn test_parse_properties() {
+        let parsed = parse_properties(concat!(
+            "# comment\n",
+            "! another comment\n",
+            "node.id=1\n",
+            "  listeners = PLAINTEXT://:9092,\\\n",
+            "      CONTROLLER://:9093\n",
+            "log.dirs:/tmp/logs\n",
+            "broker.rack us-east\\u002d1\n",
+            "key\\=with\\:separators=value\\\\\n",
+            "empty=\n",
+        ));
+        assert_eq!(parsed["node.id"], "1");
+        assert_eq!(parsed["listeners"], "PLAINTEXT://:9092,CONTROLLER://:9093");
+        assert_eq!(parsed["log.dirs"], "/tmp/logs");
+        assert_eq!(parsed["broker.rack"], "us-east-1");
+        assert_eq!(parsed["key=with:separators"], "value\\");
+        assert_eq!(parsed["empty"], "");
+        assert_eq!(parsed.len(), 6);
+    }
+
+    #[test]
+    fn test_server_config_from_properties() -> Result<()> {
+        let config = ServerConfig::from_properties(&properties(&[
+            ("node.id", "3"),
+            ("process.roles", "broker,controller"),
+            ("listeners", "PLAINTEXT://:9092,CONTROLLER://:9093"),
+            ("controller.listener.names", "CONTROLLER"),
+            ("advertised.listeners", "PLAINTEXT://broker-3.example.com:19092"),
+            ("log.dirs", "/tmp/kraft-combined-logs"),
+            ("num.partitions", "4"),
+            ("log.retention.minutes", "30"),
+            ("log.retention.hours", "1"),
+            ("log.retention.bytes", "1073741824"),
+            ("log.segment.bytes", "1048576"),
+            ("log.roll.hours", "2"),
+            ("compression.type", "zstd"),
+            ("log.message.timestamp.type", "LogAppendTime"),
+        ]))?;
+
+        assert_eq!(config.node_id, 3);
+        assert_eq!(config.listeners, vec![Listener { name: "PLAINTEXT".to_string(), host: String::new(), port: 9092 }]);
+        assert_eq!(config.bind_address(), "0.0.0.0:9092");
+        assert_eq!(config.broker_metadata(), BrokerMetadata {
+            node_id: 3,
+            host: "broker-3.example.com".to_string(),
+            port: 19092,
+            rack: None,
+        });
+        assert_eq!(config.log_dir, PathBuf::from("/tmp/kraft-combined-logs"));
+        assert_eq!(config.metadata_log_dir, config.log_dir);
+        assert_eq!(config.num_partitions, 4);
+        assert_eq!(config.log_retention_ms, 30 * 60 * 1000);
+        assert_eq!(config.log_retention_bytes, 1073741824);
+
+        let log_config = config.log_config();
+        assert_eq!(log_config.segment_bytes, 1048576);
+        assert_eq!(log_config.segment_ms, 2 * HOUR_MS);
+        assert_eq!(log_config.compression_type, CompressionConfig::Codec(CompressionType::Zstd));
+        assert_eq!(log_config.timestamp_type, TimestampType::LogAppendTime);
+        assert_eq!((log_config.retention_ms, log_config.retention_bytes), (30 * 60 * 1000, 1073741824));
+        Ok(())
+    }
+
+    #[test]
+    fn test_server_config_defaults_and_validation() {
+        let config = ServerConfig::default();
+        assert_eq!(config.node_id, 1);
+        assert_eq!(config.bind_address(), "0.0.0.0:9092");
+        assert_eq!(config.broker_metadata().host, "localhost");
+        assert_eq!(config.log_dir, PathBuf::from(DEFAULT_LOG_DIR));
+        assert_eq!(config.log_retention_ms, 168 * HOUR_MS);
+        assert_eq!(config.log_retention_bytes, -1);
+        assert_eq!(config.log_retention_check_interval_ms, DEFAULT_RETENTION_CHECK_INTERVAL_MS);
+        assert_eq!(config.socket_request_max_bytes, DEFAULT_SOCKET_REQUEST_MAX_BYTES);
+
+        for invalid in [
+            ("node.id", "-1"),
+            ("node.id", "one"),
+            ("num.partitions", "0"),
+            ("num.partitions", "10001"),
+            ("listeners", "localhost:9092"),
+            ("listeners", "CONTROLLER://:9093"),
+            ("log.dirs", "/a,/b"),
+            ("log.segment.bytes", "10"),
+            ("log.retention.check.interval.ms", "0"),
+            ("log.segment.bytes", "2147483648"),
+            ("compression.type", "brotli"),
+            ("log.message.timestamp.type", "NoTimestamp"),
+            ("socket.request.max.bytes", "0"),
+        ] {
+            let mut pairs = vec![invalid];
+            if invalid.0 == "listeners" {
+                pairs.push(("controller.listener.names", "CONTROLLER"));
+            }
+            let error = ServerConfig::from_properties(&properties(&pairs)).unwrap_err();
+            assert!(matches!(error, ApplicationError::Config(_)), "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_load_with_overrides() -> Result<()> {
+        let dir = tempfile::tempdir().map_err(ApplicationError::Io)?;
+        let path = dir.path().join("server.properties");
+        std::fs::write(&path, "node.id=1\nnum.partitions=2\n").map_err(ApplicationError::Io)?;
+
+        let command_line = CommandLine::parse([
+            path.to_string_lossy().to_string(),
+            "--override".to_string(),
+            "num.partitions=5".to_string(),
+            "--override".to_string(),
+            "listeners=PLAINTEXT://127.0.0.1:9093".to_string(),
+        ])?;
+        assert_eq!(command_line.server_properties_path.as_deref(), Some(path.as_path()));
+
+        let config = ServerConfig::load(command_line.server_properties_path.as_deref(), &command_line.overrides)?;
+        assert_eq!(config.num_partitions, 5);
+        assert_eq!(config.bind_address(), "127.0.0.1:9093");
+
+        assert!(CommandLine::parse(["--override".to_string()]).is_err());
+        assert!(CommandLine::parse(["--override".to_string(), "novalue".to_string()]).is_err());
+        assert!(ServerConfig::load(Some(&dir.path().join("missing.properties")), &[]).is_err());
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 use crate::ports::incoming::message_handler::MessageHandler;
 use crate::adapters::incoming::kafka_protocol_parser::KafkaProtocolParser;
+use crate::adapters::incoming::tcp_adapter::DEFAULT_SOCKET_REQUEST_MAX_BYTES;
 use crate::adapters::incoming::protocol::messages::{KafkaRequest, KafkaResponse};
 use crate::Result;
 use super::app_config::AppConfig;
@@ -15,7 +16,7 @@ impl MockMessageHandler {
 
 #[async_trait::async_trait]
 impl MessageHandler for MockMessageHandler {
-    async fn handle_request(&self, _request: KafkaRequest) -> Result<KafkaResponse> {
+    async fn handle_request(&self, _request: KafkaRequest) -> Result<Option<KafkaResponse>> {
         unimplemented!("Mock implementation")
     }
 }
@@ -24,5 +25,7 @@ pub fn create_test_config() -> AppConfig {
     AppConfig {
         broker: Arc::new(MockMessageHandler::new()),
         protocol_parser: KafkaProtocolParser::new(),
+        listener_address: "127.0.0.1:9092".to_string(),
+        socket_request_max_bytes: DEFAULT_SOCKET_REQUEST_MAX_BYTES,
     }
 } 
\ No newline at end of file
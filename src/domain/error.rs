@@ -3,6 +3,16 @@ pub enum DomainError {
     InvalidProtocol(String),
     InvalidRequest,
     UnsupportedVersion,
+    /// 요청한 offset 이 [log start, log end] 범위 밖
+    OffsetOutOfRange(i64),
+    /// 요청한 버전의 클라이언트가 읽을 수 없는 압축 코덱 (예: v4 미만 Fetch 의 zstd)
+    UnsupportedCompressionType,
+    /// 같은 이름의 토픽이 이미 있음
+    TopicAlreadyExists(String),
+    /// 이 ID 의 토픽이 없음
+    UnknownTopicId(String),
+    /// 이 이름의 토픽이 없음
+    UnknownTopicOrPartition(String),
 }
 
 impl std::fmt::Display for DomainError {
@@ -11,6 +21,11 @@ impl std::fmt::Display for DomainError {
             DomainError::InvalidProtocol(msg) => write!(f, "Protocol error: {}", msg),
             DomainError::InvalidRequest => write!(f, "Invalid request"),
             DomainError::UnsupportedVersion => write!(f, "Unsupported version"),
+            DomainError::OffsetOutOfRange(offset) => write!(f, "Offset out of range: {}", offset),
+            DomainError::UnsupportedCompressionType => write!(f, "Unsupported compression type"),
+            DomainError::TopicAlreadyExists(name) => write!(f, "Topic '{}' already exists", name),
+            DomainError::UnknownTopicId(id) => write!(f, "Unknown topic id: {}", id),
+            DomainError::UnknownTopicOrPartition(name) => write!(f, "Unknown topic: {}", name),
         }
     }
 }
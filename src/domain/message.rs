@@ -8,6 +8,50 @@ pub struct TopicMetadata {
     pub topic_authorized_operations: i32,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokerMetadata {
+    pub node_id: i32,
+    pub host: String,
+    pub port: i32,
+    pub rack: Option<String>,
+}
+
+/// 파티션 로그의 offset 경계
+/// 복제나 트랜잭션이 없으므로 high watermark 와 last stable offset 은 log end offset 과 같음
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PartitionOffsets {
+    pub log_start_offset: i64,
+    pub log_end_offset: i64,
+    pub high_watermark: i64,
+    pub last_stable_offset: i64,
+}
+
+impl PartitionOffsets {
+    pub fn new(log_start_offset: i64, log_end_offset: i64) -> Self {
+        Self {
+            log_start_offset,
+            log_end_offset,
+            high_watermark: log_end_offset,
+            last_stable_offset: log_end_offset,
+        }
+    }
+}
+
+/// 레코드를 추가한 결과: 첫 배치의 base offset 과 브로커가 찍은 append 시각
+/// 토픽이 CreateTime 이면 log_append_time 은 -1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LogAppendInfo {
+    pub base_offset: i64,
+    pub log_append_time: i64,
+}
+
+/// timestamp 조회 결과: 찾은 레코드의 timestamp 와 offset
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimestampOffset {
+    pub timestamp: i64,
+    pub offset: i64,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Partition {
     pub error_code: i16,
@@ -22,6 +66,7 @@ pub struct Partition {
 }
 
 impl Partition {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         error_code: i16,
         partition_index: u32,
@@ -1,22 +1,19 @@
-mod adapters;
-mod application;
-mod config;
-mod domain;
-mod ports;
-
-use crate::application::Result;
-use crate::config::AppConfig;
-use crate::adapters::incoming::tcp_adapter::TcpAdapter;
+use kafka_starter::Result;
+use kafka_starter::config::{AppConfig, CommandLine, ServerConfig};
+use kafka_starter::adapters::incoming::tcp_adapter::TcpAdapter;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let addr = "127.0.0.1:9092";
-    let config = AppConfig::new("server.properties");
+    // codecrafters-kafka [server.properties] [--override key=value ...]
+    let command_line = CommandLine::parse(std::env::args().skip(1))?;
+    let server_config = ServerConfig::load(command_line.server_properties_path.as_deref(), &command_line.overrides)?;
+    let config = AppConfig::new(&server_config).await?;
 
     let adapter = TcpAdapter::new(
-        addr,
+        &config.listener_address,
         config.broker,
         config.protocol_parser,
+        config.socket_request_max_bytes,
     ).await?;
 
     adapter.run().await?;
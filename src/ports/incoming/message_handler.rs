@@ -1,8 +1,40 @@
 use async_trait::async_trait;
 use crate::Result;
 use crate::adapters::incoming::protocol::messages::{KafkaRequest, KafkaResponse};
+use tokio::sync::oneshot;
 
 #[async_trait]
 pub trait MessageHandler: Send + Sync {
-    async fn handle_request(&self, request: KafkaRequest) -> Result<KafkaResponse>;
-} 
\ No newline at end of file
+    /// 응답을 보내지 않아야 하는 요청 (acks=0 Produce) 은 None 을 반환
+    async fn handle_request(&self, request: KafkaRequest) -> Result<Option<KafkaResponse>>;
+
+    /// handle_request 와 같지만, 응답을 purgatory 에서 기다려야 하면 기다리기 직전에 parked.park() 를 부름
+    /// 기본 구현은 기다리는 요청이 없는 핸들러용으로, 끝날 때까지 parked 를 들고 있음
+    async fn handle_parkable_request(&self, request: KafkaRequest, parked: ParkSignal) -> Result<Option<KafkaResponse>> {
+        let result = self.handle_request(request).await;
+        drop(parked);
+        result
+    }
+}
+
+/// 커넥션이 다음 요청으로 넘어가도 되는지 알리는 신호
+/// 요청이 purgatory 에 걸리거나 (park) 처리가 끝나서 드롭되면 수신 측이 깨어남
+pub struct ParkSignal(Option<oneshot::Sender<()>>);
+
+impl ParkSignal {
+    pub fn new() -> (Self, oneshot::Receiver<()>) {
+        let (tx, rx) = oneshot::channel();
+        (Self(Some(tx)), rx)
+    }
+
+    /// 기다리는 쪽이 없는 신호
+    pub fn none() -> Self {
+        Self(None)
+    }
+
+    pub fn park(&mut self) {
+        if let Some(tx) = self.0.take() {
+            let _ = tx.send(());
+        }
+    }
+}
@@ -1,9 +1,25 @@
 use async_trait::async_trait;
-use crate::adapters::incoming::protocol::messages::KafkaMessage;
+use crate::domain::message::{LogAppendInfo, PartitionOffsets, TimestampOffset};
 use crate::Result;
+use std::ops::Range;
 
 #[async_trait]
 pub trait MessageStore: Send + Sync {
-    async fn store_message(&self, message: KafkaMessage) -> Result<()>;
-    async fn read_messages(&self, topic_id: &str, partition: i32, offset: i64) -> Result<Option<Vec<u8>>>;
-} 
\ No newline at end of file
+    /// 새 토픽의 파티션들을 준비하고 토픽 설정 (compression.type, segment.bytes 등) 을 적용
+    async fn create_topic(&self, topic_name: &str, num_partitions: i32, configs: &[(String, String)]) -> Result<()>;
+    /// 기존 토픽에 새로 생긴 파티션들을 준비
+    async fn create_partitions(&self, topic_name: &str, partitions: Range<i32>) -> Result<()>;
+    /// 토픽의 파티션 로그들을 지움. 실제 파일 삭제는 백그라운드에서 이어질 수 있음
+    async fn delete_topic(&self, topic_name: &str) -> Result<()>;
+    /// 레코드 배치들을 파티션 로그 끝에 추가하고, 첫 배치에 할당된 base offset 과 append 시각을 반환
+    async fn store_message(&self, topic_name: &str, partition: i32, records: &[u8]) -> Result<LogAppendInfo>;
+    /// offset 을 포함하는 배치부터 max_bytes 안에 들어가는 온전한 배치들을 반환 (최소 한 배치)
+    /// offset 이 log end 와 같으면 None, 로그 범위 밖이면 DomainError::OffsetOutOfRange
+    async fn read_messages(&self, topic_id: &str, partition: i32, offset: i64, max_bytes: i32) -> Result<Option<Vec<u8>>>;
+    /// 파티션의 log start / log end / high watermark / last stable offset
+    async fn get_partition_offsets(&self, topic_name: &str, partition: i32) -> Result<PartitionOffsets>;
+    /// timestamp 가 주어진 값 이상인 첫 레코드. 없으면 None
+    async fn find_offset_by_timestamp(&self, topic_name: &str, partition: i32, timestamp: i64) -> Result<Option<TimestampOffset>>;
+    /// timestamp 가 가장 큰 레코드. 로그가 비었으면 None
+    async fn find_max_timestamp_offset(&self, topic_name: &str, partition: i32) -> Result<Option<TimestampOffset>>;
+}
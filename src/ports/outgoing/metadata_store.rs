@@ -2,8 +2,24 @@ use crate::domain::message::TopicMetadata;
 use crate::Result;
 use async_trait::async_trait;
 
+/// 클러스터 메타데이터를 읽는 getter 들과, 메타데이터 로그에 레코드를 붙이는 append 연산들
+/// append 가 끝나면 이후의 읽기는 그 변경을 반영한 상태를 봄
 #[async_trait]
 pub trait MetadataStore: Send + Sync {
     async fn get_topic_metadata_by_names(&self, topic_names: Vec<String>) -> Result<Option<Vec<TopicMetadata>>>;
     async fn get_topic_metadata_by_ids(&self, topic_ids: Vec<String>) -> Result<Option<Vec<TopicMetadata>>>;
-} 
\ No newline at end of file
+    async fn get_all_topic_metadata(&self) -> Result<Vec<TopicMetadata>>;
+    /// 토픽에 지정된 설정들. 없으면 빈 목록
+    async fn get_topic_configs(&self, topic_name: &str) -> Result<Vec<(String, String)>>;
+    async fn get_cluster_id(&self) -> Result<Option<String>>;
+    /// 토픽이 없으면 새로 만들고, 이미 있으면 기존 메타데이터를 그대로 반환
+    async fn create_topic(&self, topic_name: &str, num_partitions: u32, replicas: Vec<u32>) -> Result<TopicMetadata>;
+    /// 파티션 i 의 replica 목록이 assignments[i] 인 토픽을 만들고 토픽 설정을 함께 기록
+    /// 이미 있으면 DomainError::TopicAlreadyExists
+    async fn create_topic_with_assignments(&self, topic_name: &str, assignments: Vec<Vec<u32>>, configs: &[(String, String)]) -> Result<TopicMetadata>;
+    /// 기존 파티션 뒤에 assignments[i] 를 replica 목록으로 하는 파티션들을 추가하고 늘어난 토픽 메타데이터를 반환
+    /// 토픽이 없으면 DomainError::UnknownTopicOrPartition
+    async fn create_partitions(&self, topic_name: &str, assignments: Vec<Vec<u32>>) -> Result<TopicMetadata>;
+    /// 토픽 삭제를 기록. 이 ID 의 토픽이 없으면 DomainError::UnknownTopicId
+    async fn delete_topic(&self, topic_id: &str) -> Result<()>;
+}